@@ -0,0 +1,86 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Badge with the pass percentage
+
+use std::fs;
+
+/// Label displayed on the left side of the badge.
+const LABEL: &str = "DMN TCK";
+
+/// Approximate width of a single character in pixels (Verdana, 11px).
+const CHAR_WIDTH: usize = 7;
+
+/// Horizontal padding of the badge sections in pixels.
+const PADDING: usize = 10;
+
+/// Writes shields.io style SVG badge with the pass percentage.
+pub fn write(file_name: &str, success_perc: f64) {
+  let message = format!("{:.2}%", success_perc);
+  let label_width = LABEL.len() * CHAR_WIDTH + PADDING;
+  let message_width = message.len() * CHAR_WIDTH + PADDING;
+  let width = label_width + message_width;
+  let label_x = label_width * 10 / 2;
+  let message_x = (label_width + message_width / 2) * 10;
+  let svg = format!(
+    r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{LABEL}: {message}">
+  <title>{LABEL}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="110">
+    <text x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{LABEL}</text>
+    <text x="{label_x}" y="140" transform="scale(.1)">{LABEL}</text>
+    <text x="{message_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{message}</text>
+    <text x="{message_x}" y="140" transform="scale(.1)">{message}</text>
+  </g>
+</svg>
+"##,
+    color = color(success_perc)
+  );
+  fs::write(file_name, svg).unwrap_or_else(|e| panic!("writing badge file {} failed with reason: {}", file_name, e));
+}
+
+/// Returns the badge color for specified pass percentage.
+fn color(success_perc: f64) -> &'static str {
+  match success_perc {
+    p if p >= 95.0 => "#4c1",
+    p if p >= 80.0 => "#97ca00",
+    p if p >= 60.0 => "#dfb317",
+    p if p >= 40.0 => "#fe7d37",
+    _ => "#e05d44",
+  }
+}
@@ -0,0 +1,272 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Statistical comparison of two benchmark runs
+
+use crate::report::quote;
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Significance level below which the difference of latencies is considered significant.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Latency comparison of a single test present in both timing exports.
+struct Delta {
+  /// Test name in form `root/directory/file:test_id`, without root in timing exports written without root column.
+  test: String,
+  /// Median duration in the baseline run in microseconds.
+  baseline_median: f64,
+  /// Median duration in the current run in microseconds.
+  current_median: f64,
+  /// Relative change of the median duration in percents.
+  change: f64,
+  /// Two-sided p-value of Mann-Whitney U test.
+  p_value: f64,
+}
+
+impl Delta {
+  /// Returns `true` when the test is significantly slower in the current run.
+  fn is_regression(&self) -> bool {
+    self.p_value < SIGNIFICANCE_LEVEL && self.current_median > self.baseline_median
+  }
+
+  /// Returns `true` when the test is significantly faster in the current run.
+  fn is_improvement(&self) -> bool {
+    self.p_value < SIGNIFICANCE_LEVEL && self.current_median < self.baseline_median
+  }
+}
+
+/// Compares latencies of tests in two timing exports, written when `timing_file` is configured,
+/// and displays per-test deltas with their significance. Returns `true` when any test is significantly slower.
+pub fn compare(baseline_file: &str, current_file: &str, csv: bool) -> Result<bool, String> {
+  let baseline = read_timings(baseline_file)?;
+  let current = read_timings(current_file)?;
+  let mut deltas = baseline
+    .iter()
+    .filter_map(|(test, baseline_durations)| {
+      let current_durations = current.get(test)?;
+      let baseline_median = median(baseline_durations);
+      let current_median = median(current_durations);
+      Some(Delta {
+        test: test.clone(),
+        baseline_median,
+        current_median,
+        change: if baseline_median > 0.0 {
+          (current_median - baseline_median) * 100.0 / baseline_median
+        } else {
+          0.0
+        },
+        p_value: mann_whitney(baseline_durations, current_durations),
+      })
+    })
+    .collect::<Vec<Delta>>();
+  deltas.sort_by(|lhs, rhs| rhs.change.total_cmp(&lhs.change));
+  let regression_count = deltas.iter().filter(|delta| delta.is_regression()).count();
+  let improvement_count = deltas.iter().filter(|delta| delta.is_improvement()).count();
+  if csv {
+    println!(r#""test","baseline_median_us","current_median_us","change_perc","p_value","verdict""#);
+    for delta in &deltas {
+      println!(
+        r#"{},"{:.0}","{:.0}","{:.2}","{:.4}","{}""#,
+        quote(&delta.test),
+        delta.baseline_median,
+        delta.current_median,
+        delta.change,
+        delta.p_value,
+        verdict(delta)
+      );
+    }
+    return Ok(regression_count > 0);
+  }
+  let width = deltas.iter().map(|delta| delta.test.chars().count()).max().unwrap_or_default().max(4);
+  let line = "─".repeat(width + 2);
+  outln!("\nLatency of {} test(s) present in both runs:", deltas.len());
+  outln!("┌{line}┬───────────────┬──────────────┬───────────┬─────────┬─────────────┐");
+  outln!("│ {:<width$} │ Baseline (µs) │ Current (µs) │    Change │ p-value │ Verdict     │", "Test");
+  outln!("├{line}┼───────────────┼──────────────┼───────────┼─────────┼─────────────┤");
+  for delta in &deltas {
+    let color = if delta.is_regression() {
+      COLOR_RED
+    } else if delta.is_improvement() {
+      COLOR_GREEN
+    } else {
+      COLOR_RESET
+    };
+    outln!(
+      "│ {2:<width$} │ {3:>13.0} │ {4:>12.0} │ {1}{5:>+8.2}%{0} │ {6:>7.4} │ {1}{7:<11}{0} │",
+      COLOR_RESET,
+      color,
+      delta.test,
+      delta.baseline_median,
+      delta.current_median,
+      delta.change,
+      delta.p_value,
+      verdict(delta)
+    );
+  }
+  outln!("└{line}┴───────────────┴──────────────┴───────────┴─────────┴─────────────┘");
+  outln!(
+    "Significantly slower: {1}{2}{0}, significantly faster: {3}{4}{0}",
+    COLOR_RESET,
+    if regression_count > 0 { COLOR_RED } else { COLOR_GREEN },
+    regression_count,
+    COLOR_GREEN,
+    improvement_count
+  );
+  Ok(regression_count > 0)
+}
+
+/// Returns the verdict about the latency change of the test.
+fn verdict(delta: &Delta) -> &'static str {
+  if delta.is_regression() {
+    "slower"
+  } else if delta.is_improvement() {
+    "faster"
+  } else {
+    "unchanged"
+  }
+}
+
+/// Reads durations in microseconds from timing export, indexed by test name.
+/// Timing exports written by earlier versions have no root column, tests are then identified without root.
+fn read_timings(file_name: &str) -> Result<BTreeMap<String, Vec<f64>>, String> {
+  let content = fs::read_to_string(file_name).map_err(|reason| format!("reading timing file '{}' failed with reason: {}", file_name, reason))?;
+  let mut timings = BTreeMap::<String, Vec<f64>>::new();
+  let mut lines = content.lines().enumerate();
+  // the first line is the header
+  let with_root = lines.next().is_some_and(|(_, header)| csv_fields(header).first().is_some_and(|field| field == "root"));
+  for (index, line) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+    let mut fields = csv_fields(line);
+    let root = if with_root && !fields.is_empty() { Some(fields.remove(0)) } else { None };
+    let [directory, file, test_id, duration, ..] = fields.as_slice() else {
+      return Err(format!("{}:{}: expected at least {} fields", file_name, index + 1, if with_root { 5 } else { 4 }));
+    };
+    let duration = duration
+      .parse::<f64>()
+      .map_err(|_| format!("{}:{}: invalid duration '{}'", file_name, index + 1, duration))?;
+    let test = match root.filter(|root| !root.is_empty()) {
+      Some(root) => format!("{}/{}/{}:{}", root.trim_end_matches('/'), directory, file, test_id),
+      None => format!("{}/{}:{}", directory, file, test_id),
+    };
+    timings.entry(test).or_default().push(duration);
+  }
+  Ok(timings)
+}
+
+/// Splits the CSV line into fields, removing quotes around quoted fields.
+fn csv_fields(line: &str) -> Vec<String> {
+  let mut fields = vec![];
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(ch) = chars.next() {
+    match ch {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' => quoted = !quoted,
+      ',' if !quoted => fields.push(std::mem::take(&mut field)),
+      _ => field.push(ch),
+    }
+  }
+  fields.push(field);
+  fields
+}
+
+/// Returns the median of the samples.
+fn median(samples: &[f64]) -> f64 {
+  let mut sorted = samples.to_vec();
+  sorted.sort_by(f64::total_cmp);
+  let middle = sorted.len() / 2;
+  if sorted.is_empty() {
+    0.0
+  } else if sorted.len() % 2 == 1 {
+    sorted[middle]
+  } else {
+    (sorted[middle - 1] + sorted[middle]) / 2.0
+  }
+}
+
+/// Returns the two-sided p-value of Mann-Whitney U test, using normal approximation
+/// with tie correction and continuity correction. Returns 1 when the samples can not be distinguished.
+fn mann_whitney(lhs: &[f64], rhs: &[f64]) -> f64 {
+  let (n1, n2) = (lhs.len() as f64, rhs.len() as f64);
+  let mut samples = lhs
+    .iter()
+    .map(|value| (*value, true))
+    .chain(rhs.iter().map(|value| (*value, false)))
+    .collect::<Vec<(f64, bool)>>();
+  samples.sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+  let n = samples.len() as f64;
+  let mut lhs_rank_sum = 0.0;
+  let mut tie_correction = 0.0;
+  let mut start = 0;
+  while start < samples.len() {
+    let end = samples[start..]
+      .iter()
+      .position(|(value, _)| *value != samples[start].0)
+      .map_or(samples.len(), |offset| start + offset);
+    // tied samples get the average of their ranks, ranks are counted from 1
+    let rank = (start + end + 1) as f64 / 2.0;
+    lhs_rank_sum += rank * samples[start..end].iter().filter(|(_, is_lhs)| *is_lhs).count() as f64;
+    let tied = (end - start) as f64;
+    tie_correction += tied * tied * tied - tied;
+    start = end;
+  }
+  let u = lhs_rank_sum - n1 * (n1 + 1.0) / 2.0;
+  let mean = n1 * n2 / 2.0;
+  let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+  if variance.is_nan() || variance <= 0.0 {
+    return 1.0;
+  }
+  let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+  (2.0 * (1.0 - normal_cdf(z))).clamp(0.0, 1.0)
+}
+
+/// Returns the cumulative distribution function of standard normal distribution.
+fn normal_cdf(x: f64) -> f64 {
+  (1.0 + erf(x / std::f64::consts::SQRT_2)) / 2.0
+}
+
+/// Returns the error function, approximated with maximal error 1.5e-7 (Abramowitz and Stegun, 7.1.26).
+fn erf(x: f64) -> f64 {
+  let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+  let polynomial = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+  let value = 1.0 - polynomial * (-x * x).exp();
+  if x < 0.0 {
+    -value
+  } else {
+    value
+  }
+}
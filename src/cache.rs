@@ -0,0 +1,114 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Cache of content hashes of test files and models, with results of their tests
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Hash and results of the last run of a test file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+  /// Hash of the test file content and contents of all models it depends on.
+  pub hash: String,
+  /// Number of passed tests.
+  pub success: usize,
+  /// Number of failed tests.
+  pub failure: usize,
+  /// Paths of models the test file depends on, included in the hash.
+  #[serde(default)]
+  pub dependencies: Vec<String>,
+}
+
+/// Cache of test files stored in JSON file, indexed by test file path.
+pub struct Cache {
+  /// Path to cache file.
+  file_name: String,
+  /// Cached test files.
+  files: BTreeMap<String, CachedFile>,
+}
+
+impl Cache {
+  /// Loads the cache from file, the cache is empty when the file does not exist or can not be read.
+  pub fn load(file_name: &str) -> Self {
+    let files = fs::read_to_string(file_name)
+      .ok()
+      .and_then(|content| serde_json::from_str(&content).ok())
+      .unwrap_or_default();
+    Self {
+      file_name: file_name.to_string(),
+      files,
+    }
+  }
+
+  /// Returns the cached results of the test file, when the contents of the test file
+  /// and all models it depended on in the cached run did not change since the cached run.
+  pub fn unchanged(&self, file_path: &str) -> Option<&CachedFile> {
+    self
+      .files
+      .get(file_path)
+      .filter(|cached_file| cached_file.hash == hash_files(file_path, &cached_file.dependencies))
+  }
+
+  /// Stores the hash, dependencies and results of the test file.
+  pub fn update(&mut self, file_path: &str, hash: String, dependencies: Vec<String>, success: usize, failure: usize) {
+    self.files.insert(
+      file_path.to_string(),
+      CachedFile {
+        hash,
+        success,
+        failure,
+        dependencies,
+      },
+    );
+  }
+
+  /// Writes the cache to file.
+  pub fn save(&self) {
+    let content = serde_json::to_string_pretty(&self.files).unwrap_or_else(|e| panic!("serializing cache failed with reason: {}", e));
+    fs::write(&self.file_name, content).unwrap_or_else(|e| panic!("writing cache file '{}' failed with reason: {}", self.file_name, e));
+  }
+}
+
+/// Returns the hash (FNV-1a) of contents of the test file and all models it depends on, as hexadecimal string.
+/// Files that can not be read contribute only with their paths.
+pub fn hash_files(file_path: &str, dependencies: &[String]) -> String {
+  let mut hash = 0xCBF2_9CE4_8422_2325_u64;
+  for file_path in [file_path].into_iter().chain(dependencies.iter().map(String::as_str)) {
+    for byte in file_path.bytes().chain(fs::read(file_path).unwrap_or_default()) {
+      hash ^= byte as u64;
+      hash = hash.wrapping_mul(0x0100_0000_01B3);
+    }
+  }
+  format!("{:016x}", hash)
+}
@@ -0,0 +1,218 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Canonicalization of values before comparison
+
+use crate::compare::{
+  canonical_seconds, offset_minutes, round_decimal, CanonicalDuration, DurationCategory, TYPE_DATE_TIME, TYPE_DECIMAL, TYPE_DOUBLE, TYPE_DURATIONS, TYPE_STRING, TYPE_TIME,
+};
+use crate::config::{CanonicalizationStep, ConfigurationParams, RoundingMode, UnicodeNormalization};
+use crate::dto::{SimpleDto, ValueDto};
+use crate::model::canonical_type_name;
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization as _;
+
+/// Number of nanoseconds in a second.
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+/// Number of minutes in a day.
+const MINUTES_PER_DAY: i64 = 1440;
+
+/// Ordered normalization steps applied to both expected and actual values before comparison,
+/// preceded by Unicode normalization of strings.
+pub struct Canonicalization {
+  /// Unicode normalization form of strings.
+  unicode_normalization: UnicodeNormalization,
+  /// Steps applied in order.
+  steps: Vec<CanonicalizationStep>,
+  /// Rounding mode used when rounding decimals.
+  rounding: RoundingMode,
+  /// Parser of durations.
+  durations: CanonicalDuration,
+  /// Pattern of date and time values with time offset.
+  date_time_pattern: Regex,
+  /// Pattern of time values with time offset.
+  time_pattern: Regex,
+}
+
+impl Canonicalization {
+  /// Creates canonicalization with steps selected in configuration.
+  pub fn new(config: &ConfigurationParams) -> Self {
+    Self {
+      unicode_normalization: config.unicode_normalization,
+      steps: config.canonicalization.clone(),
+      rounding: config.rounding,
+      durations: CanonicalDuration::new(),
+      date_time_pattern: Regex::new(r"^(-?\d{4,})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2}(?:\.\d+)?)(Z|[+-]\d{2}:\d{2})$").unwrap(),
+      time_pattern: Regex::new(r"^(\d{2}):(\d{2}):(\d{2}(?:\.\d+)?)(Z|[+-]\d{2}:\d{2})$").unwrap(),
+    }
+  }
+
+  /// Returns `true` when neither Unicode normalization nor steps are configured.
+  pub fn is_empty(&self) -> bool {
+    self.unicode_normalization == UnicodeNormalization::None && self.steps.is_empty()
+  }
+
+  /// Applies Unicode normalization and all steps in order to the value, including nested components and list items.
+  pub fn apply(&self, value: &mut ValueDto) {
+    self.apply_to_simple_values(value, &|simple| self.normalized_string(simple));
+    for step in &self.steps {
+      self.apply_to_simple_values(value, &|simple| self.canonical_text(step, simple));
+    }
+  }
+
+  /// Replaces the texts of simple values, including nested components and list items,
+  /// with texts returned by `canonical_text`, unless it returns `None`.
+  fn apply_to_simple_values(&self, value: &mut ValueDto, canonical_text: &dyn Fn(&SimpleDto) -> Option<String>) {
+    if let Some(simple) = &mut value.simple {
+      if let Some(text) = canonical_text(simple) {
+        simple.text = Some(text);
+      }
+    }
+    for component in value.components.iter_mut().flatten() {
+      if let Some(component_value) = &mut component.value {
+        self.apply_to_simple_values(component_value, canonical_text);
+      }
+    }
+    for item in value.list.iter_mut().flat_map(|list| list.items.iter_mut()) {
+      self.apply_to_simple_values(item, canonical_text);
+    }
+  }
+
+  /// Returns the text of string value in configured Unicode normalization form.
+  fn normalized_string(&self, simple: &SimpleDto) -> Option<String> {
+    if canonical_type_name(simple.typ.as_deref()?) != TYPE_STRING {
+      return None;
+    }
+    let text = simple.text.as_deref()?;
+    match self.unicode_normalization {
+      UnicodeNormalization::None => None,
+      UnicodeNormalization::Nfc => Some(text.nfc().collect()),
+      UnicodeNormalization::Nfkc => Some(text.nfkc().collect()),
+    }
+  }
+
+  /// Returns the text of simple value after applying the step, or `None` when the step does not apply to the value.
+  fn canonical_text(&self, step: &CanonicalizationStep, simple: &SimpleDto) -> Option<String> {
+    let typ = canonical_type_name(simple.typ.as_deref()?);
+    let text = simple.text.as_deref()?;
+    match step {
+      CanonicalizationStep::TrimStrings if typ == TYPE_STRING => Some(text.trim().to_string()),
+      CanonicalizationStep::CanonicalDurations if TYPE_DURATIONS.contains(&typ.as_str()) => self.canonical_duration(text),
+      CanonicalizationStep::NormalizeTimezone if typ == TYPE_DATE_TIME => self.utc_date_time(text),
+      CanonicalizationStep::NormalizeTimezone if typ == TYPE_TIME => self.utc_time(text),
+      CanonicalizationStep::RoundDecimals { digits } if typ == TYPE_DECIMAL || typ == TYPE_DOUBLE => round_decimal(text, *digits, self.rounding),
+      _ => None,
+    }
+  }
+
+  /// Returns the duration in canonical form, with the largest possible units and without zero units.
+  fn canonical_duration(&self, text: &str) -> Option<String> {
+    let (months, nanos) = self.durations.parse(text)?;
+    if months == 0 && nanos == 0 {
+      return Some(
+        if DurationCategory::of(text) == Some(DurationCategory::YearMonth) {
+          "P0M"
+        } else {
+          "PT0S"
+        }
+        .to_string(),
+      );
+    }
+    let mut duration = if months < 0 || nanos < 0 { "-P".to_string() } else { "P".to_string() };
+    let (months, nanos) = (months.abs(), nanos.abs());
+    let seconds = nanos / NANOS_PER_SECOND;
+    let fraction = nanos % NANOS_PER_SECOND;
+    for (amount, designator) in [(months / 12, 'Y'), (months % 12, 'M'), (seconds / 86400, 'D')] {
+      if amount > 0 {
+        duration.push_str(&format!("{}{}", amount, designator));
+      }
+    }
+    if seconds % 86400 > 0 || fraction > 0 {
+      duration.push('T');
+      for (amount, designator) in [(seconds % 86400 / 3600, 'H'), (seconds % 3600 / 60, 'M')] {
+        if amount > 0 {
+          duration.push_str(&format!("{}{}", amount, designator));
+        }
+      }
+      if fraction > 0 {
+        duration.push_str(&format!("{}.{}S", seconds % 60, format!("{:09}", fraction).trim_end_matches('0')));
+      } else if seconds % 60 > 0 {
+        duration.push_str(&format!("{}S", seconds % 60));
+      }
+    }
+    Some(duration)
+  }
+
+  /// Returns the date and time with time offset converted to UTC.
+  fn utc_date_time(&self, text: &str) -> Option<String> {
+    let captures = self.date_time_pattern.captures(text.trim())?;
+    let number = |index: usize| captures[index].parse::<i64>().ok();
+    let minutes = number(4)? * 60 + number(5)? - offset_minutes(&captures[7])?;
+    let days = days_from_civil(number(1)?, number(2)?, number(3)?) + minutes.div_euclid(MINUTES_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let minutes = minutes.rem_euclid(MINUTES_PER_DAY);
+    let year = if year < 0 { format!("-{:04}", -year) } else { format!("{:04}", year) };
+    let seconds = canonical_seconds(&captures[6]);
+    Some(format!("{}-{:02}-{:02}T{:02}:{:02}:{}Z", year, month, day, minutes / 60, minutes % 60, seconds))
+  }
+
+  /// Returns the time with time offset converted to UTC.
+  fn utc_time(&self, text: &str) -> Option<String> {
+    let captures = self.time_pattern.captures(text.trim())?;
+    let number = |index: usize| captures[index].parse::<i64>().ok();
+    let minutes = (number(1)? * 60 + number(2)? - offset_minutes(&captures[4])?).rem_euclid(MINUTES_PER_DAY);
+    Some(format!("{:02}:{:02}:{}Z", minutes / 60, minutes % 60, canonical_seconds(&captures[3])))
+  }
+}
+
+/// Returns the number of days since 1970-01-01 of the date in proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146097 + day_of_era - 719468
+}
+
+/// Returns the date in proleptic Gregorian calendar of the number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let day_of_era = days - era * 146097;
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
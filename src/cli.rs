@@ -0,0 +1,319 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Command line arguments
+
+use crate::config::{EventsFormat, DEFAULT_CONFIG_FILE};
+
+/// Default number of runs included in trend report.
+const DEFAULT_LAST_RUNS: usize = 10;
+
+/// Commands supported by the runner.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+  /// Runs configured tests.
+  Run,
+  /// Displays the trend of the last runs stored in history database.
+  ReportTrend,
+  /// Generates the skeleton of the test file for DMN model.
+  Generate {
+    /// Path to DMN model file.
+    model_file: String,
+    /// Optional path to generated test file.
+    output_file: Option<String>,
+  },
+  /// Compares latencies of tests in two timing exports.
+  BenchCompare {
+    /// Path to timing file of the baseline run.
+    baseline_file: String,
+    /// Path to timing file of the current run.
+    current_file: String,
+  },
+  /// Runs tests repeatedly on commands read from standard input.
+  Daemon,
+  /// Replays requests of all tests at increasing concurrency levels.
+  Load,
+  /// Measures the time from engine readiness to the first successful evaluation of every model.
+  ColdStart,
+  /// Replays requests of all tests continuously for a long time, tracking the drift of latency and error rate.
+  Soak,
+  /// Lists discovered directories, models, test files and labels without executing tests.
+  List,
+  /// Checks the configuration file, reporting all invalid parameters.
+  ConfigCheck,
+  /// Generates the commented configuration file with default parameters.
+  Init {
+    /// Path to generated configuration file.
+    output_file: String,
+  },
+  /// Checks test files for common problems.
+  Lint {
+    /// Optional path to test file or directory, test cases directory from configuration is used when not given.
+    path: Option<String>,
+  },
+}
+
+/// Parsed command line arguments.
+#[derive(Debug)]
+pub struct Arguments {
+  /// Command to be executed.
+  pub command: Command,
+  /// Flag indicating if usage message should be displayed instead of executing the command.
+  pub help: bool,
+  /// Name of the configuration file, searched in standard locations when not given.
+  pub config_file: Option<String>,
+  /// Name of the configuration profile overriding parameters from the base section.
+  pub profile: Option<String>,
+  /// Number of last runs included in trend report.
+  pub last_runs: usize,
+  /// Flag indicating if reports should be printed in CSV format.
+  pub csv: bool,
+  /// Flag indicating if listing should be printed in JSON format.
+  pub json: bool,
+  /// Number of executions of each test, overrides the value from configuration.
+  pub repeat: Option<usize>,
+  /// Number of failed tests after which testing is stopped, overrides the value from configuration.
+  pub max_failures: Option<usize>,
+  /// Expected number of executed test cases, overrides the value from configuration.
+  pub expected_tests: Option<usize>,
+  /// Flag indicating if computed values should be written back into test files.
+  pub write_computed: bool,
+  /// Flag enabling strict validation of test files.
+  pub strict: bool,
+  /// Flag indicating if problems found by linter should be fixed.
+  pub fix: bool,
+  /// Flag indicating if tests are executed in shuffled order.
+  pub shuffle: bool,
+  /// Flag indicating if only tests from test files changed since the cached run are executed.
+  pub changed_only: bool,
+  /// Flag indicating if only tests that have failed in the last run are executed.
+  pub only_failed: bool,
+  /// Flag indicating if interactive terminal interface is displayed.
+  pub tui: bool,
+  /// Flag indicating if console output is limited to plain ASCII, without box-drawing characters and colors.
+  pub ascii: bool,
+  /// Flag indicating if curl commands reproducing failed evaluations are printed.
+  pub curl: bool,
+  /// Format of the stream of progress events, overrides the value from configuration.
+  pub events_format: Option<EventsFormat>,
+  /// Seed of the shuffled order, overrides the value from configuration.
+  pub seed: Option<usize>,
+  /// Flag indicating if result nodes without expected value are executed as smoke tests.
+  pub smoke: bool,
+  /// Flag indicating if strings are compared case-insensitively.
+  pub ignore_case: bool,
+  /// Flag indicating if leading and trailing whitespace of strings is ignored in comparisons.
+  pub trim_strings: bool,
+  /// Compliance levels of executed tests, overrides the value from configuration.
+  pub levels: Vec<u8>,
+  /// Metadata of the run as key and value pairs, added to metadata from configuration.
+  pub run_metadata: Vec<(String, String)>,
+  /// Pattern for selecting test cases by name, overrides the value from configuration.
+  pub test_case_name: Option<String>,
+  /// Maximal number of concurrent workers in load test, or number of workers in soak test,
+  /// overrides the value from configuration.
+  pub load_workers: Option<usize>,
+  /// Duration of each concurrency level of load test, duration of adaptive load test,
+  /// or duration of soak test, in seconds. Overrides the value from configuration.
+  pub load_duration: Option<usize>,
+  /// Target latency of adaptive load test in milliseconds, overrides the value from configuration.
+  pub load_target_latency: Option<usize>,
+}
+
+impl Default for Arguments {
+  fn default() -> Self {
+    Self {
+      command: Command::Run,
+      help: false,
+      config_file: None,
+      profile: None,
+      last_runs: DEFAULT_LAST_RUNS,
+      csv: false,
+      json: false,
+      repeat: None,
+      max_failures: None,
+      expected_tests: None,
+      write_computed: false,
+      strict: false,
+      fix: false,
+      shuffle: false,
+      changed_only: false,
+      only_failed: false,
+      tui: false,
+      ascii: false,
+      curl: false,
+      events_format: None,
+      seed: None,
+      smoke: false,
+      ignore_case: false,
+      trim_strings: false,
+      levels: vec![],
+      run_metadata: vec![],
+      test_case_name: None,
+      load_workers: None,
+      load_duration: None,
+      load_target_latency: None,
+    }
+  }
+}
+
+/// Parses command line arguments.
+pub fn parse() -> Result<Arguments, String> {
+  parse_args(std::env::args().skip(1))
+}
+
+/// Parses arguments given as an iterator (without the program name).
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Arguments, String> {
+  let mut arguments = Arguments::default();
+  let mut positional = vec![];
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--help" | "-h" => arguments.help = true,
+      "--last" => arguments.last_runs = required_number(&arg, args.next())?,
+      "--csv" => arguments.csv = true,
+      "--json" => arguments.json = true,
+      "--repeat" => arguments.repeat = Some(required_number(&arg, args.next())?),
+      "--max-failures" => arguments.max_failures = Some(required_number(&arg, args.next())?),
+      "--expect-tests" => arguments.expected_tests = Some(required_number(&arg, args.next())?),
+      "--write-computed" => arguments.write_computed = true,
+      "--strict" => arguments.strict = true,
+      "--fix" => arguments.fix = true,
+      "--smoke" => arguments.smoke = true,
+      "--shuffle" => arguments.shuffle = true,
+      "--changed-only" => arguments.changed_only = true,
+      "--only-failed" => arguments.only_failed = true,
+      "--tui" => arguments.tui = true,
+      "--ascii" => arguments.ascii = true,
+      "--curl" => arguments.curl = true,
+      "--events-format" => arguments.events_format = Some(required_events_format(&arg, args.next())?),
+      "--seed" => arguments.seed = Some(required_number(&arg, args.next())?),
+      "--ignore-case" => arguments.ignore_case = true,
+      "--trim-strings" => arguments.trim_strings = true,
+      "--workers" => arguments.load_workers = Some(required_number(&arg, args.next())?),
+      "--duration" => arguments.load_duration = Some(required_number(&arg, args.next())?),
+      "--target-latency" => arguments.load_target_latency = Some(required_number(&arg, args.next())?),
+      "--level" => arguments.levels.push(required_level(&arg, args.next())?),
+      "--meta" => arguments.run_metadata.push(required_key_value(&arg, args.next())?),
+      "--profile" => arguments.profile = Some(args.next().ok_or(format!("missing value for option '{}'", arg))?),
+      "--name" => arguments.test_case_name = Some(args.next().ok_or(format!("missing value for option '{}'", arg))?),
+      other if other.starts_with("--") => return Err(format!("unknown option '{}'", other)),
+      _ => positional.push(arg),
+    }
+  }
+  let mut positional = positional.into_iter();
+  match positional.next() {
+    Some(word) if word == "report" => match positional.next().as_deref() {
+      Some("trend") => arguments.command = Command::ReportTrend,
+      Some(other) => return Err(format!("unknown report '{}'", other)),
+      None => return Err("missing report name".to_string()),
+    },
+    Some(word) if word == "bench" => match positional.next().as_deref() {
+      Some("compare") => {
+        let baseline_file = positional.next().ok_or("missing baseline timing file name")?;
+        let current_file = positional.next().ok_or("missing current timing file name")?;
+        arguments.command = Command::BenchCompare { baseline_file, current_file };
+      }
+      Some(other) => return Err(format!("unknown bench command '{}'", other)),
+      None => return Err("missing bench command".to_string()),
+    },
+    Some(word) if word == "config" => match positional.next().as_deref() {
+      Some("check") => arguments.command = Command::ConfigCheck,
+      Some(other) => return Err(format!("unknown config command '{}'", other)),
+      None => return Err("missing config command".to_string()),
+    },
+    Some(word) if word == "daemon" => arguments.command = Command::Daemon,
+    Some(word) if word == "load" => arguments.command = Command::Load,
+    Some(word) if word == "soak" => arguments.command = Command::Soak,
+    Some(word) if word == "cold-start" => arguments.command = Command::ColdStart,
+    Some(word) if word == "list" => arguments.command = Command::List,
+    Some(word) if word == "lint" => arguments.command = Command::Lint { path: positional.next() },
+    Some(word) if word == "generate" => {
+      let model_file = positional.next().ok_or("missing model file name")?;
+      arguments.command = Command::Generate {
+        model_file,
+        output_file: positional.next(),
+      };
+    }
+    Some(word) if word == "init" => {
+      arguments.command = Command::Init {
+        output_file: positional.next().unwrap_or(DEFAULT_CONFIG_FILE.to_string()),
+      }
+    }
+    Some(config_file) => arguments.config_file = Some(config_file),
+    None => {}
+  }
+  if let Some(config_file) = positional.next() {
+    if matches!(
+      arguments.command,
+      Command::Run | Command::Generate { .. } | Command::BenchCompare { .. } | Command::Init { .. }
+    ) {
+      return Err(format!("unexpected argument '{}'", config_file));
+    }
+    arguments.config_file = Some(config_file);
+  }
+  if let Some(unexpected) = positional.next() {
+    return Err(format!("unexpected argument '{}'", unexpected));
+  }
+  Ok(arguments)
+}
+
+/// Returns the numeric value of the option.
+fn required_number(option: &str, value: Option<String>) -> Result<usize, String> {
+  let value = value.ok_or(format!("missing value for option '{}'", option))?;
+  value.parse::<usize>().map_err(|_| format!("invalid value '{}' for option '{}'", value, option))
+}
+
+/// Returns the key and value given as option value in form `KEY=VALUE`.
+fn required_key_value(option: &str, value: Option<String>) -> Result<(String, String), String> {
+  let value = value.ok_or(format!("missing value for option '{}'", option))?;
+  match value.split_once('=') {
+    Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+    _ => Err(format!("invalid value '{}' for option '{}', expected KEY=VALUE", value, option)),
+  }
+}
+
+/// Returns the format of the stream of events given as option value.
+fn required_events_format(option: &str, value: Option<String>) -> Result<EventsFormat, String> {
+  match value.ok_or(format!("missing value for option '{}'", option))?.as_str() {
+    "jsonl" => Ok(EventsFormat::Jsonl),
+    "none" => Ok(EventsFormat::None),
+    other => Err(format!("invalid events format '{}', expected jsonl or none", other)),
+  }
+}
+
+/// Returns the compliance level given as option value.
+fn required_level(option: &str, value: Option<String>) -> Result<u8, String> {
+  match required_number(option, value)? {
+    level @ (2 | 3) => Ok(level as u8),
+    level => Err(format!("invalid compliance level '{}', expected 2 or 3", level)),
+  }
+}
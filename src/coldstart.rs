@@ -0,0 +1,128 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Measurement of engine cold start
+
+use crate::load;
+use crate::params::EvaluateParams;
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET};
+use reqwest::blocking::Client;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use std::{process, thread};
+
+/// Default maximal time in seconds of waiting for engine readiness and for the first successful evaluation.
+pub const DEFAULT_COLD_START_TIMEOUT: u64 = 300;
+
+/// Default interval in milliseconds between health checks and between evaluation attempts.
+pub const DEFAULT_POLL_INTERVAL: u64 = 100;
+
+/// Evaluation request of a single model used for measuring the cold start.
+pub struct ModelRequest {
+  /// Name of the workspace the model is deployed in.
+  pub workspace: String,
+  /// Path of the model file.
+  pub model_path: String,
+  /// Parameters of the evaluation request.
+  pub params: EvaluateParams,
+}
+
+/// Waits until the health check of engine passes, then sends the evaluation request of every model
+/// until it succeeds, and displays the time from engine readiness to the first successful evaluation
+/// of models, per workspace. Exits with failure when the engine is not ready before timeout.
+pub fn run(client: &Client, health_url: &str, evaluate_url: &str, requests: &[ModelRequest], timeout: Duration, poll_interval: Duration) {
+  out!("Waiting for engine readiness: {} ... ", health_url);
+  let start_time = Instant::now();
+  while !is_healthy(client, health_url) {
+    if start_time.elapsed() > timeout {
+      outln!("{1}timeout after {2} s{0}", COLOR_RESET, COLOR_RED, timeout.as_secs());
+      process::exit(1);
+    }
+    thread::sleep(poll_interval);
+  }
+  let ready_time = Instant::now();
+  outln!("{1}ready after {2:.3} s{0}", COLOR_RESET, COLOR_GREEN, start_time.elapsed().as_secs_f64());
+  // times of the first successful evaluation since readiness, `None` for models never evaluated successfully
+  let mut workspaces = BTreeMap::<&str, Vec<Option<Duration>>>::new();
+  for request in requests {
+    out!("  {} ... ", request.model_path);
+    let mut attempts = 1;
+    // `load::send` returns `true` when the evaluation has failed
+    let evaluated = loop {
+      if !load::send(client, evaluate_url, &request.params) {
+        break true;
+      }
+      if ready_time.elapsed() > timeout {
+        break false;
+      }
+      thread::sleep(poll_interval);
+      attempts += 1;
+    };
+    let since_ready = ready_time.elapsed();
+    if evaluated {
+      outln!("{1}{2:.3} s{0}, {3} attempt(s)", COLOR_RESET, COLOR_GREEN, since_ready.as_secs_f64(), attempts);
+    } else {
+      outln!("{1}no successful evaluation{0}, {2} attempt(s)", COLOR_RESET, COLOR_RED, attempts);
+    }
+    workspaces.entry(&request.workspace).or_default().push(evaluated.then_some(since_ready));
+  }
+  display_results(&workspaces);
+}
+
+/// Returns `true` when the health check of engine passes.
+fn is_healthy(client: &Client, health_url: &str) -> bool {
+  client.get(health_url).send().is_ok_and(|response| response.status().is_success())
+}
+
+/// Displays the times from engine readiness to the first successful evaluation of models, per workspace.
+fn display_results(workspaces: &BTreeMap<&str, Vec<Option<Duration>>>) {
+  let width = workspaces.keys().map(|workspace| workspace.chars().count()).max().unwrap_or_default().max(9);
+  let line = "─".repeat(width + 2);
+  outln!("\nCold start (time since engine readiness to the first successful evaluation):");
+  outln!("┌{line}┬────────┬────────┬────────────┬────────────┐");
+  outln!("│ {:<width$} │ Models │ Failed │  First (s) │   Last (s) │", "Workspace");
+  outln!("├{line}┼────────┼────────┼────────────┼────────────┤");
+  for (workspace, times) in workspaces {
+    let workspace = if workspace.is_empty() { "(root)" } else { workspace };
+    let evaluated = times.iter().flatten().collect::<Vec<&Duration>>();
+    let failed = times.len() - evaluated.len();
+    let first = evaluated.iter().min().map_or("-".to_string(), |time| format!("{:.3}", time.as_secs_f64()));
+    let last = evaluated.iter().max().map_or("-".to_string(), |time| format!("{:.3}", time.as_secs_f64()));
+    let models = times.len();
+    outln!(
+      "│ {workspace:<width$} │ {models:>6} │ {1}{failed:>6}{0} │ {first:>10} │ {last:>10} │",
+      COLOR_RESET,
+      if failed > 0 { COLOR_RED } else { COLOR_GREEN }
+    );
+  }
+  outln!("└{line}┴────────┴────────┴────────────┴────────────┘");
+}
@@ -0,0 +1,1263 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Comparison of actual and expected values
+
+use crate::config::{ComparatorConfig, ConfigurationParams, RoundingMode};
+use crate::dto::{ComponentDto, FunctionDto, ListDto, SimpleDto, ValueDto};
+use crate::model::{canonical_type_name, ExtensionElement};
+use crate::plugin::WasmComparator;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use globset::Glob;
+use regex::Regex;
+use std::fmt;
+use url::Url;
+
+/// Type of string values.
+pub const TYPE_STRING: &str = "xsd:string";
+/// Type of decimal values.
+pub const TYPE_DECIMAL: &str = "xsd:decimal";
+/// Type of double values.
+pub const TYPE_DOUBLE: &str = "xsd:double";
+/// Types of duration values.
+pub const TYPE_DURATIONS: [&str; 3] = ["xsd:duration", "xsd:dayTimeDuration", "xsd:yearMonthDuration"];
+/// Type of date and time values.
+pub const TYPE_DATE_TIME: &str = "xsd:dateTime";
+/// Type of time values.
+pub const TYPE_TIME: &str = "xsd:time";
+/// Type of binary values encoded in Base64.
+pub const TYPE_BASE64_BINARY: &str = "xsd:base64Binary";
+/// Type of URI values.
+pub const TYPE_ANY_URI: &str = "xsd:anyURI";
+/// Type of integer values.
+pub const TYPE_INTEGER: &str = "xsd:integer";
+
+/// Categories of durations, distinguished by TCK even when both are of type `xsd:duration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationCategory {
+  /// Duration containing only years and months, like `P1Y2M`.
+  YearMonth,
+  /// Duration containing only days, hours, minutes and seconds, like `P1DT2H`.
+  DayTime,
+}
+
+impl fmt::Display for DurationCategory {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::YearMonth => write!(f, "year-month"),
+      Self::DayTime => write!(f, "day-time"),
+    }
+  }
+}
+
+impl DurationCategory {
+  /// Returns the category of the duration in ISO 8601 format,
+  /// or `None` when the duration mixes both categories or is not a duration.
+  pub fn of(text: &str) -> Option<Self> {
+    let text = text.trim();
+    let designators = text.strip_prefix('-').unwrap_or(text).strip_prefix('P')?;
+    let (date, time) = designators.split_once('T').unwrap_or((designators, ""));
+    let year_month = date.contains('Y') || date.contains('M');
+    let day_time = date.contains('D') || !time.is_empty();
+    match (year_month, day_time) {
+      (true, false) => Some(Self::YearMonth),
+      (false, true) => Some(Self::DayTime),
+      _ => None,
+    }
+  }
+}
+
+/// Class of the difference between actual and expected value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MismatchClass {
+  /// Values of different kind or type.
+  TypeMismatch,
+  /// Numbers differing only at a distant fractional digit.
+  NumericPrecision,
+  /// Nil value where a value was expected or vice versa.
+  Nil,
+  /// Expected component not present in actual value.
+  MissingComponent,
+  /// Actual component not present in expected value.
+  ExtraComponent,
+  /// Components in different order.
+  ComponentOrder,
+  /// Lists of different length.
+  ListLength,
+  /// Lists with different items.
+  ListItems,
+  /// Values of the same type with different content.
+  Value,
+  /// Comparator failed to compare values.
+  ComparisonError,
+}
+
+impl fmt::Display for MismatchClass {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::TypeMismatch => write!(f, "type_mismatch"),
+      Self::NumericPrecision => write!(f, "numeric_precision"),
+      Self::Nil => write!(f, "nil"),
+      Self::MissingComponent => write!(f, "missing_component"),
+      Self::ExtraComponent => write!(f, "extra_component"),
+      Self::ComponentOrder => write!(f, "component_order"),
+      Self::ListLength => write!(f, "list_length"),
+      Self::ListItems => write!(f, "list_items"),
+      Self::Value => write!(f, "value"),
+      Self::ComparisonError => write!(f, "comparison_error"),
+    }
+  }
+}
+
+/// Difference between actual and expected value.
+pub struct Mismatch {
+  /// Class of the difference.
+  pub class: MismatchClass,
+  /// Description of the difference, in form `path: actual != expected`.
+  pub description: String,
+}
+
+impl fmt::Display for Mismatch {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.description)
+  }
+}
+
+impl Mismatch {
+  /// Creates a difference of specified class.
+  pub fn new(class: MismatchClass, description: String) -> Self {
+    Self { class, description }
+  }
+}
+
+/// Policy of comparing actual values returned by engine with expected values.
+///
+/// A comparator decides only about the values it is specialized in,
+/// for other values it returns `None` and the decision is left to the next comparator.
+pub trait Comparator {
+  /// Compares whole values, returns `None` when the comparator does not decide about the values.
+  fn compare_values(&self, _actual: &ValueDto, _expected: &ValueDto) -> Option<bool> {
+    None
+  }
+
+  /// Returns the difference between whole values found different by `compare_values`,
+  /// `None` when the comparator does not describe the difference.
+  fn values_mismatch(&self, _actual: &ValueDto, _expected: &ValueDto) -> Option<Mismatch> {
+    None
+  }
+
+  /// Compares simple values, returns `None` when the comparator does not decide about the values.
+  fn compare_simple(&self, _actual: &SimpleDto, _expected: &SimpleDto) -> Option<bool> {
+    None
+  }
+
+  /// Compares lists, items are compared with `compare_items`.
+  /// Returns `None` when the comparator does not decide about the lists.
+  fn compare_lists(&self, _actual: &ListDto, _expected: &ListDto, _compare_items: &dyn Fn(&ValueDto, &ValueDto) -> bool) -> Option<bool> {
+    None
+  }
+
+  /// Returns the items of expected list missing in actual list and the unexpected items of actual list,
+  /// when this comparator decides about the lists regardless of the order of items.
+  fn unmatched_items<'a>(&self, _actual: &'a ListDto, _expected: &'a ListDto, _compare_items: &dyn Fn(&ValueDto, &ValueDto) -> bool) -> Option<UnmatchedItems<'a>> {
+    None
+  }
+
+  /// Returns the warning recorded when simple values were found equal by this comparator.
+  fn warning(&self, _actual: &SimpleDto, _expected: &SimpleDto) -> Option<String> {
+    None
+  }
+}
+
+/// Strict comparison, deciding about all values.
+///
+/// Simple values are equal when they have the same type and the same text
+/// (binary values are compared after decoding, integers and URIs in canonical form).
+/// Durations of different categories are never equal. Lists are compared item by item.
+pub struct Strict;
+
+impl Comparator for Strict {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    let typ = actual.typ.as_deref().map(canonical_type_name);
+    Some(
+      typ == expected.typ.as_deref().map(canonical_type_name)
+        && actual.nil == expected.nil
+        && !duration_categories_differ(actual, expected)
+        && texts_equal(typ.as_deref(), actual.text.as_deref(), expected.text.as_deref()),
+    )
+  }
+
+  fn compare_lists(&self, actual: &ListDto, expected: &ListDto, compare_items: &dyn Fn(&ValueDto, &ValueDto) -> bool) -> Option<bool> {
+    Some(
+      actual.nil == expected.nil
+        && actual.items.len() == expected.items.len()
+        && actual
+          .items
+          .iter()
+          .zip(&expected.items)
+          .all(|(actual_item, expected_item)| compare_items(actual_item, expected_item)),
+    )
+  }
+}
+
+/// Comparison of decimals and doubles with absolute tolerance.
+pub struct NumericTolerance {
+  /// Maximal absolute difference of equal numbers.
+  tolerance: f64,
+}
+
+impl Comparator for NumericTolerance {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    let typ = same_type(actual, expected)?;
+    if typ != TYPE_DECIMAL && typ != TYPE_DOUBLE {
+      return None;
+    }
+    let actual_number = actual.text.as_deref()?.trim().parse::<f64>().ok().filter(|number| number.is_finite())?;
+    let expected_number = expected.text.as_deref()?.trim().parse::<f64>().ok().filter(|number| number.is_finite())?;
+    Some((actual_number - expected_number).abs() <= self.tolerance)
+  }
+
+  fn warning(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<String> {
+    lenient_warning(actual, expected, &format!("within tolerance {}", self.tolerance))
+  }
+}
+
+/// Comparison of decimals and doubles rounded to the specified number of fractional digits.
+pub struct DecimalScale {
+  /// Number of fractional digits.
+  scale: u32,
+  /// Rounding mode applied to discarded digits.
+  rounding: RoundingMode,
+}
+
+impl DecimalScale {
+  /// Returns the number rounded to the scale in canonical form.
+  fn round(&self, text: &str) -> Option<String> {
+    round_decimal(text, self.scale, self.rounding)
+  }
+}
+
+impl Comparator for DecimalScale {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    let typ = same_type(actual, expected)?;
+    if typ != TYPE_DECIMAL && typ != TYPE_DOUBLE {
+      return None;
+    }
+    let actual_number = self.round(actual.text.as_deref()?)?;
+    let expected_number = self.round(expected.text.as_deref()?)?;
+    Some(actual_number == expected_number)
+  }
+
+  fn warning(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<String> {
+    lenient_warning(actual, expected, &format!("after rounding to scale {}", self.scale))
+  }
+}
+
+/// Comparison of durations by value, so that for example `PT60S` equals `PT1M`.
+pub struct CanonicalDuration {
+  /// Pattern of the duration in ISO 8601 format.
+  pattern: Regex,
+}
+
+impl CanonicalDuration {
+  /// Creates duration comparator.
+  pub fn new() -> Self {
+    Self {
+      pattern: Regex::new(r"^(-)?P(?:(\d+)Y)?(?:(\d+)M)?(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)(?:\.(\d+))?S)?)?$").unwrap(),
+    }
+  }
+
+  /// Returns the duration as the number of months and the number of nanoseconds.
+  pub fn parse(&self, text: &str) -> Option<(i128, i128)> {
+    let captures = self.pattern.captures(text.trim())?;
+    let number = |index: usize| captures.get(index).map_or(Ok(0), |m| m.as_str().parse::<i128>()).ok();
+    let nanos = captures
+      .get(8)
+      .map_or(Some(0), |m| format!("{:0<9}", &m.as_str()[..m.as_str().len().min(9)]).parse::<i128>().ok())?;
+    // numbers of any length may appear in test files, overflowing durations are not parsed
+    let months = number(2)?.checked_mul(12)?.checked_add(number(3)?)?;
+    let seconds = number(4)?
+      .checked_mul(24)?
+      .checked_add(number(5)?)?
+      .checked_mul(60)?
+      .checked_add(number(6)?)?
+      .checked_mul(60)?
+      .checked_add(number(7)?)?;
+    let nanos = seconds.checked_mul(1_000_000_000)?.checked_add(nanos)?;
+    let sign = if captures.get(1).is_some() { -1 } else { 1 };
+    Some((sign * months, sign * nanos))
+  }
+}
+
+impl Comparator for CanonicalDuration {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    if !TYPE_DURATIONS.contains(&same_type(actual, expected)?.as_str()) {
+      return None;
+    }
+    if duration_categories_differ(actual, expected) {
+      return Some(false);
+    }
+    let actual_duration = self.parse(actual.text.as_deref()?)?;
+    let expected_duration = self.parse(expected.text.as_deref()?)?;
+    Some(actual_duration == expected_duration)
+  }
+}
+
+/// Comparison of decimals with doubles by their numeric value.
+pub struct NumericCoercion;
+
+impl Comparator for NumericCoercion {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    let numeric = |typ: &str| typ == TYPE_DECIMAL || typ == TYPE_DOUBLE;
+    let actual_type = canonical_type_name(actual.typ.as_deref()?);
+    let expected_type = canonical_type_name(expected.typ.as_deref()?);
+    if actual_type == expected_type || !numeric(&actual_type) || !numeric(&expected_type) {
+      return None;
+    }
+    let actual_number = plain_decimal(actual.text.as_deref()?)?;
+    let expected_number = plain_decimal(expected.text.as_deref()?)?;
+    Some(actual.nil == expected.nil && actual_number == expected_number)
+  }
+
+  fn warning(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<String> {
+    Some(format!(
+      "{} coerced to {}",
+      actual.typ.as_deref().unwrap_or_default(),
+      expected.typ.as_deref().unwrap_or_default()
+    ))
+  }
+}
+
+/// Comparison of strings after normalization of letter case and surrounding whitespace.
+pub struct Strings {
+  /// Flag indicating if strings are compared case-insensitively.
+  ignore_case: bool,
+  /// Flag indicating if leading and trailing whitespace is ignored.
+  trim: bool,
+}
+
+impl Strings {
+  /// Returns the string normalized according to comparison options.
+  fn normalize(&self, text: &str) -> String {
+    let text = if self.trim { text.trim() } else { text };
+    if self.ignore_case {
+      text.to_lowercase()
+    } else {
+      text.to_string()
+    }
+  }
+}
+
+impl Comparator for Strings {
+  fn compare_simple(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<bool> {
+    if same_type(actual, expected)? != TYPE_STRING {
+      return None;
+    }
+    let actual_text = self.normalize(actual.text.as_deref()?);
+    let expected_text = self.normalize(expected.text.as_deref()?);
+    Some(actual_text == expected_text)
+  }
+
+  fn warning(&self, actual: &SimpleDto, expected: &SimpleDto) -> Option<String> {
+    lenient_warning(actual, expected, "after string normalization")
+  }
+}
+
+/// Items of expected list missing in actual list and unexpected items of actual list.
+pub type UnmatchedItems<'a> = (Vec<&'a ValueDto>, Vec<&'a ValueDto>);
+
+/// Comparison of lists as multisets, regardless of the order of items but respecting their multiplicity.
+pub struct ListAsSet;
+
+impl Comparator for ListAsSet {
+  fn compare_lists(&self, actual: &ListDto, expected: &ListDto, compare_items: &dyn Fn(&ValueDto, &ValueDto) -> bool) -> Option<bool> {
+    if actual.nil != expected.nil || actual.items.len() != expected.items.len() {
+      return Some(false);
+    }
+    let (missing, unexpected) = self.unmatched_items(actual, expected, compare_items)?;
+    Some(missing.is_empty() && unexpected.is_empty())
+  }
+
+  fn unmatched_items<'a>(&self, actual: &'a ListDto, expected: &'a ListDto, compare_items: &dyn Fn(&ValueDto, &ValueDto) -> bool) -> Option<UnmatchedItems<'a>> {
+    // every expected item is greedily matched with a different actual item, so multiplicity of items matters
+    let mut unexpected = actual.items.iter().collect::<Vec<&ValueDto>>();
+    let mut missing = vec![];
+    for expected_item in &expected.items {
+      match unexpected.iter().position(|actual_item| compare_items(actual_item, expected_item)) {
+        Some(index) => {
+          unexpected.remove(index);
+        }
+        None => missing.push(expected_item),
+      }
+    }
+    Some((missing, unexpected))
+  }
+}
+
+/// Comparator that could not be loaded, all compared values are different and the reason is reported as comparison error.
+/// Plugins are validated together with configuration of commands comparing values, so this is not expected in practice.
+struct Unavailable {
+  /// Reason why the comparator could not be loaded.
+  reason: String,
+}
+
+impl Comparator for Unavailable {
+  fn compare_values(&self, _actual: &ValueDto, _expected: &ValueDto) -> Option<bool> {
+    Some(false)
+  }
+
+  fn values_mismatch(&self, _actual: &ValueDto, _expected: &ValueDto) -> Option<Mismatch> {
+    Some(Mismatch::new(MismatchClass::ComparisonError, self.reason.clone()))
+  }
+}
+
+/// Composition of comparators applied in order, the first comparator deciding about compared values wins.
+/// When none of the comparators decides, values are compared strictly.
+pub struct Comparison {
+  /// Comparators in the order they are applied, [Strict] comparator is always the last one.
+  comparators: Vec<Box<dyn Comparator>>,
+  /// Flag indicating if nil value is equal to empty list.
+  nil_equals_empty_list: bool,
+  /// Flag indicating if missing component is equal to nil component.
+  missing_component_equals_nil: bool,
+  /// Flag indicating if actual components may contain components not present in expected components.
+  components_subset: bool,
+}
+
+impl Comparison {
+  /// Creates the composition of comparators selected in configuration.
+  pub fn new(config: &ConfigurationParams) -> Self {
+    Self::with_overrides(config, &[])
+  }
+
+  /// Creates the composition of comparators selected in configuration,
+  /// preceded by overriding comparators defined for specific tests.
+  pub fn with_overrides(config: &ConfigurationParams, overrides: &[ComparatorConfig]) -> Self {
+    let comparator = |config: &ComparatorConfig| -> Box<dyn Comparator> {
+      match config {
+        ComparatorConfig::NumericTolerance { tolerance } => Box::new(NumericTolerance { tolerance: *tolerance }),
+        ComparatorConfig::CanonicalDuration => Box::new(CanonicalDuration::new()),
+        ComparatorConfig::ListAsSet => Box::new(ListAsSet),
+        ComparatorConfig::NumericCoercion => Box::new(NumericCoercion),
+        ComparatorConfig::Wasm { path } => match WasmComparator::new(path) {
+          Ok(comparator) => Box::new(comparator),
+          Err(reason) => Box::new(Unavailable { reason }),
+        },
+        ComparatorConfig::Strings { ignore_case, trim } => Box::new(Strings {
+          ignore_case: *ignore_case,
+          trim: *trim,
+        }),
+      }
+    };
+    // per-test overrides precede the rounding to decimal scale, which decides about all numbers
+    let mut comparators = overrides.iter().map(comparator).collect::<Vec<Box<dyn Comparator>>>();
+    if let Some(scale) = config.decimal_scale {
+      comparators.push(Box::new(DecimalScale { scale, rounding: config.rounding }));
+    }
+    comparators.extend(config.comparators.iter().map(comparator));
+    comparators.push(Box::new(Strict));
+    Self {
+      comparators,
+      nil_equals_empty_list: config.nil_equals_empty_list,
+      missing_component_equals_nil: config.missing_component_equals_nil,
+      components_subset: config.components_subset,
+    }
+  }
+
+  /// Returns `true` when the actual value is equal to expected value.
+  pub fn equal(&self, actual: &ValueDto, expected: &ValueDto) -> bool {
+    if let Some(verdict) = self.comparators.iter().find_map(|comparator| comparator.compare_values(actual, expected)) {
+      return verdict;
+    }
+    self.nil_as_empty_list(actual, expected)
+      || both(&actual.simple, &expected.simple, |actual, expected| self.simple_equal(actual, expected))
+        && both(&actual.components, &expected.components, |actual, expected| self.components_equal(actual, expected))
+        && both(&actual.list, &expected.list, |actual, expected| self.lists_equal(actual, expected))
+        && both(&actual.function, &expected.function, functions_equal)
+  }
+
+  /// Returns the differences between actual and expected value, in form `path: actual != expected`,
+  /// like `components[rate].simple.text: "0.035" != "0.0350"`.
+  pub fn mismatches(&self, actual: &ValueDto, expected: &ValueDto) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+    self.collect_mismatches("", actual, expected, &mut mismatches);
+    mismatches
+  }
+
+  /// Collects the differences between values, placed at specified path.
+  fn collect_mismatches(&self, path: &str, actual: &ValueDto, expected: &ValueDto, mismatches: &mut Vec<Mismatch>) {
+    if let Some((comparator, verdict)) = self
+      .comparators
+      .iter()
+      .find_map(|comparator| comparator.compare_values(actual, expected).map(|verdict| (comparator, verdict)))
+    {
+      if !verdict {
+        let mismatch = comparator
+          .values_mismatch(actual, expected)
+          .unwrap_or_else(|| Mismatch::new(MismatchClass::Value, format!("{} != {}", describe(actual), describe(expected))));
+        mismatches.push(Mismatch::new(mismatch.class, format!("{}: {}", join(path, "value"), mismatch.description)));
+      }
+      return;
+    }
+    if self.nil_as_empty_list(actual, expected) {
+      return;
+    }
+    if value_kind(actual) != value_kind(expected) {
+      mismatches.push(Mismatch::new(
+        MismatchClass::TypeMismatch,
+        format!("{}: {} != {}", join(path, "value"), value_kind(actual), value_kind(expected)),
+      ));
+      return;
+    }
+    if let Some((actual_simple, expected_simple)) = actual.simple.as_ref().zip(expected.simple.as_ref()) {
+      if !self.simple_equal(actual_simple, expected_simple) {
+        let path = join(path, "simple");
+        if actual_simple.typ.as_deref().map(canonical_type_name) != expected_simple.typ.as_deref().map(canonical_type_name) {
+          mismatches.push(Mismatch::new(
+            MismatchClass::TypeMismatch,
+            format!("{}.type: {} != {}", path, quoted(&actual_simple.typ), quoted(&expected_simple.typ)),
+          ));
+        } else if actual_simple.nil != expected_simple.nil {
+          mismatches.push(Mismatch::new(
+            MismatchClass::Nil,
+            format!("{}.isNil: {} != {}", path, actual_simple.nil, expected_simple.nil),
+          ));
+        } else if let Some((actual_category, expected_category)) = duration_categories(actual_simple, expected_simple).filter(|(a, e)| a != e) {
+          mismatches.push(Mismatch::new(
+            MismatchClass::TypeMismatch,
+            format!("{}.durationCategory: {} != {}", path, actual_category, expected_category),
+          ));
+        } else {
+          let class = if numerically_close(actual_simple, expected_simple) {
+            MismatchClass::NumericPrecision
+          } else {
+            MismatchClass::Value
+          };
+          mismatches.push(Mismatch::new(
+            class,
+            format!("{}.text: {} != {}", path, quoted(&actual_simple.text), quoted(&expected_simple.text)),
+          ));
+        }
+      }
+    }
+    if let Some((actual_components, expected_components)) = actual.components.as_ref().zip(expected.components.as_ref()) {
+      let path = join(path, "components");
+      let count = mismatches.len();
+      let name = |component: &ComponentDto| component.name.clone().unwrap_or_default();
+      for expected_component in expected_components {
+        let component_path = format!("{}[{}]", path, name(expected_component));
+        match actual_components.iter().find(|actual_component| actual_component.name == expected_component.name) {
+          Some(actual_component) if actual_component.nil != expected_component.nil => {
+            mismatches.push(Mismatch::new(
+              MismatchClass::Nil,
+              format!("{}.isNil: {} != {}", component_path, actual_component.nil, expected_component.nil),
+            ));
+          }
+          Some(actual_component) => match (&actual_component.value, &expected_component.value) {
+            (Some(actual_value), Some(expected_value)) => self.collect_mismatches(&component_path, actual_value, expected_value, mismatches),
+            (None, None) => {}
+            (actual_value, _) => mismatches.push(Mismatch::new(
+              MismatchClass::Nil,
+              format!("{}.value: {} != {}", component_path, presence(actual_value), presence(&expected_component.value)),
+            )),
+          },
+          None if self.missing_as_nil(expected_component) => {}
+          None => mismatches.push(Mismatch::new(MismatchClass::MissingComponent, format!("{}: missing", component_path))),
+        }
+      }
+      for actual_component in actual_components {
+        if !self.components_subset
+          && !expected_components.iter().any(|expected_component| expected_component.name == actual_component.name)
+          && !self.missing_as_nil(actual_component)
+        {
+          mismatches.push(Mismatch::new(MismatchClass::ExtraComponent, format!("{}[{}]: unexpected", path, name(actual_component))));
+        }
+      }
+      if mismatches.len() == count && !self.components_equal(actual_components, expected_components) {
+        mismatches.push(Mismatch::new(MismatchClass::ComponentOrder, format!("{}: order differs", path)));
+      }
+    }
+    if let Some((actual_list, expected_list)) = actual.list.as_ref().zip(expected.list.as_ref()) {
+      if !self.lists_equal(actual_list, expected_list) {
+        let path = join(path, "list");
+        let count = mismatches.len();
+        if actual_list.nil != expected_list.nil {
+          mismatches.push(Mismatch::new(MismatchClass::Nil, format!("{}.isNil: {} != {}", path, actual_list.nil, expected_list.nil)));
+        } else if actual_list.items.len() != expected_list.items.len() {
+          mismatches.push(Mismatch::new(
+            MismatchClass::ListLength,
+            format!("{}.items: length {} != {}", path, actual_list.items.len(), expected_list.items.len()),
+          ));
+        } else if let Some((missing, unexpected)) = self.unmatched_items(actual_list, expected_list) {
+          for item in missing {
+            mismatches.push(Mismatch::new(MismatchClass::ListItems, format!("{}.items: item {} missing", path, describe(item))));
+          }
+          for item in unexpected {
+            mismatches.push(Mismatch::new(MismatchClass::ListItems, format!("{}.items: item {} unexpected", path, describe(item))));
+          }
+        } else {
+          for (index, (actual_item, expected_item)) in actual_list.items.iter().zip(&expected_list.items).enumerate() {
+            if !self.equal(actual_item, expected_item) {
+              self.collect_mismatches(&format!("{}.items[{}]", path, index), actual_item, expected_item, mismatches);
+            }
+          }
+        }
+        if mismatches.len() == count {
+          mismatches.push(Mismatch::new(MismatchClass::ListItems, format!("{}: items differ", path)));
+        }
+      }
+    }
+    if let Some((actual_function, expected_function)) = actual.function.as_ref().zip(expected.function.as_ref()) {
+      if !functions_equal(actual_function, expected_function) {
+        let path = join(path, "function");
+        if actual_function.typ.as_deref().map(canonical_type_name) != expected_function.typ.as_deref().map(canonical_type_name) {
+          mismatches.push(Mismatch::new(
+            MismatchClass::TypeMismatch,
+            format!("{}.type: {} != {}", path, quoted(&actual_function.typ), quoted(&expected_function.typ)),
+          ));
+        } else {
+          mismatches.push(Mismatch::new(
+            MismatchClass::Value,
+            format!("{}.text: {} != {}", path, quoted(&actual_function.text), quoted(&expected_function.text)),
+          ));
+        }
+      }
+    }
+  }
+
+  /// Returns the warnings recorded by comparators for equal actual and expected value,
+  /// like `components[rate].simple: xsd:double coerced to xsd:decimal`.
+  pub fn warnings(&self, actual: &ValueDto, expected: &ValueDto) -> Vec<String> {
+    let mut warnings = vec![];
+    self.collect_warnings("", actual, expected, &mut warnings);
+    warnings
+  }
+
+  /// Collects the warnings for values placed at specified path.
+  fn collect_warnings(&self, path: &str, actual: &ValueDto, expected: &ValueDto, warnings: &mut Vec<String>) {
+    if self.nil_as_empty_list(actual, expected) {
+      if value_kind(actual) != value_kind(expected) || is_nil(actual) != is_nil(expected) {
+        warnings.push(format!("{}: nil value treated as empty list", join(path, "value")));
+      }
+      return;
+    }
+    if let Some((actual_simple, expected_simple)) = actual.simple.as_ref().zip(expected.simple.as_ref()) {
+      let warning = self
+        .comparators
+        .iter()
+        .find(|comparator| comparator.compare_simple(actual_simple, expected_simple).is_some())
+        .and_then(|comparator| comparator.warning(actual_simple, expected_simple));
+      if let Some(warning) = warning {
+        warnings.push(format!("{}: {}", join(path, "simple"), warning));
+      }
+    }
+    if let Some((actual_components, expected_components)) = actual.components.as_ref().zip(expected.components.as_ref()) {
+      let path = join(path, "components");
+      for (components, other_components, side) in [(actual_components, expected_components, "expected"), (expected_components, actual_components, "actual")] {
+        for component in components {
+          if !other_components.iter().any(|other_component| other_component.name == component.name) && self.missing_as_nil(component) {
+            warnings.push(format!(
+              "{}[{}]: missing {} component treated as nil",
+              path,
+              component.name.as_deref().unwrap_or_default(),
+              side
+            ));
+          }
+        }
+      }
+      for expected_component in expected_components {
+        let actual_value = actual_components
+          .iter()
+          .find(|actual_component| actual_component.name == expected_component.name)
+          .and_then(|actual_component| actual_component.value.as_ref());
+        if let Some((actual_value, expected_value)) = actual_value.zip(expected_component.value.as_ref()) {
+          let component_path = format!("{}[{}]", path, expected_component.name.as_deref().unwrap_or_default());
+          self.collect_warnings(&component_path, actual_value, expected_value, warnings);
+        }
+      }
+    }
+    if let Some((actual_list, expected_list)) = actual.list.as_ref().zip(expected.list.as_ref()) {
+      for (index, (actual_item, expected_item)) in actual_list.items.iter().zip(&expected_list.items).enumerate() {
+        self.collect_warnings(&format!("{}.items[{}]", join(path, "list"), index), actual_item, expected_item, warnings);
+      }
+    }
+  }
+
+  /// Compares simple values using the first deciding comparator.
+  fn simple_equal(&self, actual: &SimpleDto, expected: &SimpleDto) -> bool {
+    self
+      .comparators
+      .iter()
+      .find_map(|comparator| comparator.compare_simple(actual, expected))
+      .unwrap_or_default()
+  }
+
+  /// Compares components in order.
+  fn components_equal(&self, actual: &[ComponentDto], expected: &[ComponentDto]) -> bool {
+    let mut actual_components = self.present_components(actual, expected);
+    if self.components_subset {
+      actual_components.retain(|actual_component| expected.iter().any(|expected_component| expected_component.name == actual_component.name));
+    }
+    let expected_components = self.present_components(expected, actual);
+    let (actual, expected) = (actual_components.as_slice(), expected_components.as_slice());
+    actual.len() == expected.len()
+      && actual.iter().zip(expected).all(|(actual_component, expected_component)| {
+        actual_component.name == expected_component.name
+          && actual_component.nil == expected_component.nil
+          && both(&actual_component.value, &expected_component.value, |actual, expected| self.equal(actual, expected))
+      })
+  }
+
+  /// Returns components, without nil components missing in other components when such components are treated as missing.
+  fn present_components<'a>(&self, components: &'a [ComponentDto], other_components: &[ComponentDto]) -> Vec<&'a ComponentDto> {
+    components
+      .iter()
+      .filter(|component| !self.missing_as_nil(component) || other_components.iter().any(|other_component| other_component.name == component.name))
+      .collect()
+  }
+
+  /// Returns `true` when the nil component is equal to missing component.
+  fn missing_as_nil(&self, component: &ComponentDto) -> bool {
+    self.missing_component_equals_nil && (component.nil || component.value.as_ref().is_none_or(is_nil))
+  }
+
+  /// Returns `true` when nil value is equal to empty list and both values are nil or empty lists.
+  fn nil_as_empty_list(&self, actual: &ValueDto, expected: &ValueDto) -> bool {
+    let nil_or_empty = |value: &ValueDto| is_nil(value) || value.list.as_ref().is_some_and(|list| list.items.is_empty());
+    self.nil_equals_empty_list && (actual.list.is_some() || expected.list.is_some()) && nil_or_empty(actual) && nil_or_empty(expected)
+  }
+
+  /// Returns the unmatched items of lists, when the first deciding comparator compares lists regardless of the order of items.
+  fn unmatched_items<'a>(&self, actual: &'a ListDto, expected: &'a ListDto) -> Option<UnmatchedItems<'a>> {
+    let compare_items = |actual: &ValueDto, expected: &ValueDto| self.equal(actual, expected);
+    self
+      .comparators
+      .iter()
+      .find(|comparator| comparator.compare_lists(actual, expected, &compare_items).is_some())?
+      .unmatched_items(actual, expected, &compare_items)
+  }
+
+  /// Compares lists using the first deciding comparator.
+  fn lists_equal(&self, actual: &ListDto, expected: &ListDto) -> bool {
+    let compare_items = |actual: &ValueDto, expected: &ValueDto| self.equal(actual, expected);
+    self
+      .comparators
+      .iter()
+      .find_map(|comparator| comparator.compare_lists(actual, expected, &compare_items))
+      .unwrap_or_default()
+  }
+}
+
+/// Name of the extension element marking expected simple value as a pattern.
+const EXPECTED_PATTERN: &str = "expectedPattern";
+
+/// Expected simple value marked as a pattern the actual value must match,
+/// defined in extension elements of result node, like:
+/// ```xml
+/// <extensionElements>
+///   <expectedPattern path="components[id]" syntax="glob"/>
+/// </extensionElements>
+/// ```
+/// The path has the same form as paths reported for mismatched values, empty path denotes the whole value.
+/// Supported syntaxes are `regex` (default) and `glob`.
+#[derive(Clone)]
+pub struct ExpectedPattern {
+  /// Path of the expected value.
+  path: String,
+  /// Syntax of the pattern.
+  syntax: String,
+}
+
+impl ExpectedPattern {
+  /// Returns expected patterns defined in extension elements.
+  pub fn from_extension_elements(elements: &[ExtensionElement]) -> Vec<Self> {
+    elements
+      .iter()
+      .filter(|element| element.name == EXPECTED_PATTERN)
+      .map(|element| {
+        let attribute = |name: &str| element.attributes.iter().find(|(attr_name, _)| attr_name == name).map(|(_, attr_value)| attr_value.clone());
+        Self {
+          path: attribute("path").unwrap_or_default(),
+          syntax: attribute("syntax").unwrap_or("regex".to_string()),
+        }
+      })
+      .collect()
+  }
+
+  /// Replaces the text of the expected value with the text of actual value, when the actual value matches the pattern.
+  /// Otherwise the expected value is left unchanged and reported as a mismatch.
+  pub fn apply(&self, actual: &ValueDto, expected: &mut ValueDto) -> Result<(), String> {
+    let Some(pattern) = value_at_mut(expected, &self.path)
+      .and_then(|value| value.simple.as_mut())
+      .filter(|simple| simple.text.is_some())
+    else {
+      return Err(format!("no expected simple value at path '{}'", self.path));
+    };
+    let text = pattern.text.as_deref().unwrap_or_default();
+    let is_match: Box<dyn Fn(&str) -> bool> = match self.syntax.as_str() {
+      "regex" => {
+        let regex = Regex::new(&format!("^(?:{})$", text)).map_err(|e| format!("invalid regex '{}' at path '{}': {}", text, self.path, e))?;
+        Box::new(move |actual_text| regex.is_match(actual_text))
+      }
+      "glob" => {
+        let matcher = Glob::new(text)
+          .map_err(|e| format!("invalid glob '{}' at path '{}': {}", text, self.path, e))?
+          .compile_matcher();
+        Box::new(move |actual_text| matcher.is_match(actual_text))
+      }
+      other => return Err(format!("unsupported pattern syntax '{}' at path '{}'", other, self.path)),
+    };
+    let actual_text = value_at(actual, &self.path).and_then(|value| value.simple.as_ref()).and_then(|simple| simple.text.as_ref());
+    if let Some(actual_text) = actual_text.filter(|actual_text| is_match(actual_text)) {
+      pattern.text = Some(actual_text.clone());
+    }
+    Ok(())
+  }
+}
+
+/// Segment of the path of the value.
+enum Segment {
+  /// Component with specified name.
+  Component(String),
+  /// List item with specified index.
+  Item(usize),
+}
+
+/// Splits the path of the value, like `components[rate].list.items[0]`, into segments.
+fn segments(path: &str) -> Option<Vec<Segment>> {
+  let mut segments = vec![];
+  let mut rest = path;
+  while !rest.is_empty() {
+    if let Some(tail) = rest.strip_prefix("components[") {
+      let (name, tail) = tail.split_once(']')?;
+      segments.push(Segment::Component(name.to_string()));
+      rest = tail;
+    } else if let Some(tail) = rest.strip_prefix("list.items[") {
+      let (index, tail) = tail.split_once(']')?;
+      segments.push(Segment::Item(index.parse().ok()?));
+      rest = tail;
+    } else {
+      return None;
+    }
+    rest = rest.strip_prefix('.').unwrap_or(rest);
+  }
+  Some(segments)
+}
+
+/// Returns the value placed at specified path.
+fn value_at<'a>(value: &'a ValueDto, path: &str) -> Option<&'a ValueDto> {
+  segments(path)?.iter().try_fold(value, |value, segment| match segment {
+    Segment::Component(name) => value.components.as_ref()?.iter().find(|component| component.name.as_deref() == Some(name))?.value.as_ref(),
+    Segment::Item(index) => value.list.as_ref()?.items.get(*index),
+  })
+}
+
+/// Returns the mutable value placed at specified path.
+fn value_at_mut<'a>(value: &'a mut ValueDto, path: &str) -> Option<&'a mut ValueDto> {
+  segments(path)?.iter().try_fold(value, |value, segment| match segment {
+    Segment::Component(name) => value
+      .components
+      .as_mut()?
+      .iter_mut()
+      .find(|component| component.name.as_deref() == Some(name))?
+      .value
+      .as_mut(),
+    Segment::Item(index) => value.list.as_mut()?.items.get_mut(*index),
+  })
+}
+
+/// Returns the name of the kind of value.
+fn value_kind(value: &ValueDto) -> &'static str {
+  if value.simple.is_some() {
+    "simple"
+  } else if value.components.is_some() {
+    "components"
+  } else if value.list.is_some() {
+    "list"
+  } else if value.function.is_some() {
+    "function"
+  } else {
+    "none"
+  }
+}
+
+/// Returns `true` when the value is nil, either as nil simple value, nil list or value without content.
+fn is_nil(value: &ValueDto) -> bool {
+  value.simple.as_ref().is_some_and(|simple| simple.nil) || value.list.as_ref().is_some_and(|list| list.nil) || value_kind(value) == "none"
+}
+
+/// Returns the short, readable description of the value, like `{name: "X", age: 3}`.
+fn describe(value: &ValueDto) -> String {
+  if let Some(simple) = &value.simple {
+    match (&simple.text, simple.nil) {
+      (Some(text), false) if simple.typ.as_deref().map(canonical_type_name).as_deref() == Some(TYPE_STRING) => format!("{:?}", text),
+      (Some(text), false) => text.clone(),
+      _ => "null".to_string(),
+    }
+  } else if let Some(components) = &value.components {
+    let components = components
+      .iter()
+      .map(|component| {
+        let value = component.value.as_ref().filter(|_| !component.nil).map_or("null".to_string(), describe);
+        format!("{}: {}", component.name.as_deref().unwrap_or_default(), value)
+      })
+      .collect::<Vec<String>>();
+    format!("{{{}}}", components.join(", "))
+  } else if let Some(list) = value.list.as_ref().filter(|list| !list.nil) {
+    format!("[{}]", list.items.iter().map(describe).collect::<Vec<String>>().join(", "))
+  } else if value.function.is_some() {
+    "function".to_string()
+  } else {
+    "null".to_string()
+  }
+}
+
+/// Appends the segment to the path of the value.
+fn join(path: &str, segment: &str) -> String {
+  if path.is_empty() {
+    segment.to_string()
+  } else {
+    format!("{}.{}", path, segment)
+  }
+}
+
+/// Returns the text in double quotes, or `null` when absent.
+fn quoted(text: &Option<String>) -> String {
+  text.as_ref().map_or("null".to_string(), |text| format!("{:?}", text))
+}
+
+/// Returns the description of the presence of the value.
+fn presence<T>(value: &Option<T>) -> &'static str {
+  if value.is_some() {
+    "present"
+  } else {
+    "absent"
+  }
+}
+
+/// Returns `true` when both values are absent, or both are present and equal.
+fn both<T>(actual: &Option<T>, expected: &Option<T>, equal: impl Fn(&T, &T) -> bool) -> bool {
+  match (actual, expected) {
+    (Some(actual), Some(expected)) => equal(actual, expected),
+    (None, None) => true,
+    _ => false,
+  }
+}
+
+/// Functions are opaque, they are equal when they have the same type and the same text,
+/// a function without text is equal only to another function without text.
+fn functions_equal(actual: &FunctionDto, expected: &FunctionDto) -> bool {
+  actual.typ.as_deref().map(canonical_type_name) == expected.typ.as_deref().map(canonical_type_name) && actual.text == expected.text
+}
+
+/// Returns the warning about values found equal only by lenient comparison,
+/// or `None` when the values are equal also in strict comparison.
+fn lenient_warning(actual: &SimpleDto, expected: &SimpleDto, leniency: &str) -> Option<String> {
+  if Strict.compare_simple(actual, expected) == Some(true) {
+    return None;
+  }
+  Some(format!("{} accepted as {} {}", quoted(&actual.text), quoted(&expected.text), leniency))
+}
+
+/// Maximal relative difference of numbers classified as a precision mismatch.
+const PRECISION_THRESHOLD: f64 = 1e-6;
+
+/// Returns `true` when both values are numbers differing only by relative difference
+/// not greater than [PRECISION_THRESHOLD].
+fn numerically_close(actual: &SimpleDto, expected: &SimpleDto) -> bool {
+  if !matches!(same_type(actual, expected).as_deref(), Some(TYPE_DECIMAL | TYPE_DOUBLE)) {
+    return false;
+  }
+  let number = |value: &SimpleDto| value.text.as_deref().and_then(|text| text.trim().parse::<f64>().ok()).filter(|number| number.is_finite());
+  let Some((actual_number, expected_number)) = number(actual).zip(number(expected)) else {
+    return false;
+  };
+  let scale = actual_number.abs().max(expected_number.abs());
+  scale > 0.0 && (actual_number - expected_number).abs() / scale <= PRECISION_THRESHOLD
+}
+
+/// Returns the categories of both values when they are durations of known category.
+fn duration_categories(actual: &SimpleDto, expected: &SimpleDto) -> Option<(DurationCategory, DurationCategory)> {
+  if !TYPE_DURATIONS.contains(&canonical_type_name(actual.typ.as_deref()?).as_str()) {
+    return None;
+  }
+  DurationCategory::of(actual.text.as_deref()?).zip(DurationCategory::of(expected.text.as_deref()?))
+}
+
+/// Returns `true` when both values are durations of different categories.
+fn duration_categories_differ(actual: &SimpleDto, expected: &SimpleDto) -> bool {
+  duration_categories(actual, expected).is_some_and(|(actual_category, expected_category)| actual_category != expected_category)
+}
+
+/// Returns the canonical type name when both values have the same type and nil flag.
+fn same_type(actual: &SimpleDto, expected: &SimpleDto) -> Option<String> {
+  let typ = canonical_type_name(actual.typ.as_deref()?);
+  (typ == canonical_type_name(expected.typ.as_deref()?) && actual.nil == expected.nil).then_some(typ)
+}
+
+/// Compares texts of simple values according to their type.
+/// Binary values are compared after decoding, integers and URIs are compared in canonical form,
+/// special double values `NaN`, `INF` and `-INF` are compared in normalized form, so `NaN` equals `NaN`,
+/// times and date times are compared in canonical lexical form. Numbers are compared by their value,
+/// decimals written without exponent are compared also by their scale, so `0.035` differs from `0.0350`.
+fn texts_equal(type_name: Option<&str>, lhs: Option<&str>, rhs: Option<&str>) -> bool {
+  let Some((lhs, rhs)) = lhs.zip(rhs) else {
+    return lhs == rhs;
+  };
+  match type_name {
+    Some(TYPE_BASE64_BINARY) => match (decode_base64(lhs), decode_base64(rhs)) {
+      (Some(lhs_bytes), Some(rhs_bytes)) => lhs_bytes == rhs_bytes,
+      _ => lhs == rhs,
+    },
+    Some(TYPE_INTEGER) => match (canonical_integer(lhs), canonical_integer(rhs)) {
+      (Some(lhs_integer), Some(rhs_integer)) => lhs_integer == rhs_integer,
+      _ => lhs == rhs,
+    },
+    Some(TYPE_ANY_URI) => canonical_uri(lhs) == canonical_uri(rhs),
+    Some(TYPE_TIME | TYPE_DATE_TIME) => canonical_time(lhs) == canonical_time(rhs),
+    Some(TYPE_DOUBLE) if special_double(lhs).is_some() || special_double(rhs).is_some() => special_double(lhs) == special_double(rhs),
+    Some(TYPE_DECIMAL) => match (plain_decimal(lhs), plain_decimal(rhs)) {
+      (Some(lhs_number), Some(rhs_number)) => lhs_number == rhs_number && plain_scale(lhs).zip(plain_scale(rhs)).is_none_or(|(lhs_scale, rhs_scale)| lhs_scale == rhs_scale),
+      _ => lhs == rhs,
+    },
+    Some(TYPE_DOUBLE) => match (plain_decimal(lhs), plain_decimal(rhs)) {
+      (Some(lhs_number), Some(rhs_number)) => lhs_number == rhs_number,
+      _ => lhs == rhs,
+    },
+    _ => lhs == rhs,
+  }
+}
+
+/// Returns the number of fractional digits of the number written without exponent,
+/// `None` for numbers written with exponent.
+fn plain_scale(text: &str) -> Option<usize> {
+  let text = text.trim();
+  if text.contains(['e', 'E']) {
+    return None;
+  }
+  Some(text.split_once('.').map_or(0, |(_, fraction)| fraction.len()))
+}
+
+/// Returns the normalized form of special double values `NaN`, `INF` and `-INF`,
+/// accepting also other textual forms like `nan`, `+INF` or `-Infinity`.
+fn special_double(text: &str) -> Option<&'static str> {
+  match text.trim().to_ascii_lowercase().as_str() {
+    "nan" => Some("NaN"),
+    "inf" | "+inf" | "infinity" | "+infinity" => Some("INF"),
+    "-inf" | "-infinity" => Some("-INF"),
+    _ => None,
+  }
+}
+
+/// Returns the number rounded to the specified number of fractional digits in canonical form,
+/// without trailing fractional zeros. Returns `None` when the text is not a number.
+pub fn round_decimal(text: &str, scale: u32, rounding: RoundingMode) -> Option<String> {
+  let text = plain_decimal(text)?;
+  let text = text.as_str();
+  let (negative, unsigned) = match text.strip_prefix('-') {
+    Some(unsigned) => (true, unsigned),
+    None => (false, text.strip_prefix('+').unwrap_or(text)),
+  };
+  let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+  if integer.is_empty() && fraction.is_empty() || !integer.chars().chain(fraction.chars()).all(|ch| ch.is_ascii_digit()) {
+    return None;
+  }
+  let scale = (scale as usize).min(fraction.len());
+  let (kept, discarded) = fraction.split_at(scale);
+  let mut digits = format!("{}{}", integer, kept).into_bytes();
+  let first_discarded = discarded.bytes().next().unwrap_or(b'0');
+  let rest_nonzero = discarded.bytes().skip(1).any(|digit| digit != b'0');
+  let any_nonzero = first_discarded != b'0' || rest_nonzero;
+  let last_odd = digits.last().is_some_and(|digit| (digit - b'0') % 2 == 1);
+  let increment = match rounding {
+    RoundingMode::HalfEven => first_discarded > b'5' || (first_discarded == b'5' && (rest_nonzero || last_odd)),
+    RoundingMode::HalfUp => first_discarded >= b'5',
+    RoundingMode::HalfDown => first_discarded > b'5' || (first_discarded == b'5' && rest_nonzero),
+    RoundingMode::Up => any_nonzero,
+    RoundingMode::Down => false,
+    RoundingMode::Ceiling => !negative && any_nonzero,
+    RoundingMode::Floor => negative && any_nonzero,
+  };
+  if increment {
+    let mut position = digits.len();
+    loop {
+      if position == 0 {
+        digits.insert(0, b'1');
+        break;
+      }
+      position -= 1;
+      if digits[position] == b'9' {
+        digits[position] = b'0';
+      } else {
+        digits[position] += 1;
+        break;
+      }
+    }
+  }
+  let digits = String::from_utf8(digits).ok()?;
+  let (integer, fraction) = digits.split_at(digits.len() - scale);
+  let integer = integer.trim_start_matches('0');
+  let fraction = fraction.trim_end_matches('0');
+  let magnitude = match (integer.is_empty(), fraction.is_empty()) {
+    (true, true) => "0".to_string(),
+    (false, true) => integer.to_string(),
+    (true, false) => format!("0.{}", fraction),
+    (false, false) => format!("{}.{}", integer, fraction),
+  };
+  Some(if negative && magnitude != "0" { format!("-{}", magnitude) } else { magnitude })
+}
+
+/// Returns the time or date and time in canonical lexical form, with UTC offset written as `Z`
+/// and without trailing fractional zeros of seconds, so `10:00:00.000+00:00` becomes `10:00:00Z`.
+fn canonical_time(text: &str) -> String {
+  let text = text.trim();
+  let (time, offset) = if let Some(time) = text.strip_suffix('Z') {
+    (time, "Z")
+  } else if text.len() > 6 && text.is_char_boundary(text.len() - 6) && is_offset(&text[text.len() - 6..]) {
+    text.split_at(text.len() - 6)
+  } else {
+    (text, "")
+  };
+  let offset = if offset_minutes(offset) == Some(0) { "Z" } else { offset };
+  // seconds are the last part of the time, following the last colon
+  let time = match time.rsplit_once(':') {
+    Some((hours_minutes, seconds)) => format!("{}:{}", hours_minutes, canonical_seconds(seconds)),
+    None => time.to_string(),
+  };
+  format!("{}{}", time, offset)
+}
+
+/// Returns the seconds without trailing fractional zeros, so `05.100` becomes `05.1` and `05.000` becomes `05`.
+pub fn canonical_seconds(seconds: &str) -> String {
+  match seconds.split_once('.') {
+    Some((whole, fraction)) if fraction.bytes().all(|digit| digit.is_ascii_digit()) => {
+      let fraction = fraction.trim_end_matches('0');
+      if fraction.is_empty() {
+        whole.to_string()
+      } else {
+        format!("{}.{}", whole, fraction)
+      }
+    }
+    _ => seconds.to_string(),
+  }
+}
+
+/// Returns the time offset in minutes, `Z` denotes UTC.
+pub fn offset_minutes(offset: &str) -> Option<i64> {
+  if offset == "Z" {
+    return Some(0);
+  }
+  let (hours, minutes) = offset.get(1..)?.split_once(':')?;
+  let minutes = hours.parse::<i64>().ok()?.checked_mul(60)?.checked_add(minutes.parse::<i64>().ok()?)?;
+  Some(if offset.starts_with('-') { -minutes } else { minutes })
+}
+
+/// Returns `true` when the text is a time offset like `+01:00`.
+fn is_offset(text: &str) -> bool {
+  let bytes = text.as_bytes();
+  bytes.len() == 6 && (bytes[0] == b'+' || bytes[0] == b'-') && bytes[3] == b':' && [1, 2, 4, 5].iter().all(|index| bytes[*index].is_ascii_digit())
+}
+
+/// Maximal absolute value of the exponent of numbers converted into decimal notation.
+const MAX_EXPONENT: i64 = 1000;
+
+/// Returns the number in canonical decimal notation, without exponent, leading zeros and trailing fractional zeros,
+/// so `1E+3` becomes `1000` and `1.0e-2` becomes `0.01`. Returns `None` when the text is not a number.
+pub fn plain_decimal(text: &str) -> Option<String> {
+  let text = text.trim();
+  let (negative, unsigned) = match text.strip_prefix('-') {
+    Some(unsigned) => (true, unsigned),
+    None => (false, text.strip_prefix('+').unwrap_or(text)),
+  };
+  let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+    Some((mantissa, exponent)) => (mantissa, exponent.strip_prefix('+').unwrap_or(exponent).parse::<i64>().ok()?),
+    None => (unsigned, 0),
+  };
+  let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+  if integer.is_empty() && fraction.is_empty() || !integer.chars().chain(fraction.chars()).all(|ch| ch.is_ascii_digit()) || exponent.abs() > MAX_EXPONENT {
+    return None;
+  }
+  let digits = format!("{}{}", integer, fraction);
+  let point = integer.len() as i64 + exponent;
+  let (integer, fraction) = if point <= 0 {
+    (String::new(), format!("{}{}", "0".repeat(-point as usize), digits))
+  } else if point as usize >= digits.len() {
+    (format!("{}{}", digits, "0".repeat(point as usize - digits.len())), String::new())
+  } else {
+    let (integer, fraction) = digits.split_at(point as usize);
+    (integer.to_string(), fraction.to_string())
+  };
+  let integer = integer.trim_start_matches('0');
+  let fraction = fraction.trim_end_matches('0');
+  let magnitude = match (integer.is_empty(), fraction.is_empty()) {
+    (true, true) => "0".to_string(),
+    (false, true) => integer.to_string(),
+    (true, false) => format!("0.{}", fraction),
+    (false, false) => format!("{}.{}", integer, fraction),
+  };
+  Some(if negative && magnitude != "0" { format!("-{}", magnitude) } else { magnitude })
+}
+
+/// Decodes Base64 encoded text, whitespace within the text is ignored.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+  STANDARD.decode(text.split_whitespace().collect::<String>()).ok()
+}
+
+/// Returns the integer without sign `+` and leading zeros, `None` when the text is not a valid integer.
+pub fn canonical_integer(text: &str) -> Option<String> {
+  let text = text.trim();
+  let (negative, digits) = match text.strip_prefix('-') {
+    Some(digits) => (true, digits),
+    None => (false, text.strip_prefix('+').unwrap_or(text)),
+  };
+  if digits.is_empty() || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+    return None;
+  }
+  let digits = digits.trim_start_matches('0');
+  Some(match (negative, digits.is_empty()) {
+    (_, true) => "0".to_string(),
+    (true, false) => format!("-{}", digits),
+    (false, false) => digits.to_string(),
+  })
+}
+
+/// Returns the URI with collapsed whitespace, absolute URIs are additionally normalized.
+fn canonical_uri(text: &str) -> String {
+  let text = collapse_whitespace(text);
+  Url::parse(&text).map(|url| url.to_string()).unwrap_or(text)
+}
+
+/// Replaces sequences of whitespace with single space and removes leading and trailing whitespace.
+pub fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
@@ -0,0 +1,165 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Writing computed values back into test files
+
+use crate::dto::ValueDto;
+use crate::serializer::escape;
+use roxmltree::{Document, Node};
+use std::fs;
+
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Fills in (or updates) the `computed` element of every result node in the test file.
+/// Computed values are given in the document order of result nodes, nodes without computed value are left unchanged.
+pub fn write(file_name: &str, computed_values: &[Option<ValueDto>]) -> Result<(), String> {
+  let content = fs::read_to_string(file_name).map_err(|e| format!("reading test file failed with reason: {}", e))?;
+  let document = Document::parse(&content).map_err(|e| format!("parsing test file failed with reason: {}", e))?;
+  let result_nodes = document
+    .root_element()
+    .children()
+    .filter(|n| n.tag_name().name() == "testCase")
+    .flat_map(|n| n.children().filter(|n| n.tag_name().name() == "resultNode"))
+    .collect::<Vec<Node>>();
+  let mut edits = vec![];
+  for (result_node, computed_value) in result_nodes.iter().zip(computed_values) {
+    if let Some(value) = computed_value {
+      edits.push(computed_edit(&content, result_node, value));
+    }
+  }
+  let mut updated = content.clone();
+  for (start, end, replacement) in edits.into_iter().rev() {
+    updated.replace_range(start..end, &replacement);
+  }
+  fs::write(file_name, updated).map_err(|e| format!("writing test file failed with reason: {}", e))
+}
+
+/// Returns the edit (range to be replaced and replacement text) that places the computed value in result node.
+fn computed_edit(content: &str, result_node: &Node, value: &ValueDto) -> (usize, usize, String) {
+  let prefix = element_prefix(content, result_node);
+  let xsi = result_node.lookup_prefix(XSI);
+  let mut computed = format!("<{}computed", prefix);
+  if xsi.is_none() {
+    computed.push_str(&format!(r#" xmlns:xsi="{}""#, XSI));
+  }
+  computed.push('>');
+  write_value(&mut computed, &prefix, xsi.unwrap_or("xsi"), value);
+  computed.push_str(&format!("</{}computed>", prefix));
+  let child = |name: &str| result_node.children().find(|n| n.tag_name().name() == name);
+  if let Some(computed_node) = child("computed") {
+    (computed_node.range().start, computed_node.range().end, computed)
+  } else if let Some(expected_node) = child("expected") {
+    let position = expected_node.range().end;
+    (position, position, format!("{}{}", line_break(content, expected_node.range().start), computed))
+  } else if let Some(first_child) = result_node.first_child() {
+    let position = first_child.range().start;
+    (position, position, computed)
+  } else {
+    // result node is an empty element, like <resultNode name="x"/>
+    let range = result_node.range();
+    let start_tag = content[range.clone()].trim_end_matches("/>").trim_end();
+    let tag_name = start_tag.trim_start_matches('<').split_whitespace().next().unwrap_or_default();
+    (range.start, range.end, format!("{}>{}</{}>", start_tag, computed, tag_name))
+  }
+}
+
+/// Returns the namespace prefix (with colon) used in the qualified name of the element, empty when no prefix is used.
+fn element_prefix(content: &str, node: &Node) -> String {
+  let tag = &content[node.range().start + 1..];
+  let qualified_name = tag.split(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/').next().unwrap_or_default();
+  match qualified_name.split_once(':') {
+    Some((prefix, _)) => format!("{}:", prefix),
+    None => String::new(),
+  }
+}
+
+/// Returns the line break with indentation of the line where the element starts.
+fn line_break(content: &str, position: usize) -> String {
+  let line_start = content[..position].rfind('\n').map(|index| index + 1).unwrap_or(0);
+  let indentation = &content[line_start..position];
+  if indentation.chars().all(char::is_whitespace) {
+    format!("\n{}", indentation)
+  } else {
+    String::new()
+  }
+}
+
+/// Writes the value in test file format.
+fn write_value(out: &mut String, prefix: &str, xsi: &str, value: &ValueDto) {
+  if let Some(simple) = &value.simple {
+    out.push_str(&format!("<{}value", prefix));
+    if let Some(typ) = &simple.typ {
+      out.push_str(&format!(r#" {}:type="{}""#, xsi, escape(typ)));
+    }
+    match (&simple.text, simple.nil) {
+      (Some(text), false) => out.push_str(&format!(">{}</{}value>", escape(text), prefix)),
+      (_, true) => out.push_str(&format!(r#" {}:nil="true"/>"#, xsi)),
+      (None, false) => out.push_str("/>"),
+    }
+  }
+  if let Some(components) = &value.components {
+    for component in components {
+      out.push_str(&format!("<{}component", prefix));
+      if let Some(name) = &component.name {
+        out.push_str(&format!(r#" name="{}""#, escape(name)));
+      }
+      match &component.value {
+        Some(value) if !component.nil => {
+          out.push('>');
+          write_value(out, prefix, xsi, value);
+          out.push_str(&format!("</{}component>", prefix));
+        }
+        _ => out.push_str(&format!(r#" {}:nil="true"/>"#, xsi)),
+      }
+    }
+  }
+  if let Some(list) = &value.list {
+    if list.nil {
+      out.push_str(&format!(r#"<{}list {}:nil="true"/>"#, prefix, xsi));
+    } else {
+      out.push_str(&format!("<{}list>", prefix));
+      for item in &list.items {
+        out.push_str(&format!("<{}item>", prefix));
+        write_value(out, prefix, xsi, item);
+        out.push_str(&format!("</{}item>", prefix));
+      }
+      out.push_str(&format!("</{}list>", prefix));
+    }
+  }
+  if let Some(function) = &value.function {
+    out.push_str(&format!("<{}value", prefix));
+    if let Some(typ) = &function.typ {
+      out.push_str(&format!(r#" {}:type="{}""#, xsi, escape(typ)));
+    }
+    out.push_str(&format!(">{}</{}value>", escape(function.text.as_deref().unwrap_or_default()), prefix));
+  }
+}
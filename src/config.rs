@@ -32,31 +32,966 @@
 
 //! # Configuration data
 
-use serde::{Deserialize, Serialize};
+use crate::plugin::WasmComparator;
+use crate::selection;
+use regex::Regex;
+use serde::de::{self, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Name of the configuration file searched in the current directory.
+pub const DEFAULT_CONFIG_FILE: &str = "config.yml";
+
+/// Alternative name of the configuration file searched in the current directory.
+const RUNNER_CONFIG_FILE: &str = "dmntk-runner.yml";
+
+/// Key of the section with named profiles overriding parameters of the base section.
+const PROFILES_KEY: &str = "profiles";
+
+/// Name of the directory in user's configuration directory, where configuration file is searched.
+const CONFIG_DIR: &str = "dmntk-runner";
 
 /// Runner configuration parameters.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigurationParams {
-  /// Path to directory containing test cases.
-  pub test_cases_dir_path: String,
-  /// Pattern for matching test file names.
-  /// Only files whose name matches the pattern will be processed.
+  /// Paths to directories containing test cases, specified as a single path or a list of paths.
+  #[serde(rename = "test_cases_dir_path", deserialize_with = "one_or_many")]
+  pub test_cases_dir_paths: Vec<String>,
+  /// Pattern for matching full names of model and test files.
+  /// Only files whose name matches the pattern will be processed, all files are processed when empty.
+  #[serde(default)]
   pub file_search_pattern: String,
+  /// Glob patterns for matching test files, relative to test cases directory,
+  /// test files must match any of the globs in addition to include patterns.
+  #[serde(default)]
+  pub file_search_globs: Vec<String>,
+  /// Patterns of included test files, matched against the path relative to test cases directory.
+  /// Patterns prefixed with `glob:` are globs, other patterns are regular expressions.
+  /// When specified, only test files matching any of the patterns will be processed.
+  #[serde(default)]
+  pub include: Vec<String>,
+  /// Compliance levels of executed tests, a test file belongs to the level when it is placed
+  /// in `compliance-level-N` directory or labelled with `Compliance Level N`.
+  /// All test files are executed when not specified.
+  #[serde(default)]
+  pub compliance_levels: Vec<u8>,
+  /// Patterns of excluded test files, in the same form as include patterns.
+  /// Test files matching any of the patterns are not processed, even when included.
+  #[serde(default)]
+  pub exclude: Vec<String>,
+  /// Optional pattern for matching test case names.
+  /// When specified, only test cases whose name matches the pattern will be executed.
+  pub test_case_name_pattern: Option<String>,
   /// URL to service where model definitions will be evaluated.
   pub evaluate_url: String,
+  /// Optional IP address, without port, the host of evaluate URL is resolved to, bypassing DNS resolution.
+  /// Requests are sent to the port of evaluate URL.
+  pub endpoint_address: Option<String>,
+  /// Optional path to unix domain socket engine listens on, used instead of the address of evaluate URL.
+  pub endpoint_socket: Option<String>,
   /// Path to report file.
   pub report_file: String,
   /// Path to report file for TCK.
   pub tck_report_file: String,
-  /// Flag indicating if testing should immediately stop on failure.
+  /// Flag indicating if testing should stop on the first failure, the same as `max_failures: 1`.
   pub stop_on_failure: bool,
+  /// Optional number of failed tests after which testing is stopped, with reports written and summary displayed.
+  pub max_failures: Option<usize>,
+  /// Optional expected number of executed test cases, the run fails when a different number of test cases is executed.
+  pub expected_tests: Option<usize>,
+  /// Optional path to SQLite database where the history of all runs is stored.
+  pub history_db: Option<String>,
+  /// Metadata of the run, like engine version, git commit or environment name,
+  /// embedded in reports and history database to distinguish results of different engine builds.
+  #[serde(default)]
+  pub run_metadata: BTreeMap<String, String>,
+  /// Optional path to SVG badge file with the percentage of passed test cases.
+  pub badge_file: Option<String>,
+  /// Format of the CSV report.
+  #[serde(default)]
+  pub report_format: ReportFormat,
+  /// Rendering of paths of test files and directories in console output and in all reports.
+  #[serde(default)]
+  pub path_display: PathDisplay,
+  /// Prefix replacing the tests root directory in displayed paths, required when paths are displayed with prefix.
+  pub path_prefix: Option<String>,
+  /// Optional path to file where unified diffs of mismatched values are written.
+  pub diff_file: Option<String>,
+  /// Optional path to directory where artifacts of failed tests are written.
+  pub failure_artifacts_dir: Option<String>,
+  /// Optional path to directory where parameters of evaluation requests of failed tests are written,
+  /// as `<file>/<test id>.json`, building the corpus of failing inputs.
+  pub failures_dir: Option<String>,
+  /// Optional URL of the engine endpoint evaluating multiple invocables in one request.
+  /// When specified, tests from the same test file are sent in batches of evaluation requests,
+  /// and the endpoint responds with the array of results in the same order.
+  pub batch_url: Option<String>,
+  /// Maximal number of evaluation requests sent in one batch.
+  pub batch_size: Option<usize>,
+  /// Optional time budget of every test in milliseconds, including all repeated executions.
+  /// A test exceeding its budget is cancelled and reported as failed.
+  pub test_timeout_ms: Option<u64>,
+  /// Time budgets of tests in milliseconds overriding `test_timeout_ms`, indexed by test label,
+  /// directory name or directory path, labels take precedence over directories.
+  #[serde(default)]
+  pub test_timeouts: BTreeMap<String, u64>,
+  /// Number of executions of each test, tests with inconsistent results are reported as flaky.
+  pub repeat: Option<usize>,
+  /// Flag indicating if test files and test cases within test files are executed in shuffled order.
+  /// Test cases of lazily parsed test files are executed in the order they are defined.
+  #[serde(default)]
+  pub shuffle: bool,
+  /// Seed of the shuffled order, random seed is used when not specified.
+  pub seed: Option<u64>,
+  /// Optional path to JSON file where hashes of test files and models are cached with results of their tests.
+  pub cache_file: Option<String>,
+  /// Flag indicating if only tests from test files changed since the cached run are executed,
+  /// a test file is changed when its content or content of any model it depends on has changed.
+  #[serde(default)]
+  pub changed_only: bool,
+  /// Flag indicating if only tests that have failed in the last run are executed.
+  /// Failed tests are read from the history database when configured, otherwise from the report file.
+  #[serde(default)]
+  pub only_failed: bool,
+  /// Optional path to CSV file where the timing of every request is written.
+  pub timing_file: Option<String>,
+  /// Optional identifier of engine process, whose CPU and memory usage is sampled during the run.
+  pub engine_pid: Option<u32>,
+  /// Optional path to control group (version 2) of engine, whose CPU and memory usage is sampled during the run,
+  /// used when engine process identifier is not specified.
+  pub engine_cgroup: Option<String>,
+  /// Interval between samples of engine resources in milliseconds.
+  pub resource_sampling_interval_ms: Option<u64>,
+  /// Optional path to file where the histogram of request latencies is written in HdrHistogram format (hgrm).
+  pub latency_histogram_file: Option<String>,
+  /// Maximal number of concurrent workers in load test, the number of workers doubles from one up to this number.
+  pub load_max_workers: Option<usize>,
+  /// Duration of each concurrency level of load test in seconds.
+  pub load_level_duration: Option<u64>,
+  /// Optional target latency in milliseconds, when specified the number of workers in load test
+  /// is controlled adaptively, increased while the 95th percentile of latency stays under the target
+  /// and halved when it rises above the target or errors occur.
+  pub load_target_latency_ms: Option<u64>,
+  /// Duration of adaptive load test in seconds.
+  pub load_adaptive_duration: Option<u64>,
+  /// Duration of soak test in seconds.
+  pub soak_duration: Option<u64>,
+  /// Interval in seconds, in which latency and error rate of soak test are measured.
+  pub soak_interval: Option<u64>,
+  /// Number of concurrent workers in soak test.
+  pub soak_workers: Option<usize>,
+  /// Optional URL of engine health check, engine is ready when it responds with success status.
+  pub health_url: Option<String>,
+  /// Maximal time in seconds of waiting for engine readiness and for the first successful evaluation of models.
+  pub cold_start_timeout: Option<u64>,
+  /// Interval in milliseconds between health checks and between evaluation attempts when measuring cold start.
+  pub cold_start_poll_interval_ms: Option<u64>,
+  /// Flag indicating if models and invocables not exercised by any test should be reported.
+  #[serde(default)]
+  pub coverage_report: bool,
+  /// Optional path to CSV report file with results grouped by test labels.
+  pub labels_report_file: Option<String>,
+  /// Optional URL receiving the JSON notification when the run is completed.
+  pub notify_url: Option<String>,
+  /// Optional path to HTML dashboard file with results of all tests.
+  pub dashboard_file: Option<String>,
+  /// Flag indicating if interactive terminal interface is displayed instead of the console output.
+  #[serde(default)]
+  pub tui: bool,
+  /// Flag indicating if console output is limited to plain ASCII, with `+-|` tables and without colors.
+  #[serde(default)]
+  pub ascii: bool,
+  /// Format of the stream of progress events written to standard output,
+  /// the console output is written to standard error when events are emitted.
+  #[serde(default)]
+  pub events_format: EventsFormat,
+  /// Flag indicating if curl commands reproducing evaluation requests of failed tests are printed
+  /// and written to failure artifacts.
+  #[serde(default)]
+  pub curl_commands: bool,
+  /// Flag indicating if computed values should be written back into test files.
+  #[serde(default)]
+  pub write_computed: bool,
+  /// Flag indicating if test files with duplicated test case identifiers or result nodes are reported as failures.
+  #[serde(default)]
+  pub strict: bool,
+  /// Flag indicating if types of untyped values should be inferred from variable types defined in DMN models.
+  #[serde(default)]
+  pub infer_types: bool,
+  /// Size of test files in bytes above which test cases are parsed lazily and executed while parsing.
+  pub streaming_threshold: Option<u64>,
+  /// Size of response bodies in bytes above which responses are deserialized and compared
+  /// in a worker thread, while next requests are sent to engine.
+  pub comparison_worker_threshold: Option<usize>,
+  /// Order of components when comparing actual and expected values.
+  #[serde(default)]
+  pub component_order: ComponentOrder,
+  /// Flag indicating if result nodes without expected value are executed as smoke tests,
+  /// passing when the engine returns a result without errors.
+  #[serde(default)]
+  pub smoke_tests: bool,
+  /// Flag indicating if result nodes without expected value are compared with computed value, when present.
+  #[serde(default)]
+  pub computed_fallback: bool,
+  /// Matching of error messages reported by engine for results with `errorResult="true"`
+  /// with error messages expected in `expectedError` extension element.
+  #[serde(default)]
+  pub error_message_matching: ErrorMessageMatching,
+  /// Aliases of type names with the type names they are replaced with before comparing values,
+  /// like `number: xsd:decimal`.
+  #[serde(default)]
+  pub type_aliases: BTreeMap<String, String>,
+  /// Optional number of fractional digits decimals and doubles are rounded to before comparison,
+  /// not allowed together with `numeric_tolerance` comparator.
+  pub decimal_scale: Option<u32>,
+  /// Rounding mode used when decimals are rounded to configured scale.
+  #[serde(default)]
+  pub rounding: RoundingMode,
+  /// Flag indicating if nil value and empty list are equal, reported as a warning.
+  #[serde(default)]
+  pub nil_equals_empty_list: bool,
+  /// Flag indicating if missing component and nil component are equal, reported as a warning.
+  #[serde(default)]
+  pub missing_component_equals_nil: bool,
+  /// Flag indicating if actual value may contain components not present in expected value.
+  #[serde(default)]
+  pub components_subset: bool,
+  /// Comparators applied before configured comparators only for tests with specified identifiers,
+  /// in form `name:test_id`, where the name is the test file name, its directory name or the directory path.
+  #[serde(default)]
+  pub comparison_overrides: BTreeMap<String, Vec<ComparatorConfig>>,
+  /// Unicode normalization form applied to strings before comparison.
+  #[serde(default)]
+  pub unicode_normalization: UnicodeNormalization,
+  /// Normalization steps applied in order to both expected and actual values before comparison.
+  #[serde(default)]
+  pub canonicalization: Vec<CanonicalizationStep>,
+  /// Comparators applied in order before strict comparison of values.
+  #[serde(default)]
+  pub comparators: Vec<ComparatorConfig>,
+}
+
+/// Rounding modes of decimal numbers, with the same meaning as in `java.math.RoundingMode`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+  /// Rounds towards the nearest neighbor, or towards the even neighbor when both neighbors are equidistant.
+  #[default]
+  HalfEven,
+  /// Rounds towards the nearest neighbor, or away from zero when both neighbors are equidistant.
+  HalfUp,
+  /// Rounds towards the nearest neighbor, or towards zero when both neighbors are equidistant.
+  HalfDown,
+  /// Rounds away from zero.
+  Up,
+  /// Rounds towards zero.
+  Down,
+  /// Rounds towards positive infinity.
+  Ceiling,
+  /// Rounds towards negative infinity.
+  Floor,
+}
+
+/// Comparators of values selectable in configuration, like:
+/// ```yaml
+/// comparators:
+///   - type: numeric_tolerance
+///     tolerance: 0.000001
+///   - type: list_as_set
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ComparatorConfig {
+  /// Decimals and doubles are equal when they differ by no more than specified tolerance.
+  NumericTolerance {
+    /// Maximal absolute difference of equal numbers.
+    tolerance: f64,
+  },
+  /// Durations are compared by their value.
+  CanonicalDuration,
+  /// Decimals and doubles are compared with each other by their numeric value, recording a warning.
+  NumericCoercion,
+  /// Lists are compared as multisets, regardless of the order of items but respecting their multiplicity.
+  #[serde(alias = "list_as_multiset")]
+  ListAsSet,
+  /// Values are compared by WASM plugin, see [WasmComparator](crate::plugin::WasmComparator) for the required exports.
+  Wasm {
+    /// Path to WASM module file.
+    path: String,
+  },
+  /// Strings are compared after optional normalization.
+  Strings {
+    /// Flag indicating if strings are compared case-insensitively.
+    #[serde(default)]
+    ignore_case: bool,
+    /// Flag indicating if leading and trailing whitespace is ignored.
+    #[serde(default)]
+    trim: bool,
+  },
+}
+
+/// Unicode normalization forms of strings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnicodeNormalization {
+  /// Strings are compared as returned by engine.
+  None,
+  /// Canonical composition, so composed and decomposed accents are equal.
+  #[default]
+  Nfc,
+  /// Compatibility composition, additionally compatibility characters like ligatures are equal to their equivalents.
+  Nfkc,
+}
+
+/// Normalization steps of values selectable in configuration, like:
+/// ```yaml
+/// canonicalization:
+///   - type: trim_strings
+///   - type: round_decimals
+///     digits: 4
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CanonicalizationStep {
+  /// Leading and trailing whitespace is removed from strings.
+  TrimStrings,
+  /// Durations are rewritten in canonical form, like `PT90M` into `PT1H30M`.
+  CanonicalDurations,
+  /// Date and time values and time values with time offset are converted to UTC.
+  NormalizeTimezone,
+  /// Decimals and doubles are rounded to specified number of fractional digits using configured rounding mode.
+  RoundDecimals {
+    /// Number of fractional digits.
+    digits: u32,
+  },
+}
+
+/// Matching of error messages reported by engine with expected error messages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorMessageMatching {
+  /// Error message must contain the expected message.
+  #[default]
+  Substring,
+  /// Error message must match the expected regular expression.
+  Regex,
+}
+
+/// Rendering of paths of test files and directories.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathDisplay {
+  /// Paths are displayed relative to the tests root directory.
+  #[default]
+  Relative,
+  /// Paths are displayed as full paths, including the tests root directory.
+  Absolute,
+  /// Paths are displayed relative to the tests root directory, preceded by configured prefix.
+  Prefixed,
+}
+
+/// Formats of the stream of progress events.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventsFormat {
+  /// Events are not emitted.
+  #[default]
+  None,
+  /// Every event is written as a single line with JSON object.
+  Jsonl,
+}
+
+/// Order of components when comparing values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentOrder {
+  /// Components are sorted by name before comparison, so the order returned by engine does not matter.
+  #[default]
+  Sorted,
+  /// Components are compared in document order, components with duplicated names are allowed.
+  Document,
+}
+
+/// Format of the CSV report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportFormat {
+  /// Delimiter separating the fields.
+  pub delimiter: String,
+  /// Flag indicating if fields are enclosed in double quotes.
+  pub quoted: bool,
+  /// Flag indicating if the first row contains column names.
+  pub header: bool,
+  /// Columns in the order they appear in the report.
+  pub columns: Vec<ReportColumn>,
+}
+
+impl Default for ReportFormat {
+  /// Default format is compatible with the format of previous versions, with the tests root directory appended.
+  fn default() -> Self {
+    Self {
+      delimiter: ",".to_string(),
+      quoted: true,
+      header: false,
+      columns: vec![
+        ReportColumn::Directory,
+        ReportColumn::File,
+        ReportColumn::TestId,
+        ReportColumn::Result,
+        ReportColumn::Remarks,
+        ReportColumn::Root,
+      ],
+    }
+  }
+}
+
+/// Columns available in the CSV report.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportColumn {
+  /// Directory of the test file.
+  Directory,
+  /// Test file name without extension.
+  File,
+  /// Identifier of the test case.
+  TestCaseId,
+  /// Identifier of the test.
+  TestId,
+  /// Name of the test case.
+  TestCaseName,
+  /// Description of the test case.
+  Description,
+  /// Test result.
+  Result,
+  /// Failure remarks.
+  Remarks,
+  /// Evaluation duration in microseconds.
+  Duration,
+  /// Failure category.
+  Category,
+  /// Failure class, the class of the difference for mismatches.
+  FailureClass,
+  /// Version of the DMN specification of the test file.
+  SpecVersion,
+  /// Metadata of the run, as comma separated `key=value` pairs.
+  RunMetadata,
+  /// Tests root directory containing the test file.
+  Root,
+}
+
+impl ReportColumn {
+  /// Returns the column name used in header row.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Directory => "directory",
+      Self::File => "file",
+      Self::TestCaseId => "test_case_id",
+      Self::TestId => "test_id",
+      Self::TestCaseName => "test_case_name",
+      Self::Description => "description",
+      Self::Result => "result",
+      Self::Remarks => "remarks",
+      Self::Duration => "duration",
+      Self::Category => "category",
+      Self::FailureClass => "failure_class",
+      Self::SpecVersion => "spec_version",
+      Self::RunMetadata => "run_metadata",
+      Self::Root => "root",
+    }
+  }
+}
+
+/// Deserializes a single string or a list of strings into a list of strings.
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+  }
+  let value = OneOrMany::deserialize(deserializer).map_err(|_| de::Error::custom("test_cases_dir_path: expected a directory path or a list of directory paths"))?;
+  Ok(match value {
+    OneOrMany::One(value) => vec![value],
+    OneOrMany::Many(values) => values,
+  })
+}
+
+/// Reads configuration parameters from specified file.
+/// When the profile is given, parameters defined in the profile override parameters from the base section.
+/// Placeholders of environment variables in string values are expanded, see [interpolate].
+/// Unknown keys are reported with the most similar known key names, when there are any.
+pub fn get(cfg_file_name: &str, profile: Option<&str>) -> Result<ConfigurationParams, String> {
+  let file_content = std::fs::read_to_string(cfg_file_name).map_err(|e| format!("reading configuration file '{}' failed with reason: {}", cfg_file_name, e))?;
+  let invalid = |reason: String| format!("invalid configuration file '{}':\n  {}", cfg_file_name, reason);
+  let mut value = serde_yaml::from_str::<serde_yaml::Value>(&file_content).map_err(|e| invalid(e.to_string()))?;
+  let profiles = value.as_mapping_mut().and_then(|base| base.remove(PROFILES_KEY)).unwrap_or_default();
+  let mut unknown_keys = config_unknown_keys(&value, "");
+  for (name, profile) in profiles.as_mapping().into_iter().flatten() {
+    let name = name.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", name));
+    unknown_keys.append(&mut config_unknown_keys(profile, &format!("{}.{}.", PROFILES_KEY, name)));
+  }
+  if !unknown_keys.is_empty() {
+    return Err(invalid(unknown_keys.join("\n  ")));
+  }
+  if let Some(profile) = profile {
+    let profile_value = profiles.get(profile).ok_or_else(|| {
+      let names = profiles.as_mapping().into_iter().flatten().filter_map(|(name, _)| name.as_str()).collect::<Vec<&str>>();
+      match names.is_empty() {
+        true => format!("profile '{}' not found in configuration file '{}'", profile, cfg_file_name),
+        false => format!(
+          "profile '{}' not found in configuration file '{}', available profiles: {}",
+          profile,
+          cfg_file_name,
+          names.join(", ")
+        ),
+      }
+    })?;
+    merge(&mut value, profile_value.clone());
+  }
+  let mut undefined = vec![];
+  let interpolated = interpolate(&mut value, "", &mut undefined);
+  if !undefined.is_empty() {
+    return Err(invalid(undefined.join("\n  ")));
+  }
+  if profile.is_none() && !interpolated {
+    // unchanged file is deserialized directly, to report locations of invalid values
+    return serde_yaml::from_str(&file_content).map_err(|e| invalid(e.to_string()));
+  }
+  // expanded values are deserialized from text, so numbers expanded from placeholders are accepted also in string parameters
+  let content = serde_yaml::to_string(&value).map_err(|e| invalid(e.to_string()))?;
+  serde_yaml::from_str(&content).map_err(|e| {
+    // locations in expanded text do not correspond to locations in configuration file
+    let reason = e.to_string();
+    let reason = match e.location() {
+      Some(location) => reason.trim_end_matches(&format!(" at line {} column {}", location.line(), location.column())).to_string(),
+      None => reason,
+    };
+    match profile {
+      Some(profile) => invalid(format!("profile '{}': {}", profile, reason)),
+      None => invalid(reason),
+    }
+  })
+}
+
+/// Parameters containing regular expressions, where placeholders of environment variables are not expanded.
+const REGEX_KEYS: [&str; 4] = ["file_search_pattern", "test_case_name_pattern", "include", "exclude"];
+
+/// Pattern of environment variable placeholder `${NAME}` or `${NAME:-default}`, or escaped `$${`, compiled once.
+static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+
+/// Expands placeholders of environment variables in string values, in form `${NAME}`
+/// or `${NAME:-default}`, where the default is used when the variable is not defined.
+/// Literal `${` is written as `$${`. Parameters containing regular expressions are not expanded, see [REGEX_KEYS].
+/// Value consisting of a single placeholder becomes a number or boolean when the expanded text is one.
+/// Messages about undefined variables without default are appended to `undefined`.
+/// Returns `true` when any value was changed.
+fn interpolate(value: &mut serde_yaml::Value, path: &str, undefined: &mut Vec<String>) -> bool {
+  match value {
+    serde_yaml::Value::Mapping(mapping) => {
+      let mut interpolated = false;
+      for (key, value) in mapping.iter_mut() {
+        if path.is_empty() && key.as_str().is_some_and(|key| REGEX_KEYS.contains(&key)) {
+          continue;
+        }
+        let key = key.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", key));
+        let path = if path.is_empty() { key } else { format!("{}.{}", path, key) };
+        interpolated |= interpolate(value, &path, undefined);
+      }
+      interpolated
+    }
+    serde_yaml::Value::Sequence(sequence) => {
+      let mut interpolated = false;
+      for (index, value) in sequence.iter_mut().enumerate() {
+        interpolated |= interpolate(value, &format!("{}[{}]", path, index), undefined);
+      }
+      interpolated
+    }
+    serde_yaml::Value::String(text) => {
+      let placeholder = PLACEHOLDER.get_or_init(|| Regex::new(r"\$\$\{|\$\{([A-Za-z_][A-Za-z0-9_]*)(:-([^}]*))?}").unwrap());
+      let Some(first) = placeholder.find(text) else {
+        return false;
+      };
+      let single = first.range() == (0..text.len()) && first.as_str() != "$${";
+      let expanded = placeholder
+        .replace_all(text, |captures: &regex::Captures| match (captures.get(1), captures.get(3)) {
+          (None, _) => "${".to_string(),
+          (Some(name), default) => match (env::var(name.as_str()), default) {
+            (Ok(value), _) => value,
+            (Err(_), Some(default)) => default.as_str().to_string(),
+            (Err(_), None) => {
+              undefined.push(format!("{}: environment variable '{}' is not defined", path, name.as_str()));
+              String::new()
+            }
+          },
+        })
+        .to_string();
+      *value = match serde_yaml::from_str::<serde_yaml::Value>(&expanded) {
+        Ok(scalar @ (serde_yaml::Value::Number(_) | serde_yaml::Value::Bool(_))) if single => scalar,
+        _ => serde_yaml::Value::String(expanded),
+      };
+      true
+    }
+    _ => false,
+  }
+}
+
+/// Merges the overriding value into the base value, mappings are merged recursively, other values are replaced.
+fn merge(base: &mut serde_yaml::Value, overriding: serde_yaml::Value) {
+  match (base, overriding) {
+    (serde_yaml::Value::Mapping(base), serde_yaml::Value::Mapping(overriding)) => {
+      for (key, value) in overriding {
+        match base.get_mut(&key) {
+          Some(base_value) => merge(base_value, value),
+          None => {
+            base.insert(key, value);
+          }
+        }
+      }
+    }
+    (base, overriding) => *base = overriding,
+  }
+}
+
+/// Returns messages describing unknown keys of the configuration section,
+/// including keys of report format, comparators and canonicalization steps.
+fn config_unknown_keys(value: &serde_yaml::Value, prefix: &str) -> Vec<String> {
+  let mut messages = unknown_keys(value, field_names::<ConfigurationParams>(), prefix);
+  if let Some(report_format) = value.get("report_format") {
+    messages.append(&mut unknown_keys(report_format, field_names::<ReportFormat>(), &format!("{}report_format.", prefix)));
+  }
+  for (key, variant_fields) in [("comparators", COMPARATOR_FIELDS), ("canonicalization", CANONICALIZATION_STEP_FIELDS)] {
+    messages.append(&mut tagged_list_unknown_keys(value.get(key), variant_fields, &format!("{}{}", prefix, key)));
+  }
+  for (identifier, comparators) in value.get("comparison_overrides").and_then(serde_yaml::Value::as_mapping).into_iter().flatten() {
+    let identifier = identifier.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", identifier));
+    let prefix = format!("{}comparison_overrides.{}", prefix, identifier);
+    messages.append(&mut tagged_list_unknown_keys(Some(comparators), COMPARATOR_FIELDS, &prefix));
+  }
+  messages
+}
+
+/// Fields of comparators, in addition to the `type` tag, see [ComparatorConfig].
+const COMPARATOR_FIELDS: &[(&str, &[&str])] = &[("numeric_tolerance", &["tolerance"]), ("wasm", &["path"]), ("strings", &["ignore_case", "trim"])];
+
+/// Fields of canonicalization steps, in addition to the `type` tag, see [CanonicalizationStep].
+const CANONICALIZATION_STEP_FIELDS: &[(&str, &[&str])] = &[("round_decimals", &["digits"])];
+
+/// Returns messages describing unknown keys of items in the list of values tagged with `type`,
+/// known keys of every item are the tag and fields of its type.
+fn tagged_list_unknown_keys(list: Option<&serde_yaml::Value>, variant_fields: &[(&str, &[&str])], prefix: &str) -> Vec<String> {
+  let mut messages = vec![];
+  for (index, item) in list.and_then(serde_yaml::Value::as_sequence).into_iter().flatten().enumerate() {
+    let typ = item.get("type").and_then(serde_yaml::Value::as_str).unwrap_or_default();
+    let fields = variant_fields.iter().find(|(name, _)| *name == typ).map_or(&[][..], |(_, fields)| *fields);
+    let known_keys = ["type"].into_iter().chain(fields.iter().copied()).collect::<Vec<&str>>();
+    messages.append(&mut unknown_keys(item, &known_keys, &format!("{}[{}].", prefix, index)));
+  }
+  messages
 }
 
-pub fn get() -> ConfigurationParams {
-  let args: Vec<String> = std::env::args().collect();
-  let cfg_file_name = if args.len() == 2 { args[1].as_str() } else { "config.yml" };
-  let err_read = format!("reading configuration file '{}' failed", cfg_file_name);
-  let file_content = std::fs::read_to_string(cfg_file_name).expect(&err_read);
-  let err_parse = format!("parsing configuration file '{}' failed", cfg_file_name);
-  serde_yaml::from_str(&file_content).expect(&err_parse)
+/// Parameters validated for a command, every scope includes parameters of the preceding scopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Validation {
+  /// No parameters are validated, the command uses neither configured tests nor engine.
+  Nothing,
+  /// Parameters used for discovering, selecting and displaying test files.
+  TestFiles,
+  /// Parameters used for sending requests to engine.
+  Requests,
+  /// All parameters, used for executing tests and writing reports.
+  All,
 }
+
+/// Checks values of configuration parameters within specified scope, returns messages describing all invalid parameters.
+pub fn validate(config: &ConfigurationParams, validation: Validation) -> Vec<String> {
+  let mut errors = vec![];
+  if validation >= Validation::TestFiles {
+    validate_test_files(config, &mut errors);
+  }
+  if validation >= Validation::Requests {
+    validate_requests(config, &mut errors);
+  }
+  if validation >= Validation::All {
+    validate_execution(config, &mut errors);
+  }
+  errors
+}
+
+/// Checks parameters used for discovering, selecting and displaying test files.
+fn validate_test_files(config: &ConfigurationParams, errors: &mut Vec<String>) {
+  if config.test_cases_dir_paths.is_empty() {
+    errors.push("test_cases_dir_path: no test cases directory specified".to_string());
+  }
+  for test_cases_dir_path in &config.test_cases_dir_paths {
+    if !Path::new(test_cases_dir_path).is_dir() {
+      errors.push(format!("test_cases_dir_path: directory '{}' does not exist", test_cases_dir_path));
+    }
+  }
+  if let Err(e) = Regex::new(&config.file_search_pattern) {
+    errors.push(format!("file_search_pattern: invalid regular expression, {}", e));
+  }
+  if let Some(Err(e)) = config.test_case_name_pattern.as_deref().map(Regex::new) {
+    errors.push(format!("test_case_name_pattern: invalid regular expression, {}", e));
+  }
+  let patterns = config
+    .include
+    .iter()
+    .map(|pattern| ("include", pattern.clone()))
+    .chain(config.exclude.iter().map(|pattern| ("exclude", pattern.clone())))
+    .chain(config.file_search_globs.iter().map(|glob| ("file_search_globs", format!("glob:{}", glob))));
+  for (key, pattern) in patterns {
+    if let Err(reason) = selection::check_pattern(&pattern) {
+      errors.push(format!("{}: {}", key, reason));
+    }
+  }
+  for level in &config.compliance_levels {
+    if !matches!(level, 2 | 3) {
+      errors.push(format!("compliance_levels: invalid compliance level '{}', expected 2 or 3", level));
+    }
+  }
+  if config.path_display == PathDisplay::Prefixed && config.path_prefix.is_none() {
+    errors.push("path_prefix: prefix is required when paths are displayed with prefix".to_string());
+  }
+}
+
+/// Checks parameters used for sending requests to engine.
+fn validate_requests(config: &ConfigurationParams, errors: &mut Vec<String>) {
+  let urls = [
+    ("evaluate_url", Some(&config.evaluate_url)),
+    ("batch_url", config.batch_url.as_ref()),
+    ("health_url", config.health_url.as_ref()),
+    ("notify_url", config.notify_url.as_ref()),
+  ];
+  for (key, url) in urls {
+    if let Some(url) = url {
+      match url::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "http" || parsed.scheme() == "https" => {}
+        Ok(parsed) => errors.push(format!("{}: invalid URL '{}', unsupported scheme '{}'", key, url, parsed.scheme())),
+        Err(e) => errors.push(format!("{}: invalid URL '{}', {}", key, url, e)),
+      }
+    }
+  }
+  if let Some(address) = &config.endpoint_address {
+    if address.parse::<SocketAddr>().is_ok() {
+      errors.push(format!("endpoint_address: port is not allowed in '{}', the port of evaluate URL is used", address));
+    } else if address.parse::<IpAddr>().is_err() {
+      errors.push(format!("endpoint_address: invalid IP address '{}'", address));
+    }
+  }
+  if config.soak_interval == Some(0) {
+    errors.push("soak_interval: measurement interval must be greater than zero".to_string());
+  }
+}
+
+/// Checks parameters used for executing tests and writing reports.
+fn validate_execution(config: &ConfigurationParams, errors: &mut Vec<String>) {
+  if let Some(engine_cgroup) = &config.engine_cgroup {
+    if !Path::new(engine_cgroup).is_dir() {
+      errors.push(format!("engine_cgroup: directory '{}' does not exist", engine_cgroup));
+    }
+  }
+  if config.test_timeout_ms == Some(0) {
+    errors.push("test_timeout_ms: time budget must be greater than zero".to_string());
+  }
+  for (name, _) in config.test_timeouts.iter().filter(|(_, timeout)| **timeout == 0) {
+    errors.push(format!("test_timeouts: time budget of '{}' must be greater than zero", name));
+  }
+  if config.max_failures == Some(0) {
+    errors.push("max_failures: maximal number of failures must be greater than zero".to_string());
+  }
+  if config.ascii && config.tui {
+    errors.push("tui: interactive terminal interface is not available in ASCII-only mode".to_string());
+  }
+  for path in config.comparators.iter().filter_map(wasm_path) {
+    if let Err(reason) = WasmComparator::new(path) {
+      errors.push(format!("comparators: {}", reason));
+    }
+  }
+  for (identifier, overrides) in &config.comparison_overrides {
+    for path in overrides.iter().filter_map(wasm_path) {
+      if let Err(reason) = WasmComparator::new(path) {
+        errors.push(format!("comparison_overrides: {}: {}", identifier, reason));
+      }
+    }
+  }
+  if config.decimal_scale.is_some() && config.comparators.iter().any(|comparator| matches!(comparator, ComparatorConfig::NumericTolerance { .. })) {
+    errors.push("decimal_scale: rounding to decimal scale can not be combined with numeric_tolerance comparator".to_string());
+  }
+}
+
+/// Returns the path of WASM plugin when the comparator is implemented as WASM plugin.
+fn wasm_path(comparator: &ComparatorConfig) -> Option<&str> {
+  match comparator {
+    ComparatorConfig::Wasm { path } => Some(path),
+    _ => None,
+  }
+}
+
+/// Returns messages describing keys of the mapping not found in known keys,
+/// with suggestions of the most similar known keys.
+fn unknown_keys(value: &serde_yaml::Value, known_keys: &[&str], prefix: &str) -> Vec<String> {
+  let Some(mapping) = value.as_mapping() else {
+    return vec![];
+  };
+  let mut messages = vec![];
+  for key in mapping.keys() {
+    let key = key.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", key));
+    if known_keys.contains(&key.as_str()) {
+      continue;
+    }
+    let suggestion = known_keys
+      .iter()
+      .map(|known_key| (edit_distance(&key, known_key), known_key))
+      .filter(|(distance, _)| *distance <= (key.chars().count() / 3).max(1))
+      .min();
+    match suggestion {
+      Some((_, known_key)) => messages.push(format!("unknown key '{}{}', did you mean '{}{}'?", prefix, key, prefix, known_key)),
+      None => messages.push(format!("unknown key '{}{}'", prefix, key)),
+    }
+  }
+  messages
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b = b.chars().collect::<Vec<char>>();
+  let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+  for (i, ch_a) in a.chars().enumerate() {
+    let mut current = vec![i + 1];
+    for (j, ch_b) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(ch_a != *ch_b);
+      current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
+/// Returns names of fields of deserialized structure, as known to its derived deserializer.
+fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+  let mut fields: &'static [&'static str] = &[];
+  let _ = T::deserialize(FieldNames(&mut fields));
+  fields
+}
+
+/// Deserializer capturing names of fields of deserialized structure, deserialization always fails.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+  type Error = de::value::Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+    Err(de::Error::custom("only structures are supported"))
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, fields: &'static [&'static str], _: V) -> Result<V::Value, Self::Error> {
+    *self.0 = fields;
+    Err(de::Error::custom("field names captured"))
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+    newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+  }
+}
+
+/// Returns the path of the first existing configuration file from standard locations:
+/// `./config.yml`, `./dmntk-runner.yml` and `$XDG_CONFIG_HOME/dmntk-runner/config.yml`
+/// (with `~/.config` used when `XDG_CONFIG_HOME` is not set).
+pub fn discover() -> Result<String, String> {
+  let mut locations = vec![PathBuf::from(DEFAULT_CONFIG_FILE), PathBuf::from(RUNNER_CONFIG_FILE)];
+  let config_home = env::var_os("XDG_CONFIG_HOME")
+    .filter(|config_home| !config_home.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+  if let Some(config_home) = config_home {
+    locations.push(config_home.join(CONFIG_DIR).join(DEFAULT_CONFIG_FILE));
+  }
+  locations
+    .iter()
+    .find(|location| location.is_file())
+    .map(|location| location.display().to_string())
+    .ok_or_else(|| {
+      let searched = locations.iter().map(|location| location.display().to_string()).collect::<Vec<String>>();
+      format!("configuration file not found, searched: {}", searched.join(", "))
+    })
+}
+
+/// Writes the commented configuration file with default parameters, existing file is never overwritten.
+pub fn init(file_name: &str) -> Result<(), String> {
+  if Path::new(file_name).exists() {
+    return Err(format!("configuration file '{}' already exists", file_name));
+  }
+  std::fs::write(file_name, TEMPLATE).map_err(|e| format!("writing configuration file '{}' failed with reason: {}", file_name, e))
+}
+
+/// Template of the configuration file generated by `init` command.
+const TEMPLATE: &str = r#"# Configuration of DMNTK test runner.
+
+# Path to directory containing test cases, relative to current directory.
+# Multiple directories may be specified as a list, all of them are tested in one run.
+test_cases_dir_path: ../tck/TestCases
+# test_cases_dir_path:
+#   - ../tck/TestCases
+#   - ../proprietary/TestCases
+
+# Pattern (regular expression) for matching test file names, empty pattern matches all files.
+file_search_pattern: ""
+
+# Patterns of included and excluded test files, relative to test cases directory.
+# Patterns prefixed with 'glob:' are globs, other patterns are regular expressions.
+# include:
+#   - "compliance-level-3/.*"
+#   - "glob:non-compliant/**/*.xml"
+# exclude:
+#   - ".*\\b0092-feel-lambda\\b.*"
+
+# URL to service where model definitions are evaluated.
+# String values may contain environment variables, like ${ENGINE_URL:-http://127.0.0.1:22022/tck},
+# literal '${' is written as '$${', regular expressions in patterns are not expanded.
+evaluate_url: http://127.0.0.1:22022/tck
+
+# Path to report file with results of all tests.
+report_file: output/results.csv
+
+# Path to report file for TCK.
+tck_report_file: output/tck_results.csv
+
+# Flag indicating if testing should immediately stop on failure.
+stop_on_failure: false
+
+# Number of executions of each test, tests with inconsistent results are reported as flaky.
+# repeat: 1
+
+# Time budget of every test in milliseconds.
+# test_timeout_ms: 5000
+
+# Rendering of paths in console output and reports: relative (default), absolute or prefixed.
+# path_display: prefixed
+# path_prefix: tck
+
+# Path to file where unified diffs of mismatched values are written.
+# diff_file: output/diff.txt
+
+# Path to CSV file where the timing of every request is written.
+# timing_file: output/timing.csv
+
+# Path to HTML dashboard file with results of all tests.
+# dashboard_file: output/dashboard.html
+
+# Path to SQLite database where the history of all runs is stored.
+# history_db: output/history.db
+
+# Flag indicating if types of untyped values are inferred from variable types defined in DMN models.
+# infer_types: false
+
+# Named profiles selected with --profile option, overriding parameters defined above.
+# profiles:
+#   ci:
+#     evaluate_url: http://dmntk:22022/tck
+#     stop_on_failure: true
+"#;
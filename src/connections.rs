@@ -0,0 +1,186 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Connections to engine and their metrics
+
+use crate::config::ConfigurationParams;
+use reqwest::blocking::{Client, ClientBuilder};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Number of connections opened to engine and the time spent in connection setup,
+/// shared by all connections established by the client.
+#[derive(Default)]
+pub struct ConnectionMetrics {
+  /// Number of successfully opened connections.
+  opened: AtomicUsize,
+  /// Number of connections that could not be opened.
+  failed: AtomicUsize,
+  /// Total time spent in connection setup in nanoseconds, including failed attempts.
+  setup_time: AtomicU64,
+}
+
+impl ConnectionMetrics {
+  /// Returns the number of successfully opened connections.
+  pub fn opened(&self) -> usize {
+    self.opened.load(Ordering::Relaxed)
+  }
+
+  /// Returns the number of connections that could not be opened.
+  pub fn failed(&self) -> usize {
+    self.failed.load(Ordering::Relaxed)
+  }
+
+  /// Returns the total time spent in connection setup.
+  pub fn setup_time(&self) -> Duration {
+    Duration::from_nanos(self.setup_time.load(Ordering::Relaxed))
+  }
+
+  /// Clears all metrics, connections opened before are counted as reused.
+  pub fn reset(&self) {
+    self.opened.store(0, Ordering::Relaxed);
+    self.failed.store(0, Ordering::Relaxed);
+    self.setup_time.store(0, Ordering::Relaxed);
+  }
+
+  /// Records the connection attempt.
+  fn record(&self, duration: Duration, opened: bool) {
+    if opened {
+      self.opened.fetch_add(1, Ordering::Relaxed);
+    } else {
+      self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+    self.setup_time.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+  }
+}
+
+/// Creates the client recording the metrics of all connections it opens.
+pub fn client(config: &ConfigurationParams, metrics: &Arc<ConnectionMetrics>) -> Client {
+  builder(config)
+    .connector_layer(MetricsLayer { metrics: Arc::clone(metrics) })
+    .build()
+    .unwrap_or_else(|e| panic!("building HTTP client failed with reason: {}", e))
+}
+
+/// Creates the client sending requests to engine without collecting connection metrics.
+pub fn plain_client(config: &ConfigurationParams) -> Client {
+  builder(config).build().unwrap_or_else(|e| panic!("building HTTP client failed with reason: {}", e))
+}
+
+/// Returns the builder of the client connecting to engine at the address or unix socket configured,
+/// instead of the address the host of evaluate URL resolves to.
+fn builder(config: &ConfigurationParams) -> ClientBuilder {
+  let builder = Client::builder();
+  if let Some(socket_path) = &config.endpoint_socket {
+    return unix_socket(builder, socket_path);
+  }
+  let Some(address) = &config.endpoint_address else {
+    return builder;
+  };
+  let url = url::Url::parse(&config.evaluate_url).unwrap_or_else(|e| panic!("parsing evaluate URL failed with reason: {}", e));
+  let Some(host) = url.host_str() else {
+    return builder;
+  };
+  // the port of resolved address is ignored, the port from URL is always used
+  let ip = address
+    .parse::<IpAddr>()
+    .unwrap_or_else(|e| panic!("parsing endpoint address '{}' failed with reason: {}", address, e));
+  builder.resolve(host, SocketAddr::new(ip, 0))
+}
+
+/// Connects the client to engine listening on unix domain socket.
+#[cfg(unix)]
+fn unix_socket(builder: ClientBuilder, socket_path: &str) -> ClientBuilder {
+  builder.unix_socket(socket_path.to_string())
+}
+
+/// Connects the client to engine listening on unix domain socket.
+#[cfg(not(unix))]
+fn unix_socket(_: ClientBuilder, _: &str) -> ClientBuilder {
+  panic!("unix domain sockets are not supported on this platform")
+}
+
+/// Layer wrapping the connector of the client with [MetricsService].
+#[derive(Clone)]
+struct MetricsLayer {
+  metrics: Arc<ConnectionMetrics>,
+}
+
+impl<S> Layer<S> for MetricsLayer {
+  type Service = MetricsService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    MetricsService {
+      inner,
+      metrics: Arc::clone(&self.metrics),
+    }
+  }
+}
+
+/// Connector service measuring the time of establishing every connection.
+#[derive(Clone)]
+struct MetricsService<S> {
+  inner: S,
+  metrics: Arc<ConnectionMetrics>,
+}
+
+impl<S, R> Service<R> for MetricsService<S>
+where
+  S: Service<R>,
+  S::Future: Send + 'static,
+{
+  type Response = S::Response;
+  type Error = S::Error;
+  type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, request: R) -> Self::Future {
+    let metrics = Arc::clone(&self.metrics);
+    let start_time = Instant::now();
+    let connecting = self.inner.call(request);
+    Box::pin(async move {
+      let result = connecting.await;
+      metrics.record(start_time.elapsed(), result.is_ok());
+      result
+    })
+  }
+}
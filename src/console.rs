@@ -0,0 +1,143 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Console output
+//!
+//! Human-readable console output is printed with `out!` and `outln!` macros.
+//! In ASCII-only mode the output is transliterated to plain ASCII: box-drawing characters
+//! are replaced with `+-|`, colors are removed and other characters are escaped.
+//! Machine-readable output, like JSON or CSV listings, is printed directly to standard output.
+//! The console output may be redirected to standard error or suppressed, when standard output
+//! is reserved for the stream of events or for the interactive interface.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Prints human-readable text to the console, transliterated to plain ASCII in ASCII-only mode.
+macro_rules! out {
+  ($($arg:tt)*) => {
+    $crate::console::print(format_args!($($arg)*))
+  };
+}
+
+/// Prints human-readable text with a newline to the console, transliterated to plain ASCII in ASCII-only mode.
+macro_rules! outln {
+  () => {
+    out!("\n")
+  };
+  ($($arg:tt)*) => {
+    out!("{}\n", format_args!($($arg)*))
+  };
+}
+
+/// Flag indicating if console output is transliterated to plain ASCII.
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Enables ASCII-only mode of console output.
+pub fn enable_ascii() {
+  ASCII.store(true, Ordering::Relaxed);
+}
+
+/// Returns `true` when ASCII-only mode of console output is enabled.
+pub fn is_ascii() -> bool {
+  ASCII.load(Ordering::Relaxed)
+}
+
+/// Destination of the console output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+  /// Console output is printed to standard output.
+  Stdout,
+  /// Console output is printed to standard error.
+  Stderr,
+  /// Console output is suppressed.
+  Null,
+}
+
+/// Current destination of the console output.
+static TARGET: AtomicU8 = AtomicU8::new(Target::Stdout as u8);
+
+/// Redirects the console output to specified destination, returns the previous destination.
+pub fn redirect(target: Target) -> Target {
+  match TARGET.swap(target as u8, Ordering::SeqCst) {
+    previous if previous == Target::Stderr as u8 => Target::Stderr,
+    previous if previous == Target::Null as u8 => Target::Null,
+    _ => Target::Stdout,
+  }
+}
+
+/// Prints formatted text to the console, transliterated to plain ASCII when ASCII-only mode is enabled.
+pub fn print(args: fmt::Arguments) {
+  let target = TARGET.load(Ordering::SeqCst);
+  if target == Target::Null as u8 {
+    return;
+  }
+  let text = match is_ascii() {
+    true => transliterate(&args.to_string()),
+    false => args.to_string(),
+  };
+  if target == Target::Stderr as u8 {
+    eprint!("{}", text);
+  } else {
+    print!("{}", text);
+  }
+}
+
+/// Returns the text with box-drawing characters replaced with `+-|` and without ANSI escape sequences.
+/// Other characters outside ASCII are replaced with their escaped Unicode code points,
+/// except ellipsis and micro sign, replaced with single characters to keep the alignment of columns.
+pub fn transliterate(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(ch) = chars.next() {
+    match ch {
+      '\u{1b}' => {
+        // escape sequences end with the first character in range '@'..='~' after the opening bracket
+        if chars.next() == Some('[') {
+          for ch in chars.by_ref() {
+            if ('@'..='~').contains(&ch) {
+              break;
+            }
+          }
+        }
+      }
+      '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => result.push('+'),
+      '─' => result.push('-'),
+      '│' => result.push('|'),
+      '…' => result.push('~'),
+      'µ' => result.push('u'),
+      ch if ch.is_ascii() => result.push(ch),
+      ch => result.push_str(&ch.escape_unicode().to_string()),
+    }
+  }
+  result
+}
@@ -32,18 +32,54 @@
 
 //! # Context for testing process
 
+use crate::cache::Cache;
+use crate::canonical::Canonicalization;
+use crate::compare::{Comparison, MismatchClass};
+use crate::config::{ComponentOrder, ConfigurationParams, ErrorMessageMatching, PathDisplay};
+use crate::coverage::{Coverage, INVOCABLE_ELEMENTS};
+use crate::curl::Curl;
+use crate::dashboard::Dashboard;
+use crate::diff::unified;
+use crate::events::{Event, EventStream};
+use crate::histogram::LatencyHistogram;
+use crate::history::History;
+use crate::humanize;
+use crate::imports::{ImportGraph, ModelImport};
+use crate::interrupt;
+use crate::params::EvaluateParams;
+use crate::report;
+use crate::report::{ReportRow, ReportWriter};
+use crate::selection::FileSelection;
+use crate::shuffle::Shuffle;
+use crate::tui::Tui;
+use crate::types::{variable_types, InferredType};
 use crate::{COLOR_BRIGHT_WHITE, COLOR_GREEN, COLOR_RED, COLOR_RESET, COLOR_YELLOW};
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
-use std::{fmt, fs, process};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fmt, fs};
 use url::Url;
 
 /// Test results.
 pub enum TestResult {
   Success,
-  Failure,
+  /// Test without expected value, executed by engine without errors (smoke test).
+  Executed,
+  /// Test passed only thanks to lenient comparison, like numeric tolerance or type coercion.
+  Warning,
+  Failure(FailureCategory),
+  Skipped,
+}
+
+impl TestResult {
+  /// Returns `true` when the test has passed.
+  pub fn is_success(&self) -> bool {
+    matches!(self, Self::Success | Self::Executed | Self::Warning)
+  }
 }
 
 impl fmt::Display for TestResult {
@@ -53,175 +89,1221 @@ impl fmt::Display for TestResult {
       f,
       "{}",
       match self {
-        Self::Success => "SUCCESS",
-        Self::Failure => "ERROR",
+        Self::Success | Self::Executed => "SUCCESS",
+        Self::Warning => "WARNING",
+        Self::Failure(_) => "ERROR",
+        Self::Skipped => "SKIPPED",
       }
     )
   }
 }
 
+/// Results of tests that did not fail, as written in reports and in history of runs.
+/// All other results, like `ERROR`, denote failed tests.
+pub const NOT_FAILED_RESULTS: [&str; 3] = ["SUCCESS", "WARNING", "SKIPPED"];
+
+/// Returns `true` when the result written in report or in history of runs denotes a failed test.
+pub fn is_failed_result(result: &str) -> bool {
+  !NOT_FAILED_RESULTS.contains(&result)
+}
+
+/// Categories of test failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCategory {
+  /// Actual value differs from expected value, classified by the first difference.
+  Mismatch(MismatchClass),
+  /// Test case has no expected value.
+  NoExpectedValue,
+  /// Engine returned no value.
+  NoActualValue,
+  /// Engine reported an error.
+  EngineError,
+  /// Response from engine could not be interpreted.
+  InvalidResponse,
+  /// Request could not be delivered to engine.
+  TransportError,
+  /// Repeated executions gave inconsistent results.
+  Flaky,
+  /// Test file could not be parsed.
+  InvalidTestFile,
+  /// Engine reported an error different from expected, or returned a value when an error was expected.
+  UnexpectedError,
+  /// Test exceeded its time budget.
+  Timeout,
+}
+
+/// Reason why the model file referenced in test file could not be used.
+pub enum ModelError {
+  /// Model file was not found or is ambiguous, tests are skipped.
+  Unresolved(String),
+  /// Model file was found but could not be read or parsed, the test file fails.
+  Invalid(String),
+}
+
+impl fmt::Display for ModelError {
+  /// Converts [ModelError] into string.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Unresolved(reason) | Self::Invalid(reason) => write!(f, "{}", reason),
+    }
+  }
+}
+
+impl fmt::Display for FailureCategory {
+  /// Converts [FailureCategory] into string.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Mismatch(_) => "mismatch",
+        Self::NoExpectedValue => "no_expected_value",
+        Self::NoActualValue => "no_actual_value",
+        Self::EngineError => "engine_error",
+        Self::InvalidResponse => "invalid_response",
+        Self::TransportError => "transport_error",
+        Self::Flaky => "flaky",
+        Self::InvalidTestFile => "invalid_test_file",
+        Self::UnexpectedError => "unexpected_error",
+        Self::Timeout => "timeout",
+      }
+    )
+  }
+}
+
+impl FailureCategory {
+  /// Returns the failure class, the class of the difference for mismatches,
+  /// and the category name for other failures.
+  pub fn class(&self) -> String {
+    match self {
+      Self::Mismatch(class) => class.to_string(),
+      other => other.to_string(),
+    }
+  }
+}
+
+/// Test that has failed in the last run, identified by tests root directory, directory, file and test identifier.
+/// Tests root directory is empty when it was not recorded, such failures match tests in all tests root directories.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FailedTest {
+  /// Tests root directory, without trailing slash.
+  pub root: String,
+  /// Directory of the test file, displayed as configured.
+  pub directory: String,
+  /// Test file name without extension.
+  pub file: String,
+  /// Identifier of the test.
+  pub test_id: String,
+}
+
+impl FailedTest {
+  /// Returns `true` when the failed test belongs to specified test file.
+  fn is_in_file(&self, root: &str, directory: &str, file: &str) -> bool {
+    (self.root.is_empty() || self.root == root) && self.directory == directory && self.file == file
+  }
+}
+
+/// Identification of the executed test.
+#[derive(Clone)]
+pub struct TestInfo {
+  /// Tests root directory containing the test file.
+  pub root: String,
+  /// Directory of the test file, displayed as configured, used in console output and in all reports.
+  pub directory: String,
+  /// Directory of the test file, relative to tests root directory.
+  pub relative_directory: String,
+  /// Test file name without extension.
+  pub file: String,
+  /// Identifier of the test case.
+  pub test_case_id: String,
+  /// Identifier of the test (test case identifier with result node index).
+  pub test_id: String,
+  /// Name of the test case, empty when not specified.
+  pub test_case_name: String,
+  /// Description of the test case, empty when not specified.
+  pub description: String,
+  /// Labels defined in the test file.
+  pub labels: Vec<String>,
+  /// Metadata defined in extension elements of the test case and result node.
+  pub metadata: BTreeMap<String, String>,
+  /// Version of the DMN specification detected from test file namespace.
+  pub spec_version: String,
+  /// Type of the invocable evaluated by the test, empty when not evaluated.
+  pub invocable_type: String,
+  /// Position of the test case in the test file, counted from zero.
+  pub test_case_index: usize,
+}
+
+impl TestInfo {
+  /// Returns `true` when the test has specified identifier in form `name:test_id`,
+  /// where the name is the test file name, its directory name or the directory path relative to tests root directory.
+  pub fn has_identifier(&self, identifier: &str) -> bool {
+    identifier
+      .rsplit_once(':')
+      .is_some_and(|(name, test_id)| test_id == self.test_id && (name == self.file || name == self.relative_directory || self.relative_directory.rsplit('/').next() == Some(name)))
+  }
+
+  /// Returns the name of the test in form `directory/file:test_id`.
+  pub fn name(&self) -> String {
+    format!("{}/{}:{}", self.directory, self.file, self.test_id)
+  }
+}
+
+/// Time spent in phases of the testing process.
+#[derive(Default)]
+pub struct PhaseTimes {
+  /// Parsing of test files.
+  pub parsing: Duration,
+  /// Serialization of requests to JSON.
+  pub serialization: Duration,
+  /// Sending requests and receiving responses.
+  pub network: Duration,
+  /// Deserialization of responses from JSON.
+  pub deserialization: Duration,
+  /// Comparison of actual and expected values.
+  pub comparison: Duration,
+}
+
+/// Sizes of request and response bodies in bytes.
+#[derive(Default, Clone)]
+pub struct PayloadSizes {
+  /// Number of requests.
+  count: usize,
+  /// Total size of request bodies.
+  request_total: usize,
+  /// Size of the largest request body.
+  request_max: usize,
+  /// Total size of response bodies.
+  response_total: usize,
+  /// Size of the largest response body.
+  response_max: usize,
+}
+
+impl PayloadSizes {
+  /// Adds the sizes of request and response bodies.
+  fn add(&mut self, request_size: usize, response_size: usize) {
+    self.count += 1;
+    self.request_total += request_size;
+    self.request_max = self.request_max.max(request_size);
+    self.response_total += response_size;
+    self.response_max = self.response_max.max(response_size);
+  }
+
+  /// Adds all sizes accounted in other sizes.
+  fn merge(&mut self, other: &PayloadSizes) {
+    self.count += other.count;
+    self.request_total += other.request_total;
+    self.request_max = self.request_max.max(other.request_max);
+    self.response_total += other.response_total;
+    self.response_max = self.response_max.max(other.response_max);
+  }
+}
+
+/// Latencies of requests of a single type of evaluated invocable.
+#[derive(Default)]
+struct TypeLatencies {
+  /// Histogram of request latencies.
+  histogram: LatencyHistogram,
+  /// Start of the first request.
+  first_start: Option<Instant>,
+  /// End of the last request.
+  last_end: Option<Instant>,
+}
+
+impl TypeLatencies {
+  /// Adds the latency of the request that has just ended.
+  fn add(&mut self, latency: Duration) {
+    let end = Instant::now();
+    let start = end.checked_sub(latency).unwrap_or(end);
+    self.histogram.record(latency);
+    self.first_start = Some(self.first_start.map_or(start, |first_start| first_start.min(start)));
+    self.last_end = Some(end);
+  }
+
+  /// Returns the wall-clock time from the start of the first to the end of the last request.
+  fn elapsed(&self) -> Duration {
+    match (self.first_start, self.last_end) {
+      (Some(first_start), Some(last_end)) => last_end.duration_since(first_start),
+      _ => Duration::ZERO,
+    }
+  }
+}
+
+/// Status of the passed test case in TCK report.
+const TCK_SUCCESS: &str = "SUCCESS";
+
+/// Status of the failed test case in TCK report.
+const TCK_FAILURE: &str = "ERROR";
+
+/// Test case identified by tests root directory, directory, file and test case identifier.
+type TestCaseKey = (String, String, String, String);
+
+/// Default maximal number of requests sent in one batch.
+const DEFAULT_BATCH_SIZE: usize = 50;
+
 /// Context used during testing process.
 pub struct Context {
-  /// Model RDNNs indexed by file name.
+  /// Model RDNNs indexed by model file path.
   model_rdnns: HashMap<String, String>,
-  /// Model names indexed by file name.
+  /// Model names indexed by model file path.
   model_names: HashMap<String, String>,
-  /// Workspace names indexed by file name.
+  /// Workspace names indexed by model file path.
   workspace_names: HashMap<String, String>,
-  /// Test results writer.
-  report_writer: BufWriter<File>,
-  /// Test cases (TCK ready) results writer.
-  tck_report_writer: BufWriter<File>,
+  /// Reasons why model files could not be read or parsed, indexed by model file path.
+  invalid_models: HashMap<String, String>,
+  /// Test results writer, not present in contexts without reports.
+  report_writer: Option<ReportWriter>,
+  /// Test cases (TCK ready) results writer, not present in contexts without reports.
+  tck_report_writer: Option<BufWriter<File>>,
+  /// Optional writer of unified diffs for mismatched values.
+  diff_writer: Option<BufWriter<File>>,
+  /// Optional directory where artifacts of failed tests are written.
+  failure_artifacts_dir: Option<String>,
+  /// Optional directory where parameters of evaluation requests of failed tests are written.
+  failures_dir: Option<String>,
+  /// Optional writer of request timings.
+  timing_writer: Option<BufWriter<File>>,
+  /// Optional histogram of request latencies with the path to file where it is written.
+  latency_histogram: Option<(LatencyHistogram, String)>,
+  /// Coverage of models and invocables by executed tests.
+  pub coverage: Coverage,
+  /// Graph of imports between models.
+  pub imports: ImportGraph,
+  /// Optional dashboard with results of all tests.
+  dashboard: Option<Dashboard>,
+  /// Interactive terminal interface, displayed while tests are executed.
+  pub tui: Option<Tui>,
+  /// Optional builder of curl commands reproducing evaluation requests of failed tests.
+  pub curl: Option<Curl>,
+  /// Stream of progress events, open while tests are executed when configured.
+  pub events: Option<EventStream>,
+  /// Number of passed and failed tests indexed by test label.
+  label_results: BTreeMap<String, (usize, usize)>,
+  /// Optional path to CSV report file with results grouped by test labels.
+  labels_report_file: Option<String>,
   /// Number of tests that have passed.
   pub success_count: usize,
+  /// Number of tests that have passed with warnings, included in the number of passed tests.
+  pub warning_count: usize,
   /// Number of tests that have failed.
   pub failure_count: usize,
+  /// Number of failed tests indexed by failure class.
+  failure_classes: BTreeMap<String, usize>,
+  /// Number of skipped tests.
+  pub skipped_count: usize,
+  /// Cache of test file hashes with results of their tests.
+  pub cache: Option<Cache>,
+  /// Flag indicating if only tests from test files changed since the cached run are executed.
+  pub changed_only: bool,
+  /// Tests that have failed in the last run as tuples of directory, file and test identifier,
+  /// only these tests are executed when specified.
+  pub previous_failures: Option<BTreeSet<FailedTest>>,
+  /// Number of test files skipped, because they did not change since the cached run.
+  pub unchanged_file_count: usize,
+  /// Flag indicating if computed values should be written back into test files.
+  pub write_computed: bool,
+  /// Flag indicating if test files are validated strictly.
+  pub strict: bool,
+  /// Flag indicating if types of untyped values are inferred from DMN models.
+  pub infer_types: bool,
+  /// Size of test files in bytes above which test cases are parsed lazily.
+  pub streaming_threshold: Option<u64>,
+  /// Order of components when comparing values.
+  pub component_order: ComponentOrder,
+  /// Flag indicating if result nodes without expected value are executed as smoke tests.
+  pub smoke_tests: bool,
+  /// Flag indicating if result nodes without expected value are compared with computed value.
+  pub computed_fallback: bool,
+  /// Matching of error messages reported by engine with expected error messages.
+  pub error_message_matching: ErrorMessageMatching,
+  /// Compiled patterns of expected error messages, each pattern is compiled only once.
+  error_patterns: HashMap<String, Regex>,
+  /// Aliases of type names applied before comparing values.
+  pub type_aliases: BTreeMap<String, String>,
+  /// Normalization of actual and expected values before comparison.
+  pub canonicalization: Canonicalization,
+  /// Comparison of actual and expected values.
+  comparison: Comparison,
+  /// Comparison of actual and expected values of tests with overridden comparators, indexed by test identifier.
+  comparison_overrides: Vec<(String, Comparison)>,
+  /// Inferred types of variables indexed by model file path and variable name.
+  variable_types: HashMap<String, HashMap<String, InferredType>>,
   /// Total endpoint execution time in nanoseconds.
   pub execution_time: u128,
-  /// Flag indicating if testing should be stopped after first test failure.
-  pub stop_on_failure: bool,
+  /// Time spent in phases of the testing process.
+  pub phase_times: PhaseTimes,
+  /// Sizes of request and response bodies indexed by directory of test files.
+  payload_sizes: BTreeMap<String, PayloadSizes>,
+  /// Latencies of requests per type of evaluated invocable.
+  type_latencies: BTreeMap<String, TypeLatencies>,
+  /// Number of requests sent to engine.
+  pub request_count: usize,
+  /// Number of executions of each test.
+  pub repeat: usize,
+  /// Optional time budget of every test.
+  test_timeout: Option<Duration>,
+  /// Time budgets of tests indexed by test label, directory name or directory path.
+  test_timeouts: BTreeMap<String, Duration>,
+  /// URL of the engine endpoint evaluating batches of requests, when tests are executed in batches.
+  pub batch_url: Option<String>,
+  /// Maximal number of requests in one batch.
+  pub batch_size: usize,
+  /// Generator of shuffled execution order, when shuffling is requested.
+  pub shuffle: Option<Shuffle>,
+  /// Tests with inconsistent results, with the number of passed and total executions.
+  flaky_tests: BTreeMap<String, (usize, usize)>,
+  /// Optional number of failed tests after which no more tests are executed.
+  max_failures: Option<usize>,
+  /// Optional expected number of executed test cases.
+  expected_tests: Option<usize>,
   /// Pattern for filtering files to be tested.
   pub file_search_pattern: String,
-  /// Tests root directory.
-  pub root_dir_path: String,
-  /// Test cases that have succeeded.
-  pub test_case_success: BTreeSet<(String, String, String)>,
-  /// Test cases that have failed.
-  pub test_case_failure: BTreeMap<(String, String, String), Vec<String>>,
+  /// Include and exclude patterns for filtering test files, relative to tests root directory.
+  pub file_selection: FileSelection,
+  /// Optional pattern for filtering test cases by name.
+  pub test_case_name_pattern: Option<Regex>,
+  /// Tests root directories, each with trailing slash.
+  pub root_dir_paths: Vec<String>,
+  /// Rendering of paths of test files and directories.
+  path_display: PathDisplay,
+  /// Prefix replacing the tests root directory in paths displayed with prefix.
+  path_prefix: String,
+  /// Remarks of failed tests of executed test cases, indexed by tests root directory, directory, file and test case identifier.
+  /// Results are aggregated by test case, because tests of a test case may be executed in shuffled order or repeatedly,
+  /// and written to TCK report when testing is completed.
+  test_case_failures: BTreeMap<TestCaseKey, Vec<String>>,
+  /// Optional history of test runs.
+  history: Option<History>,
 }
 
 impl Context {
   /// Creates a new testing context.
-  pub fn new(stop_on_failure: bool, file_search_pattern: String, report_file_name: &str, tck_report_file_name: &str, root_dir: String) -> Self {
-    let report_file = File::create(report_file_name).unwrap_or_else(|e| panic!("creating output file {} failed with reason: {}", report_file_name, e));
-    let report_writer = BufWriter::new(report_file);
-    let tck_report_file = File::create(tck_report_file_name).unwrap_or_else(|e| panic!("creating output file {} failed with reason: {}", tck_report_file_name, e));
-    let tck_report_writer = BufWriter::new(tck_report_file);
+  pub fn new(config: &ConfigurationParams, root_dirs: Vec<String>, history: Option<History>) -> Self {
+    // artifacts of failed tests from previous runs are removed
+    for artifacts_dir in config.failure_artifacts_dir.iter().chain(&config.failures_dir) {
+      remove_json_files(Path::new(artifacts_dir));
+    }
+    Self {
+      report_writer: Some(ReportWriter::new(&config.report_file, config.report_format.clone(), &config.run_metadata)),
+      tck_report_writer: Some(create_writer(&config.tck_report_file)),
+      diff_writer: config.diff_file.as_deref().map(create_writer),
+      latency_histogram: config.latency_histogram_file.as_ref().map(|file_name| (LatencyHistogram::default(), file_name.clone())),
+      timing_writer: config.timing_file.as_deref().map(|timing_file_name| {
+        let mut timing_writer = create_writer(timing_file_name);
+        writeln!(timing_writer, "root,directory,file,test_id,duration_us,http_status,request_bytes,response_bytes")
+          .unwrap_or_else(|e| panic!("writing timing header failed with reason: {}", e));
+        timing_writer
+      }),
+      dashboard: config.dashboard_file.as_ref().map(|dashboard_file| Dashboard::new(dashboard_file, &config.run_metadata)),
+      history,
+      cache: config.cache_file.as_deref().map(Cache::load),
+      ..Self::without_reports(config, root_dirs)
+    }
+  }
+
+  /// Creates a new testing context for the next run, keeping model definitions processed by this context.
+  /// All reports of this context are flushed before the reports of the next run are created.
+  pub fn restart(mut self, config: &ConfigurationParams, history: Option<History>) -> Self {
+    self.flush();
+    let mut coverage = self.coverage;
+    coverage.reset();
+    Self {
+      model_rdnns: self.model_rdnns,
+      model_names: self.model_names,
+      workspace_names: self.workspace_names,
+      invalid_models: self.invalid_models,
+      imports: self.imports,
+      variable_types: self.variable_types,
+      coverage,
+      events: self.events,
+      ..Self::new(config, self.root_dir_paths, history)
+    }
+  }
+
+  /// Creates a new context without any report files, used for sending requests without checking the results.
+  pub fn without_reports(config: &ConfigurationParams, root_dirs: Vec<String>) -> Self {
     Self {
       model_rdnns: HashMap::new(),
       model_names: HashMap::new(),
       workspace_names: HashMap::new(),
-      report_writer,
-      tck_report_writer,
+      invalid_models: HashMap::new(),
+      report_writer: None,
+      tck_report_writer: None,
+      diff_writer: None,
+      failure_artifacts_dir: config.failure_artifacts_dir.clone(),
+      failures_dir: config.failures_dir.clone(),
+      timing_writer: None,
+      latency_histogram: None,
+      coverage: Coverage::default(),
+      imports: ImportGraph::default(),
+      dashboard: None,
+      tui: None,
+      curl: config.curl_commands.then(|| Curl::new(config)),
+      events: None,
+      label_results: BTreeMap::new(),
+      labels_report_file: config.labels_report_file.clone(),
       success_count: 0,
+      warning_count: 0,
       failure_count: 0,
+      failure_classes: BTreeMap::new(),
+      skipped_count: 0,
+      cache: None,
+      changed_only: config.changed_only,
+      previous_failures: None,
+      unchanged_file_count: 0,
+      write_computed: config.write_computed,
+      strict: config.strict,
+      infer_types: config.infer_types,
+      streaming_threshold: config.streaming_threshold,
+      component_order: config.component_order,
+      smoke_tests: config.smoke_tests,
+      computed_fallback: config.computed_fallback,
+      error_message_matching: config.error_message_matching,
+      error_patterns: HashMap::new(),
+      type_aliases: config.type_aliases.clone(),
+      canonicalization: Canonicalization::new(config),
+      comparison: Comparison::new(config),
+      comparison_overrides: config
+        .comparison_overrides
+        .iter()
+        .map(|(identifier, overrides)| (identifier.clone(), Comparison::with_overrides(config, overrides)))
+        .collect(),
+      variable_types: HashMap::new(),
       execution_time: 0,
-      stop_on_failure,
-      file_search_pattern,
-      root_dir_path: root_dir + "/",
-      test_case_success: BTreeSet::new(),
-      test_case_failure: BTreeMap::new(),
+      phase_times: PhaseTimes::default(),
+      payload_sizes: BTreeMap::new(),
+      type_latencies: BTreeMap::new(),
+      request_count: 0,
+      repeat: config.repeat.unwrap_or(1).max(1),
+      test_timeout: config.test_timeout_ms.map(Duration::from_millis),
+      test_timeouts: config.test_timeouts.iter().map(|(name, timeout)| (name.clone(), Duration::from_millis(*timeout))).collect(),
+      batch_url: config.batch_url.clone(),
+      batch_size: config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+      shuffle: config.shuffle.then(|| Shuffle::new(config.seed.unwrap_or_default())),
+      flaky_tests: BTreeMap::new(),
+      // stopping on failure is stopping after the first failure
+      max_failures: if config.stop_on_failure { Some(1) } else { config.max_failures },
+      expected_tests: config.expected_tests,
+      file_search_pattern: config.file_search_pattern.clone(),
+      file_selection: FileSelection::new(config),
+      test_case_name_pattern: config
+        .test_case_name_pattern
+        .as_deref()
+        .map(|pattern| Regex::new(pattern).unwrap_or_else(|e| panic!("parsing test case name pattern failed with reason: {}", e))),
+      root_dir_paths: root_dirs.into_iter().map(|root_dir| root_dir + "/").collect(),
+      path_display: config.path_display,
+      path_prefix: config.path_prefix.clone().unwrap_or_default(),
+      test_case_failures: BTreeMap::new(),
+      history: None,
     }
   }
 
-  pub fn process_model_definitions(&mut self, root_dir_path: &Path, dir_name: &str, file_name: &str) {
+  /// Retrieves model name, namespace, invocables and imports from the DMN file.
+  /// Model files that could not be read or parsed are remembered, test files referencing them fail.
+  pub fn process_model_definitions(&mut self, root_dir_path: &Path, dir_name: &str, file_name: &str) -> Result<(), String> {
     let file_path = Path::new(dir_name).join(Path::new(file_name));
-    let content = fs::read_to_string(&file_path).unwrap();
-    let document = roxmltree::Document::parse(&content).unwrap();
+    let model_path = file_path.to_string_lossy().to_string();
+    let result = self.read_model_definitions(root_dir_path, &file_path, &model_path);
+    if let Err(reason) = &result {
+      self.invalid_models.insert(model_path, reason.clone());
+    }
+    result
+  }
+
+  /// Reads model definitions from the DMN file, nothing is stored when the file is invalid.
+  fn read_model_definitions(&mut self, root_dir_path: &Path, file_path: &Path, model_path: &str) -> Result<(), String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("reading model file {} failed with reason: {}", model_path, e))?;
+    let document = roxmltree::Document::parse(&content).map_err(|e| format!("parsing model file {} failed with reason: {}", model_path, e))?;
     let root_node = document.root_element();
+    let model_name = root_node.attribute("name").ok_or_else(|| format!("model file {} has no model name", model_path))?;
+    let namespace = root_node.attribute("namespace").ok_or_else(|| format!("model file {} has no namespace", model_path))?;
+    let model_path = model_path.to_string();
     // process model name
-    let model_name = root_node.attribute("name").unwrap();
-    self.model_names.insert(file_name.to_string(), model_name.to_string());
+    self.model_names.insert(model_path.clone(), model_name.to_string());
     // process namespace
-    let namespace = root_node.attribute("namespace").unwrap();
-    self.model_rdnns.insert(file_name.to_string(), to_rdnn(namespace));
+    self.model_rdnns.insert(model_path.clone(), to_rdnn(namespace));
     // process workspace names
-    self.workspace_names.insert(file_name.to_string(), workspace_name(root_dir_path, &file_path));
+    self.workspace_names.insert(model_path.clone(), workspace_name(root_dir_path, file_path));
+    // process invocables
+    let invocable_names: Vec<String> = root_node
+      .children()
+      .filter(|node| INVOCABLE_ELEMENTS.contains(&node.tag_name().name()))
+      .filter_map(|node| node.attribute("name").map(|name| name.to_string()))
+      .collect();
+    // process imports of other DMN models
+    let imports = root_node
+      .children()
+      .filter(|node| node.tag_name().name() == "import")
+      .filter(|node| node.attribute("importType").is_some_and(|import_type| import_type.contains("/DMN/")))
+      .filter_map(|node| {
+        node.attribute("namespace").map(|namespace| ModelImport {
+          name: node.attribute("name").unwrap_or_default().to_string(),
+          namespace: namespace.to_string(),
+        })
+      })
+      .collect();
+    self.imports.add_model(&model_path, namespace, imports, &invocable_names);
+    self.variable_types.insert(model_path.clone(), variable_types(&root_node));
+    self.coverage.add_model(&model_path, invocable_names);
+    Ok(())
+  }
+
+  /// Resolves the model file referenced in test file placed in specified directory.
+  /// The model is searched in the directory of the test file first, then in all other directories.
+  /// Returns the path of the model file or the reason why the model could not be used.
+  pub fn resolve_model(&self, dir_name: &str, model_file_name: &str) -> Result<String, ModelError> {
+    let model_path = Path::new(dir_name).join(model_file_name).to_string_lossy().to_string();
+    let model_path = if self.model_names.contains_key(&model_path) || self.invalid_models.contains_key(&model_path) {
+      model_path
+    } else {
+      let mut candidates = self
+        .model_names
+        .keys()
+        .chain(self.invalid_models.keys())
+        .filter(|path| Path::new(path).file_name().is_some_and(|name| name == model_file_name))
+        .cloned()
+        .collect::<Vec<String>>();
+      candidates.sort();
+      match candidates.len() {
+        0 => return Err(ModelError::Unresolved(format!("model file '{}' not found", model_file_name))),
+        1 => candidates.remove(0),
+        _ => {
+          return Err(ModelError::Unresolved(format!(
+            "model file '{}' is ambiguous, found in: {}",
+            model_file_name,
+            candidates.join(", ")
+          )))
+        }
+      }
+    };
+    match self.invalid_models.get(&model_path) {
+      Some(reason) => Err(ModelError::Invalid(reason.clone())),
+      None => Ok(model_path),
+    }
+  }
+
+  /// Displays warnings about imports that could not be resolved and about cyclic imports.
+  pub fn display_import_warnings(&self) {
+    for model_path in self.model_names.keys().collect::<BTreeSet<&String>>() {
+      for (import, imported_path) in self.imports.imported_models(model_path) {
+        if imported_path.is_none() {
+          outln!(
+            "{1}model {2} imports unknown namespace: {3}{0}",
+            COLOR_RESET,
+            COLOR_YELLOW,
+            self.display_path(model_path),
+            import.namespace
+          );
+        }
+      }
+    }
+    if let Err(cycle) = self.imports.deployment_order() {
+      let models = cycle.iter().map(|path| self.display_path(path)).collect::<Vec<String>>();
+      outln!("{1}cyclic imports between models: {2}{0}", COLOR_RESET, COLOR_YELLOW, models.join(", "));
+    }
+  }
+
+  /// Returns the type of the variable defined in model, inferred from its type reference.
+  pub fn variable_type(&self, model_path: &str, variable_name: &str) -> Option<&InferredType> {
+    self.variable_types.get(model_path).and_then(|types| types.get(variable_name))
   }
 
   pub fn get_model_name(&self, file_name: &str) -> String {
-    self.model_names.get(file_name).cloned().expect("model name not found for specified file name")
+    self.model_names.get(file_name).cloned().expect("model name not found for specified file path")
   }
 
   pub fn get_workspace_name(&self, file_name: &str) -> String {
-    self.workspace_names.get(file_name).cloned().expect("workspace name not found for specified file name")
+    self.workspace_names.get(file_name).cloned().expect("workspace name not found for specified file path")
   }
 
   pub fn get_model_rdnn(&self, file_name: &str) -> String {
-    self.model_rdnns.get(file_name).cloned().expect("model RDNN not found for specified file name")
-  }
-
-  pub fn write_line(&mut self, test_file_name: &str, test_case_id: &str, test_id: &str, test_result: TestResult, remarks: &str) {
-    let test_file_directory = dir_name_stripped_prefix(&dir_name(test_file_name), &self.root_dir_path);
-    let test_file_stem = file_stem(test_file_name);
-    let test_case_key = (test_file_directory.clone(), test_file_stem.clone(), test_case_id.to_string());
-    writeln!(
-      self.report_writer,
-      r#""{}","{}","{}","{}","{}""#,
-      test_file_directory,
-      test_file_stem,
-      test_id,
-      test_result,
-      if matches!(test_result, TestResult::Failure) { remarks } else { "" }
-    )
-    .unwrap_or_else(|e| panic!("writing line to CSV report failed with reason: {}", e));
+    self.model_rdnns.get(file_name).cloned().expect("model RDNN not found for specified file path")
+  }
+
+  /// Returns `true` when the test case with specified name should be executed.
+  pub fn is_selected(&self, test_case_name: Option<&str>) -> bool {
+    match &self.test_case_name_pattern {
+      Some(pattern) => test_case_name.is_some_and(|name| pattern.is_match(name)),
+      None => true,
+    }
+  }
+
+  /// Returns `true` when the test file contains any test that has failed in the last run,
+  /// or when all tests are executed. Failed tests are identified by directories displayed as configured.
+  pub fn has_previous_failures(&self, file_path: &str) -> bool {
+    let (root, directory, file) = (self.test_root(file_path), self.display_path(&dir_name(file_path)), file_stem(file_path));
+    self
+      .previous_failures
+      .as_ref()
+      .is_none_or(|failures| failures.iter().any(|failure| failure.is_in_file(&root, &directory, &file)))
+  }
+
+  /// Returns `true` when the test has failed in the last run, or when all tests are executed.
+  pub fn has_previously_failed(&self, file_path: &str, test_id: &str) -> bool {
+    let (root, directory, file) = (self.test_root(file_path), self.display_path(&dir_name(file_path)), file_stem(file_path));
+    self
+      .previous_failures
+      .as_ref()
+      .is_none_or(|failures| failures.iter().any(|failure| failure.is_in_file(&root, &directory, &file) && failure.test_id == test_id))
+  }
+
+  /// Returns the tests root directory containing the test file, without trailing slash.
+  fn test_root(&self, file_path: &str) -> String {
+    self.root_dir_path(file_path).trim_end_matches('/').to_string()
+  }
+
+  /// Returns the comparison of actual and expected values for specified test.
+  pub fn comparison(&self, test: &TestInfo) -> &Comparison {
+    self
+      .comparison_overrides
+      .iter()
+      .find(|(identifier, _)| test.has_identifier(identifier))
+      .map_or(&self.comparison, |(_, comparison)| comparison)
+  }
+
+  /// Returns the time budget of the test, configured for any of its labels,
+  /// for its directory or for all tests, in this order.
+  pub fn test_timeout(&self, test: &TestInfo) -> Option<Duration> {
+    test
+      .labels
+      .iter()
+      .find_map(|label| self.test_timeouts.get(label))
+      .or_else(|| self.test_timeouts.get(&test.relative_directory))
+      .or_else(|| test.relative_directory.rsplit('/').next().and_then(|name| self.test_timeouts.get(name)))
+      .or(self.test_timeout.as_ref())
+      .copied()
+  }
+
+  /// Creates the identification of the test defined in specified test file.
+  pub fn test_info(&self, file_path: &str, test_case_id: &str, test_id: &str, labels: &[String]) -> TestInfo {
+    TestInfo {
+      root: self.test_root(file_path),
+      directory: self.display_path(&dir_name(file_path)),
+      relative_directory: self.relative_path(&dir_name(file_path)),
+      file: file_stem(file_path),
+      test_case_id: test_case_id.to_string(),
+      test_id: test_id.to_string(),
+      test_case_name: String::new(),
+      description: String::new(),
+      labels: labels.to_vec(),
+      metadata: BTreeMap::new(),
+      spec_version: String::new(),
+      invocable_type: String::new(),
+      test_case_index: 0,
+    }
+  }
+
+  pub fn write_line(&mut self, test: &TestInfo, duration: Duration, test_result: TestResult, remarks: &str) {
+    let (remarks, category, failure_class) = match &test_result {
+      TestResult::Success => ("", String::new(), String::new()),
+      TestResult::Executed => ("", "smoke_test".to_string(), String::new()),
+      TestResult::Warning => (remarks, "lenient_match".to_string(), String::new()),
+      TestResult::Failure(category) => (remarks, category.to_string(), category.class()),
+      TestResult::Skipped => (remarks, String::new(), String::new()),
+    };
+    if let Some(report_writer) = &mut self.report_writer {
+      report_writer.write_row(&ReportRow {
+        root: &test.root,
+        directory: &test.directory,
+        file: &test.file,
+        test_case_id: &test.test_case_id,
+        test_id: &test.test_id,
+        test_case_name: &test.test_case_name,
+        description: &test.description,
+        result: &test_result.to_string(),
+        remarks,
+        duration_us: duration.as_micros(),
+        category: &category,
+        failure_class: &failure_class,
+        spec_version: &test.spec_version,
+      });
+    }
+    if let Some(history) = &self.history {
+      history.add_result(
+        &test.root,
+        &test.directory,
+        &test.file,
+        &test.test_case_id,
+        &test.test_id,
+        &test_result.to_string(),
+        remarks,
+        duration.as_micros(),
+      );
+    }
+    if let Some(dashboard) = &mut self.dashboard {
+      dashboard.add_row(test, &test_result.to_string(), &category, remarks, duration.as_micros());
+    }
+    if let Some(events) = &mut self.events {
+      events.emit(&Event::TestFinished {
+        root: &test.root,
+        directory: &test.directory,
+        file: &test.file,
+        test_case_id: &test.test_case_id,
+        test_id: &test.test_id,
+        result: &test_result.to_string(),
+        category: &category,
+        remarks,
+        duration_us: duration.as_micros(),
+      });
+    }
+    if let Some(tui) = &self.tui {
+      match test_result {
+        TestResult::Success | TestResult::Executed | TestResult::Warning => tui.add_result(test, None),
+        TestResult::Failure(_) => tui.add_result(test, Some(remarks)),
+        TestResult::Skipped => {}
+      }
+    }
     match test_result {
-      TestResult::Success => {
+      TestResult::Success | TestResult::Executed => {
+        self.add_label_results(&test.labels, true);
         self.success_count += 1;
-        self.test_case_success.insert(test_case_key);
-        println!("{1}success{0} {remarks}", COLOR_RESET, COLOR_GREEN);
+        self.add_test_case_result(test, None);
+        let smoke = if matches!(test_result, TestResult::Executed) { " (smoke test)" } else { "" };
+        outln!("{1}success{0}{3} {2} µs", COLOR_RESET, COLOR_GREEN, duration.as_micros(), smoke);
       }
-      TestResult::Failure => {
+      TestResult::Warning => {
+        self.add_label_results(&test.labels, true);
+        self.success_count += 1;
+        self.warning_count += 1;
+        self.add_test_case_result(test, None);
+        outln!("{1}warning{0} {2} µs", COLOR_RESET, COLOR_YELLOW, duration.as_micros());
+        outln!("{1}{remarks}{0}", COLOR_RESET, COLOR_YELLOW);
+      }
+      TestResult::Failure(_) => {
+        self.add_label_results(&test.labels, false);
         self.failure_count += 1;
-        self
-          .test_case_failure
-          .entry(test_case_key)
-          .and_modify(|failures| failures.push(remarks.to_string()))
-          .or_insert(vec![remarks.to_string()]);
-        println!("{1}failure{0}\n{2}{remarks}{0}", COLOR_RESET, COLOR_RED, COLOR_YELLOW);
-        if self.stop_on_failure {
-          process::exit(1);
+        *self.failure_classes.entry(failure_class).or_default() += 1;
+        self.add_test_case_result(test, Some(remarks));
+        outln!("{1}failure{0}", COLOR_RESET, COLOR_RED);
+        if !test.description.is_empty() {
+          outln!("{1}description{0}: {2}", COLOR_RESET, COLOR_BRIGHT_WHITE, test.description);
         }
+        outln!("{1}{remarks}{0}", COLOR_RESET, COLOR_YELLOW);
+      }
+      TestResult::Skipped => {
+        self.skipped_count += 1;
+        outln!("{1}skipped{0}", COLOR_RESET, COLOR_YELLOW);
       }
     }
   }
 
-  pub fn display_test_cases_report(&mut self) {
-    let mut total = self.test_case_success.clone();
-    total.extend(self.test_case_failure.keys().cloned().collect::<HashSet<(String, String, String)>>());
-    let mut success = self.test_case_success.clone();
-    success.retain(|item| !self.test_case_failure.contains_key(item));
-    let total_count = total.len();
-    let success_count = success.len();
-    let failure_count = self.test_case_failure.len();
+  /// Writes the unified diff of expected and actual value, when diff file, dashboard or interactive interface is configured.
+  pub fn write_diff(&mut self, test: &TestInfo, expected: &str, actual: &str) {
+    if self.diff_writer.is_none() && self.dashboard.is_none() && self.tui.is_none() {
+      return;
+    }
+    let test_name = test.name();
+    let diff = unified(&format!("expected {}", test_name), expected, &format!("actual {}", test_name), actual);
+    if let Some(diff_writer) = &mut self.diff_writer {
+      writeln!(diff_writer, "{}", diff).unwrap_or_else(|e| panic!("writing diff failed with reason: {}", e));
+    }
+    if let Some(tui) = &self.tui {
+      tui.add_diff(test, &diff);
+    }
+    if let Some(dashboard) = &mut self.dashboard {
+      dashboard.add_diff(test, diff);
+    }
+  }
+
+  /// Writes the JSON artifact of the failed test, when artifacts directory is configured.
+  pub fn write_failure_artifact<T: Serialize>(&self, test: &TestInfo, artifact: &T) {
+    if let Some(failure_artifacts_dir) = &self.failure_artifacts_dir {
+      write_test_json(&self.artifacts_directory(failure_artifacts_dir, test).join(&test.file), test, artifact);
+    }
+  }
+
+  /// Returns the directory of artifacts of the test in specified base directory, mirroring the directory of the test file,
+  /// prefixed with the tests root directory when tests from multiple root directories are executed.
+  fn artifacts_directory(&self, base_dir: &str, test: &TestInfo) -> PathBuf {
+    let base_dir = Path::new(base_dir);
+    if self.root_dir_paths.len() > 1 {
+      base_dir.join(test.root.trim_start_matches('/')).join(&test.relative_directory)
+    } else {
+      base_dir.join(&test.relative_directory)
+    }
+  }
+
+  /// Writes the parameters of evaluation request of the failed test, when failures directory is configured.
+  pub fn write_failing_params(&self, test: &TestInfo, params: &EvaluateParams) {
+    if let Some(failures_dir) = &self.failures_dir {
+      write_test_json(&self.artifacts_directory(failures_dir, test).join(&test.file), test, params);
+    }
+  }
+
+  /// Accounts the duration of a single request to the type of evaluated invocable
+  /// and writes the timing of the request, when timing file is configured.
+  pub fn write_timing(&mut self, test: &TestInfo, duration: Duration, status: Option<u16>, request_size: usize, response_size: usize) {
+    self.type_latencies.entry(test.invocable_type.clone()).or_default().add(duration);
+    if let Some(timing_writer) = &mut self.timing_writer {
+      writeln!(
+        timing_writer,
+        "{},{},{},{},{},{},{},{}",
+        report::quote(&test.root),
+        report::quote(&test.directory),
+        report::quote(&test.file),
+        report::quote(&test.test_id),
+        duration.as_micros(),
+        status.map(|status| status.to_string()).unwrap_or_default(),
+        request_size,
+        response_size
+      )
+      .unwrap_or_else(|e| panic!("writing timing failed with reason: {}", e));
+    }
+  }
+
+  /// Adds the result of a single test to the result of its test case.
+  fn add_test_case_result(&mut self, test: &TestInfo, failure: Option<&str>) {
+    let key = (test.root.clone(), test.directory.clone(), test.file.clone(), test.test_case_id.clone());
+    let failures = self.test_case_failures.entry(key).or_default();
+    if let Some(failure) = failure.filter(|failure| !failures.iter().any(|existing| existing == failure)) {
+      failures.push(failure.to_string());
+    }
+  }
+
+  /// Writes the results of all executed test cases to TCK report, the report is written only once.
+  fn write_tck_report(&mut self) {
+    let Some(mut tck_report_writer) = self.tck_report_writer.take() else {
+      return;
+    };
+    for ((_, test_directory, test_file, test_case_id), failures) in &self.test_case_failures {
+      let (status, remarks) = if failures.is_empty() {
+        (TCK_SUCCESS, String::new())
+      } else {
+        (TCK_FAILURE, failures.join(","))
+      };
+      writeln!(
+        tck_report_writer,
+        "{},{},{},{},{}",
+        report::quote(test_directory),
+        report::quote(test_file),
+        report::quote(test_case_id),
+        report::quote(status),
+        report::quote(&remarks)
+      )
+      .unwrap_or_else(|e| panic!("writing line to TCK report failed with reason: {}", e));
+    }
+    tck_report_writer.flush().unwrap_or_else(|e| panic!("flushing report failed with reason: {}", e));
+  }
+
+  /// Returns the number of passed and failed test cases.
+  fn test_case_counts(&self) -> (usize, usize) {
+    let failure_count = self.test_case_failures.values().filter(|failures| !failures.is_empty()).count();
+    (self.test_case_failures.len() - failure_count, failure_count)
+  }
+
+  /// Adds the result of a single test to all specified labels.
+  fn add_label_results(&mut self, labels: &[String], passed: bool) {
+    for label in labels {
+      let (success, failure) = self.label_results.entry(label.clone()).or_default();
+      if passed {
+        *success += 1;
+      } else {
+        *failure += 1;
+      }
+    }
+  }
+
+  /// Displays test results grouped by labels.
+  pub fn display_labels_report(&self) {
+    if self.label_results.is_empty() {
+      return;
+    }
+    let width = self.label_results.keys().map(|label| label.chars().count()).max().unwrap_or_default().max(5);
+    let line = "─".repeat(width + 2);
+    outln!("\nLabels:");
+    outln!("┌{line}┬─────────┬─────────┬─────────┐");
+    outln!("│ {:<width$} │   Total │   Fails │ Success │", "Label");
+    outln!("├{line}┼─────────┼─────────┼─────────┤");
+    for (label, (success_count, failure_count)) in &self.label_results {
+      let total_count = success_count + failure_count;
+      let (success_perc, _) = Self::calc_perc(total_count, *success_count, *failure_count);
+      let color = if *failure_count > 0 { COLOR_RED } else { COLOR_GREEN };
+      let (total, failures) = (humanize::count(total_count), humanize::count(*failure_count));
+      outln!("│ {label:<width$} │ {total:>7} │ {1}{failures:>7}{0} │{1}{success_perc:>7.2}%{0} │", COLOR_RESET, color);
+    }
+    outln!("└{line}┴─────────┴─────────┴─────────┘");
+  }
+
+  /// Writes test results grouped by labels to labels report, when configured.
+  fn write_labels_report(&self) {
+    let Some(labels_report_file) = &self.labels_report_file else {
+      return;
+    };
+    let mut writer = create_writer(labels_report_file);
+    writeln!(writer, r#""label","total","success","failure""#).unwrap_or_else(|e| panic!("writing labels report failed with reason: {}", e));
+    for (label, (success_count, failure_count)) in &self.label_results {
+      writeln!(
+        writer,
+        r#"{},"{}","{}","{}""#,
+        report::quote(label),
+        success_count + failure_count,
+        success_count,
+        failure_count
+      )
+      .unwrap_or_else(|e| panic!("writing labels report failed with reason: {}", e));
+    }
+    writer.flush().unwrap_or_else(|e| panic!("flushing labels report failed with reason: {}", e));
+  }
+
+  /// Accounts the sizes of request and response bodies to specified directory.
+  pub fn add_payload_sizes(&mut self, directory: &str, request_size: usize, response_size: usize) {
+    self.payload_sizes.entry(directory.to_string()).or_default().add(request_size, response_size);
+  }
+
+  /// Displays total, average and maximal sizes of request and response bodies per directory.
+  pub fn display_payload_sizes_report(&self) {
+    if self.payload_sizes.is_empty() {
+      return;
+    }
+    let mut total = PayloadSizes::default();
+    for sizes in self.payload_sizes.values() {
+      total.merge(sizes);
+    }
+    let width = self.payload_sizes.keys().map(|directory| directory.chars().count()).max().unwrap_or_default().max(9);
+    let line = "─".repeat(width + 2);
+    let row = |directory: &str, sizes: &PayloadSizes| {
+      let (count, request_total, request_max, response_total, response_max) = (sizes.count, sizes.request_total, sizes.request_max, sizes.response_total, sizes.response_max);
+      let (request_avg, response_avg) = (request_total / count.max(1), response_total / count.max(1));
+      let [count, request_total, request_avg, request_max, response_total, response_avg, response_max] =
+        [count, request_total, request_avg, request_max, response_total, response_avg, response_max].map(humanize::count);
+      outln!("│ {directory:<width$} │ {count:>8} │ {request_total:>10} │ {request_avg:>8} │ {request_max:>8} │ {response_total:>10} │ {response_avg:>8} │ {response_max:>8} │");
+    };
+    outln!("\nPayload sizes (bytes):");
+    outln!("┌{line}┬──────────┬────────────┬──────────┬──────────┬────────────┬──────────┬──────────┐");
+    outln!(
+      "│ {:<width$} │ Requests │  Req total │  Req avg │  Req max │ Resp total │ Resp avg │ Resp max │",
+      "Directory"
+    );
+    outln!("├{line}┼──────────┼────────────┼──────────┼──────────┼────────────┼──────────┼──────────┤");
+    for (directory, sizes) in &self.payload_sizes {
+      row(directory, sizes);
+    }
+    outln!("├{line}┼──────────┼────────────┼──────────┼──────────┼────────────┼──────────┼──────────┤");
+    row("Total", &total);
+    outln!("└{line}┴──────────┴────────────┴──────────┴──────────┴────────────┴──────────┴──────────┘");
+  }
+
+  /// Displays the number of requests, latencies and throughput per type of evaluated invocable.
+  /// Throughput is the number of requests divided by the wall-clock time from the start of the first
+  /// to the end of the last request of the given type.
+  pub fn display_invocable_types_report(&self) {
+    if self.type_latencies.is_empty() {
+      return;
+    }
+    let width = self.type_latencies.keys().map(|typ| typ.chars().count()).max().unwrap_or_default().max(4);
+    let line = "─".repeat(width + 2);
+    outln!("\nInvocable types:");
+    outln!("┌{line}┬──────────┬────────────┬────────────┬────────────┬──────────┐");
+    outln!("│ {:<width$} │ Requests │        Avg │        p95 │        Max │    Req/s │", "Type");
+    outln!("├{line}┼──────────┼────────────┼────────────┼────────────┼──────────┤");
+    for (typ, latencies) in &self.type_latencies {
+      let count = latencies.histogram.count() as usize;
+      let elapsed = latencies.elapsed();
+      let requests_per_second = if elapsed > Duration::ZERO { count as f64 / elapsed.as_secs_f64() } else { 0.0 };
+      let average = humanize::duration(latencies.histogram.mean());
+      let p95 = humanize::duration(latencies.histogram.quantile(0.95));
+      let max = humanize::duration(latencies.histogram.max());
+      let (count, requests_per_second) = (humanize::count(count), humanize::count(requests_per_second.round() as usize));
+      outln!("│ {typ:<width$} │ {count:>8} │ {average:>10} │ {p95:>10} │ {max:>10} │ {requests_per_second:>8} │");
+    }
+    outln!("└{line}┴──────────┴────────────┴────────────┴────────────┴──────────┘");
+  }
+
+  /// Displays the number of failed tests per failure class, the most frequent class first.
+  pub fn display_failure_classes_report(&self) {
+    if self.failure_classes.is_empty() {
+      return;
+    }
+    let mut failure_classes = self.failure_classes.iter().collect::<Vec<(&String, &usize)>>();
+    failure_classes.sort_by(|(lhs_class, lhs_count), (rhs_class, rhs_count)| rhs_count.cmp(lhs_count).then(lhs_class.cmp(rhs_class)));
+    let width = failure_classes.iter().map(|(class, _)| class.chars().count()).max().unwrap_or_default().max(5);
+    let line = "─".repeat(width + 2);
+    outln!("\nFailure classes:");
+    outln!("┌{line}┬─────────┬─────────┐");
+    outln!("│ {:<width$} │   Fails │ Percent │", "Class");
+    outln!("├{line}┼─────────┼─────────┤");
+    for (class, count) in failure_classes {
+      let perc = *count as f64 * 100.0 / self.failure_count as f64;
+      let count = humanize::count(*count);
+      outln!("│ {class:<width$} │ {1}{count:>7}{0} │{1}{perc:>7.2}%{0} │", COLOR_RESET, COLOR_RED);
+    }
+    outln!("└{line}┴─────────┴─────────┘");
+  }
+
+  /// Registers the test with inconsistent results of repeated executions.
+  pub fn add_flaky_test(&mut self, test: &TestInfo, passed: usize, total: usize) {
+    self.flaky_tests.insert(test.name(), (passed, total));
+  }
+
+  /// Displays tests with inconsistent results of repeated executions.
+  pub fn display_flaky_tests_report(&self) {
+    if self.repeat > 1 {
+      outln!(
+        "\nFlaky tests: {1}{2}{0}",
+        COLOR_RESET,
+        if self.flaky_tests.is_empty() { COLOR_GREEN } else { COLOR_RED },
+        self.flaky_tests.len()
+      );
+      for (test_name, (passed, total)) in &self.flaky_tests {
+        outln!("  {1}{2}{0} passed {3} of {4} executions", COLOR_RESET, COLOR_YELLOW, test_name, passed, total);
+      }
+    }
+  }
+
+  pub fn display_test_cases_report(&self) {
+    let (success_count, failure_count) = self.test_case_counts();
+    let total_count = success_count + failure_count;
     let (success_perc, failure_perc) = Self::calc_perc(total_count, success_count, failure_count);
-    println!("\nTest cases:");
-    println!("┌─────────┬───────┬─────────┐");
-    println!("│   Total │ {total_count:>5} │         │");
-    println!("├─────────┼───────┼─────────┤");
-    println!("│ {1}Success{0} │ {1}{success_count:>5}{0} │{1}{success_perc:>7.2}%{0} │", COLOR_RESET, COLOR_GREEN);
-    println!(
-      "│ {1}Failure{0} │ {1}{failure_count:>5}{0} │{1}{failure_perc:>7.2}%{0} │",
+    let (total, success, failure) = (humanize::count(total_count), humanize::count(success_count), humanize::count(failure_count));
+    outln!("\nTest cases:");
+    outln!("┌─────────┬─────────┬─────────┐");
+    outln!("│   Total │ {total:>7} │         │");
+    outln!("├─────────┼─────────┼─────────┤");
+    outln!("│ {1}Success{0} │ {1}{success:>7}{0} │{1}{success_perc:>7.2}%{0} │", COLOR_RESET, COLOR_GREEN);
+    outln!(
+      "│ {1}Failure{0} │ {1}{failure:>7}{0} │{1}{failure_perc:>7.2}%{0} │",
       COLOR_RESET,
       if failure_count > 0 { COLOR_RED } else { COLOR_BRIGHT_WHITE }
     );
-    println!("└─────────┴───────┴─────────┘");
-
-    // write TCK report
-    for key @ (test_directory, test_file, test_case_id) in &total {
-      if success.contains(key) {
-        writeln!(
-          self.tck_report_writer,
-          r#""{}","{}","{}","{}","""#,
-          test_directory,
-          test_file,
-          test_case_id,
-          TestResult::Success,
-        )
-        .unwrap_or_else(|e| panic!("writing line to TCK report failed with reason: {}", e));
-      }
-      if self.test_case_failure.contains_key(key) {
-        writeln!(
-          self.tck_report_writer,
-          r#""{}","{}","{}","{}","{}""#,
-          test_directory,
-          test_file,
-          test_case_id,
-          TestResult::Failure,
-          self.test_case_failure.get(key).unwrap().join(",")
-        )
-        .unwrap_or_else(|e| panic!("writing line to TCK report failed with reason: {}", e));
-      }
+    outln!("└─────────┴─────────┴─────────┘");
+  }
+
+  /// Returns the percentage of test cases that have passed.
+  pub fn test_cases_success_perc(&self) -> f64 {
+    let (success_count, failure_count) = self.test_case_counts();
+    Self::calc_perc(success_count + failure_count, success_count, failure_count).0
+  }
+
+  /// Writes the dashboard, when configured.
+  pub fn write_dashboard(&mut self, dashboard_file: &str) {
+    if let Some(dashboard) = &mut self.dashboard {
+      dashboard.write(dashboard_file);
+    }
+  }
+
+  /// Returns tests that have passed in the previous run and failed in the current run,
+  /// regressions are available only when history database is configured.
+  pub fn regressions(&self) -> Vec<String> {
+    self.history.as_ref().map(|history| history.regressions()).unwrap_or_default()
+  }
+
+  /// Writes TCK report, flushes all buffered report writers and closes the history of the current run.
+  pub fn flush(&mut self) {
+    if let Some(tui) = self.tui.take() {
+      tui.finish();
+    }
+    self.write_tck_report();
+    self.write_labels_report();
+    if let Some(report_writer) = &mut self.report_writer {
+      report_writer.flush();
+    }
+    for writer in [self.diff_writer.as_mut(), self.timing_writer.as_mut()].into_iter().flatten() {
+      writer.flush().unwrap_or_else(|e| panic!("flushing report failed with reason: {}", e));
+    }
+    self.finish_history();
+    self.save_cache();
+    self.write_latency_histogram();
+  }
+
+  /// Returns the compiled pattern of expected error message, compiling the pattern only once.
+  pub fn error_pattern(&mut self, message: &str) -> Result<Regex, String> {
+    if let Some(pattern) = self.error_patterns.get(message) {
+      return Ok(pattern.clone());
+    }
+    let pattern = Regex::new(message).map_err(|reason| reason.to_string())?;
+    self.error_patterns.insert(message.to_string(), pattern.clone());
+    Ok(pattern)
+  }
+
+  /// Returns `true` when the number of failed tests has reached configured maximum.
+  pub fn max_failures_reached(&self) -> bool {
+    self.max_failures.is_some_and(|max_failures| self.failure_count >= max_failures)
+  }
+
+  /// Returns `true` when only some of the discovered test cases are executed,
+  /// because tests are filtered by changes, by test case names or by previous failures.
+  pub fn is_filtered(&self) -> bool {
+    (self.changed_only && self.cache.is_some()) || self.test_case_name_pattern.is_some() || self.previous_failures.is_some()
+  }
+
+  /// Returns the expected and the actual number of executed test cases, when they differ.
+  /// The number of executed test cases is not checked when tests are filtered.
+  pub fn unexpected_test_count(&self) -> Option<(usize, usize)> {
+    let (success_count, failure_count) = self.test_case_counts();
+    self
+      .expected_tests
+      .filter(|_| !self.is_filtered())
+      .filter(|expected_tests| *expected_tests != success_count + failure_count)
+      .map(|expected_tests| (expected_tests, success_count + failure_count))
+  }
+
+  /// Returns `true` when no more tests should be executed, because the run was interrupted
+  /// or the number of failed tests has reached configured maximum.
+  pub fn is_stopped(&self) -> bool {
+    interrupt::is_interrupted() || self.max_failures_reached()
+  }
+
+  /// Emits the progress event, when the stream of events is open.
+  pub fn emit(&mut self, event: &Event) {
+    if let Some(events) = &mut self.events {
+      events.emit(event);
+    }
+  }
+
+  /// Records the latency of a single request.
+  pub fn record_latency(&mut self, latency: Duration) {
+    if let Some((histogram, _)) = &mut self.latency_histogram {
+      histogram.record(latency);
+    }
+  }
+
+  /// Writes the histogram of request latencies, when configured.
+  pub fn write_latency_histogram(&self) {
+    if let Some((histogram, file_name)) = &self.latency_histogram {
+      histogram.write(file_name);
+    }
+  }
+
+  /// Writes the cache of test file hashes, when configured.
+  pub fn save_cache(&self) {
+    if let Some(cache) = &self.cache {
+      cache.save();
+    }
+  }
+
+  /// Returns the tests root directory containing specified path, the most nested one when roots are nested.
+  /// Returns an empty string when the path is not placed in any of the tests root directories.
+  pub fn root_dir_path(&self, path: &str) -> &str {
+    self
+      .root_dir_paths
+      .iter()
+      .filter(|root_dir_path| path.starts_with(root_dir_path.as_str()))
+      .max_by_key(|root_dir_path| root_dir_path.len())
+      .map_or("", String::as_str)
+  }
+
+  /// Returns specified path relative to the tests root directory containing it.
+  pub fn relative_path(&self, path: &str) -> String {
+    dir_name_stripped_prefix(path, self.root_dir_path(path))
+  }
+
+  /// Returns specified path displayed as configured: relative to the tests root directory containing it,
+  /// as a full path, or relative to the tests root directory preceded by configured prefix.
+  /// Paths placed outside of tests root directories are displayed as full paths.
+  pub fn display_path(&self, path: &str) -> String {
+    let relative_path = self.relative_path(path);
+    match self.path_display {
+      PathDisplay::Absolute => path.to_string(),
+      _ if relative_path == path => relative_path,
+      PathDisplay::Relative => relative_path,
+      PathDisplay::Prefixed => format!("{}/{}", self.path_prefix.trim_end_matches('/'), relative_path),
+    }
+  }
+
+  /// Stores the summary of the current run in the history database, when configured.
+  pub fn finish_history(&mut self) {
+    if let Some(history) = self.history.take() {
+      history.finish(self.success_count, self.failure_count, self.execution_time / 1_000);
     }
   }
 
@@ -235,6 +1317,42 @@ impl Context {
   }
 }
 
+/// Writes the value serialized to pretty JSON into specified directory, in the file named after test identifier.
+fn write_test_json<T: Serialize>(dir: &Path, test: &TestInfo, value: &T) {
+  fs::create_dir_all(dir).unwrap_or_else(|e| panic!("creating directory {} failed with reason: {}", dir.display(), e));
+  // test cases without identifier are identified by their position in the test file
+  let name = if test.test_case_id.is_empty() {
+    format!("test-case-{}{}", test.test_case_index + 1, test.test_id)
+  } else {
+    test.test_id.clone()
+  };
+  let file = dir.join(format!("{}.json", name.replace(':', "_")));
+  let content = serde_json::to_string_pretty(value).unwrap_or_else(|e| panic!("serializing {} failed with reason: {}", file.display(), e));
+  fs::write(&file, content).unwrap_or_else(|e| panic!("writing file {} failed with reason: {}", file.display(), e));
+}
+
+/// Removes JSON files from the directory and its subdirectories, together with directories left empty.
+fn remove_json_files(dir: &Path) {
+  let Ok(entries) = fs::read_dir(dir) else {
+    return;
+  };
+  for path in entries.flatten().map(|entry| entry.path()) {
+    if path.is_dir() {
+      remove_json_files(&path);
+      // directories containing other files are not removed
+      let _ = fs::remove_dir(&path);
+    } else if path.extension().is_some_and(|extension| extension == "json") {
+      fs::remove_file(&path).unwrap_or_else(|e| panic!("removing file {} failed with reason: {}", path.display(), e));
+    }
+  }
+}
+
+/// Creates the output file with buffered writer.
+fn create_writer(file_name: &str) -> BufWriter<File> {
+  let file = File::create(file_name).unwrap_or_else(|e| panic!("creating output file {} failed with reason: {}", file_name, e));
+  BufWriter::new(file)
+}
+
 /// Retrieves the parent path without file name from given `name`.
 pub fn dir_name(name: &str) -> String {
   Path::new(name).parent().unwrap().to_str().unwrap().to_string()
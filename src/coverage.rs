@@ -0,0 +1,91 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Coverage of models and invocables by test cases
+
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET, COLOR_YELLOW};
+use std::collections::BTreeMap;
+
+/// Names of DMN elements that can be invoked in tests.
+pub const INVOCABLE_ELEMENTS: [&str; 3] = ["decision", "businessKnowledgeModel", "decisionService"];
+
+/// Coverage of discovered models and their invocables.
+#[derive(Default)]
+pub struct Coverage {
+  /// Flags indicating if invocables were exercised, indexed by model path and invocable name.
+  models: BTreeMap<String, BTreeMap<String, bool>>,
+}
+
+impl Coverage {
+  /// Registers a discovered model with all its invocables.
+  pub fn add_model(&mut self, model_path: &str, invocable_names: Vec<String>) {
+    self.models.insert(model_path.to_string(), invocable_names.into_iter().map(|name| (name, false)).collect());
+  }
+
+  /// Marks the invocable as exercised by a test.
+  pub fn mark_exercised(&mut self, model_path: &str, invocable_name: &str) {
+    if let Some(invocables) = self.models.get_mut(model_path) {
+      invocables.insert(invocable_name.to_string(), true);
+    }
+  }
+
+  /// Marks all invocables as not exercised, keeping registered models.
+  pub fn reset(&mut self) {
+    self
+      .models
+      .values_mut()
+      .flat_map(|invocables| invocables.values_mut())
+      .for_each(|exercised| *exercised = false);
+  }
+
+  /// Displays models and invocables that were never exercised by any test,
+  /// model paths are displayed as returned by specified function, relative to root directories.
+  pub fn display_report(&self, relative_path: impl Fn(&str) -> String) {
+    let total_count = self.models.values().map(|invocables| invocables.len()).sum::<usize>();
+    let exercised_count = self.models.values().flat_map(|invocables| invocables.values()).filter(|exercised| **exercised).count();
+    let color = if exercised_count == total_count { COLOR_GREEN } else { COLOR_RED };
+    outln!("\nModel coverage: {1}{2}{0} of {3} invocables exercised", COLOR_RESET, color, exercised_count, total_count);
+    for (model_path, invocables) in &self.models {
+      let model_path = relative_path(model_path);
+      let missing = invocables
+        .iter()
+        .filter(|(_, exercised)| !**exercised)
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>();
+      if missing.len() == invocables.len() && !invocables.is_empty() {
+        outln!("  {1}{2}{0}: model never exercised", COLOR_RESET, COLOR_YELLOW, model_path);
+      } else if !missing.is_empty() {
+        outln!("  {1}{2}{0}: {3}", COLOR_RESET, COLOR_YELLOW, model_path, missing.join(", "));
+      }
+    }
+  }
+}
@@ -0,0 +1,90 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Reproduction of evaluation requests with curl
+
+use crate::config::ConfigurationParams;
+use crate::params::EvaluateParams;
+use std::net::{IpAddr, SocketAddr};
+
+/// Builder of curl commands sending the same evaluation requests to engine as the runner does.
+pub struct Curl {
+  /// URL to service where model definitions are evaluated.
+  evaluate_url: String,
+  /// Optional IP address the host of evaluate URL is resolved to.
+  endpoint_address: Option<String>,
+  /// Optional path to unix domain socket engine listens on.
+  endpoint_socket: Option<String>,
+}
+
+impl Curl {
+  /// Creates the builder of curl commands connecting to engine configured for the runner.
+  pub fn new(config: &ConfigurationParams) -> Self {
+    Self {
+      evaluate_url: config.evaluate_url.clone(),
+      endpoint_address: config.endpoint_address.clone(),
+      endpoint_socket: config.endpoint_socket.clone(),
+    }
+  }
+
+  /// Returns the ready-to-paste curl command sending the evaluation request.
+  pub fn command(&self, params: &EvaluateParams) -> String {
+    let body = serde_json::to_string(params).unwrap_or_else(|e| panic!("serializing request failed with reason: {}", e));
+    let mut command = "curl -sS -X POST".to_string();
+    if let Some(endpoint_socket) = &self.endpoint_socket {
+      command.push_str(&format!(" --unix-socket {}", quote(endpoint_socket)));
+    } else if let Some(resolve) = self.resolve() {
+      command.push_str(&format!(" --resolve {}", quote(&resolve)));
+    }
+    command.push_str(&format!(
+      " -H {} --data-raw {} {}",
+      quote("Content-Type: application/json"),
+      quote(&body),
+      quote(&self.evaluate_url)
+    ));
+    command
+  }
+
+  /// Returns the value of `--resolve` option, resolving the host of evaluate URL to configured address.
+  fn resolve(&self) -> Option<String> {
+    let address = self.endpoint_address.as_ref()?;
+    let url = url::Url::parse(&self.evaluate_url).ok()?;
+    let ip = address.parse::<SocketAddr>().map(|address| address.ip()).or_else(|_| address.parse::<IpAddr>()).ok()?;
+    let ip = if ip.is_ipv6() { format!("[{}]", ip) } else { ip.to_string() };
+    Some(format!("{}:{}:{}", url.host_str()?, url.port_or_known_default()?, ip))
+  }
+}
+
+/// Returns the argument quoted for POSIX shell.
+fn quote(argument: &str) -> String {
+  format!("'{}'", argument.replace('\'', r"'\''"))
+}
@@ -0,0 +1,259 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Interactive HTML dashboard with results of all tests
+
+use crate::context::TestInfo;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// Single row of the dashboard.
+#[derive(Serialize)]
+struct DashboardRow {
+  /// Tests root directory containing the test file.
+  root: String,
+  /// Directory of the test file.
+  directory: String,
+  /// Test file name without extension.
+  file: String,
+  /// Identifier of the test.
+  test_id: String,
+  /// Labels defined in the test file.
+  labels: Vec<String>,
+  /// Metadata defined in extension elements.
+  metadata: BTreeMap<String, String>,
+  /// Version of the DMN specification of the test file.
+  spec_version: String,
+  /// Test result.
+  result: String,
+  /// Failure category.
+  category: String,
+  /// Failure remarks.
+  remarks: String,
+  /// Evaluation duration in microseconds.
+  duration_us: u128,
+  /// Unified diff of expected and actual value.
+  diff: Option<String>,
+}
+
+/// Dashboard collecting the results of all tests.
+/// Rows are streamed into a temporary file next to the dashboard file, one JSON row per line,
+/// only the last row is kept in memory until its diff is added.
+pub struct Dashboard {
+  /// Path of the temporary file with rows.
+  rows_file: String,
+  /// Writer of the temporary file with rows.
+  rows_writer: BufWriter<File>,
+  /// The last added row, waiting for its unified diff.
+  pending_row: Option<DashboardRow>,
+  /// Metadata of the run, displayed above the results.
+  run_metadata: BTreeMap<String, String>,
+}
+
+impl Dashboard {
+  /// Creates an empty dashboard of the run with specified metadata, written to specified file.
+  pub fn new(file_name: &str, run_metadata: &BTreeMap<String, String>) -> Self {
+    let rows_file = format!("{}.rows", file_name);
+    let rows_writer = File::create(&rows_file)
+      .map(BufWriter::new)
+      .unwrap_or_else(|e| panic!("creating dashboard rows file {} failed with reason: {}", rows_file, e));
+    Self {
+      rows_file,
+      rows_writer,
+      pending_row: None,
+      run_metadata: run_metadata.clone(),
+    }
+  }
+
+  /// Adds the result of a single test.
+  pub fn add_row(&mut self, test: &TestInfo, result: &str, category: &str, remarks: &str, duration_us: u128) {
+    self.write_pending_row();
+    self.pending_row = Some(DashboardRow {
+      root: test.root.clone(),
+      directory: test.directory.clone(),
+      file: test.file.clone(),
+      test_id: test.test_id.clone(),
+      labels: test.labels.clone(),
+      metadata: test.metadata.clone(),
+      spec_version: test.spec_version.clone(),
+      result: result.to_string(),
+      category: category.to_string(),
+      remarks: remarks.to_string(),
+      duration_us,
+      diff: None,
+    });
+  }
+
+  /// Adds the unified diff of expected and actual value of the test, diffs are reported right after test results.
+  pub fn add_diff(&mut self, test: &TestInfo, diff: String) {
+    if let Some(row) = &mut self.pending_row {
+      if row.root == test.root && row.directory == test.directory && row.file == test.file && row.test_id == test.test_id {
+        row.diff = Some(diff);
+      }
+    }
+  }
+
+  /// Writes the pending row into the temporary file with rows.
+  fn write_pending_row(&mut self) {
+    if let Some(row) = self.pending_row.take() {
+      let line = serde_json::to_string(&row).unwrap_or_else(|e| panic!("serializing dashboard data failed with reason: {}", e));
+      writeln!(self.rows_writer, "{}", line).unwrap_or_else(|e| panic!("writing dashboard rows file {} failed with reason: {}", self.rows_file, e));
+    }
+  }
+
+  /// Writes the dashboard as a single self-contained HTML file.
+  pub fn write(&mut self, file_name: &str) {
+    self.write_pending_row();
+    self
+      .rows_writer
+      .flush()
+      .unwrap_or_else(|e| panic!("writing dashboard rows file {} failed with reason: {}", self.rows_file, e));
+    self
+      .write_html(file_name)
+      .unwrap_or_else(|e| panic!("writing dashboard file {} failed with reason: {}", file_name, e));
+  }
+
+  /// Writes the HTML file, copying rows from the temporary file in place of the `DATA` placeholder.
+  fn write_html(&self, file_name: &str) -> io::Result<()> {
+    let run_metadata = serde_json::to_string(&self.run_metadata)
+      .unwrap_or_else(|e| panic!("serializing run metadata failed with reason: {}", e))
+      .replace("</", "<\\/");
+    let html = TEMPLATE.replace("/*RUN*/{}", &run_metadata);
+    let (head, tail) = html.split_once("/*DATA*/[]").unwrap_or((&html, ""));
+    let mut writer = BufWriter::new(File::create(file_name)?);
+    write!(writer, "{}[", head)?;
+    for (index, line) in BufReader::new(File::open(&self.rows_file)?).lines().enumerate() {
+      if index > 0 {
+        write!(writer, ",")?;
+      }
+      write!(writer, "{}", line?.replace("</", "<\\/"))?;
+    }
+    write!(writer, "]{}", tail)?;
+    writer.flush()
+  }
+}
+
+impl Drop for Dashboard {
+  /// Removes the temporary file with rows.
+  fn drop(&mut self) {
+    let _ = fs::remove_file(&self.rows_file);
+  }
+}
+
+/// Template of the dashboard page, test results are injected in place of the `DATA` placeholder.
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>DMN TCK results</title>
+<style>
+  body { font-family: sans-serif; margin: 16px; color: #222; }
+  .filters { display: flex; gap: 8px; flex-wrap: wrap; margin-bottom: 12px; }
+  .filters input, .filters select { padding: 4px; }
+  table { border-collapse: collapse; width: 100%; font-size: 13px; }
+  th, td { border-bottom: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: top; }
+  th { background: #f4f4f4; position: sticky; top: 0; }
+  tr.ERROR td.result { color: #c00; font-weight: bold; }
+  tr.SUCCESS td.result { color: #080; }
+  tr.WARNING td.result { color: #b80; }
+  tr.expandable { cursor: pointer; }
+  td.duration { text-align: right; }
+  pre { margin: 0; background: #f8f8f8; padding: 8px; }
+  .del { color: #c00; } .ins { color: #080; }
+  #summary { margin-bottom: 12px; }
+  #run { margin-bottom: 8px; color: #555; }
+</style>
+</head>
+<body>
+<h2>DMN TCK results</h2>
+<div id="run"></div>
+<div id="summary"></div>
+<div class="filters">
+  <input id="search" type="search" placeholder="Search...">
+  <select id="directory"><option value="">All directories</option></select>
+  <select id="label"><option value="">All labels</option></select>
+  <select id="status"><option value="">All results</option><option>SUCCESS</option><option>WARNING</option><option>ERROR</option><option>SKIPPED</option></select>
+  <input id="duration" type="number" min="0" placeholder="Min. duration [µs]">
+</div>
+<table>
+  <thead><tr><th>Directory</th><th>File</th><th>Test</th><th>Result</th><th>Category</th><th>Remarks</th><th>Metadata</th><th>Duration [µs]</th></tr></thead>
+  <tbody id="rows"></tbody>
+</table>
+<script>
+const data = /*DATA*/[];
+const run = /*RUN*/{};
+const byId = (id) => document.getElementById(id);
+const text = (value) => { const span = document.createElement('span'); span.textContent = value; return span.innerHTML; };
+byId('run').textContent = Object.entries(run).map(([key, value]) => `${key}: ${value}`).join(' | ');
+const fill = (select, values) => [...new Set(values)].sort().forEach((value) => select.add(new Option(value, value)));
+fill(byId('directory'), data.map((row) => row.directory));
+fill(byId('label'), data.flatMap((row) => row.labels));
+const metadata = (row) => Object.entries(row.metadata).map(([key, value]) => `${key}=${value}`).join(', ');
+const renderDiff = (diff) => diff.split('\n').map((line) => {
+  const cls = line.startsWith('-') ? 'del' : line.startsWith('+') ? 'ins' : '';
+  return `<span class="${cls}">${text(line)}</span>`;
+}).join('\n');
+function render() {
+  const search = byId('search').value.toLowerCase();
+  const directory = byId('directory').value;
+  const label = byId('label').value;
+  const status = byId('status').value;
+  const duration = Number(byId('duration').value || 0);
+  const rows = data.filter((row) =>
+    (!search || `${row.directory}/${row.file}:${row.test_id} ${row.remarks} ${metadata(row)}`.toLowerCase().includes(search)) &&
+    (!directory || row.directory === directory) &&
+    (!label || row.labels.includes(label)) &&
+    (!status || row.result === status) &&
+    row.duration_us >= duration);
+  const failures = rows.filter((row) => row.result === 'ERROR').length;
+  byId('summary').textContent = `Displayed ${rows.length} of ${data.length} tests, ${failures} failure(s).`;
+  byId('rows').innerHTML = rows.map((row, index) => {
+    const expandable = row.diff ? ' expandable' : '';
+    const main = `<tr class="${row.result}${expandable}" data-index="${index}"><td title="${text(row.root)}">${text(row.directory)}</td><td title="DMN ${text(row.spec_version)}">${text(row.file)}</td>` +
+      `<td>${text(row.test_id)}</td><td class="result">${row.result}</td><td>${text(row.category)}</td><td>${text(row.remarks)}</td><td>${text(metadata(row))}</td>` +
+      `<td class="duration">${row.duration_us}</td></tr>`;
+    const diff = row.diff ? `<tr class="diff" hidden><td colspan="8"><pre>${renderDiff(row.diff)}</pre></td></tr>` : '';
+    return main + diff;
+  }).join('');
+}
+byId('rows').addEventListener('click', (event) => {
+  const row = event.target.closest('tr.expandable');
+  if (row && row.nextElementSibling) { row.nextElementSibling.hidden = !row.nextElementSibling.hidden; }
+});
+['search', 'directory', 'label', 'status', 'duration'].forEach((id) => byId(id).addEventListener('input', render));
+render();
+</script>
+</body>
+</html>
+"#;
@@ -0,0 +1,244 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Unified and side-by-side diff of expected and actual values
+
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET};
+use std::ops::Range;
+
+/// Number of unchanged lines displayed around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Maximal width of the column in side-by-side diff, longer lines are truncated.
+const MAX_COLUMN_WIDTH: usize = 80;
+
+/// Marker of truncated lines.
+const ELLIPSIS: char = '…';
+
+/// Single line operation in the edit script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+  /// Line is present in both texts.
+  Equal(usize, usize),
+  /// Line is present only in expected text.
+  Delete(usize),
+  /// Line is present only in actual text.
+  Insert(usize),
+}
+
+/// Returns the unified diff of two texts, empty when texts are equal.
+pub fn unified(expected_label: &str, expected: &str, actual_label: &str, actual: &str) -> String {
+  let expected_lines = expected.lines().collect::<Vec<&str>>();
+  let actual_lines = actual.lines().collect::<Vec<&str>>();
+  let edits = edit_script(&expected_lines, &actual_lines);
+  if edits.iter().all(|edit| matches!(edit, Edit::Equal(_, _))) {
+    return String::new();
+  }
+  let mut output = format!("--- {}\n+++ {}\n", expected_label, actual_label);
+  for (start, end) in hunks(&edits) {
+    let hunk = &edits[start..end];
+    let (expected_start, actual_start) = hunk_start(&edits, start);
+    let expected_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Insert(_))).count();
+    let actual_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Delete(_))).count();
+    output.push_str(&format!(
+      "@@ -{},{} +{},{} @@\n",
+      hunk_line(expected_start, expected_count),
+      expected_count,
+      hunk_line(actual_start, actual_count),
+      actual_count
+    ));
+    for edit in hunk {
+      match edit {
+        Edit::Equal(i, _) => output.push_str(&format!(" {}\n", expected_lines[*i])),
+        Edit::Delete(i) => output.push_str(&format!("-{}\n", expected_lines[*i])),
+        Edit::Insert(j) => output.push_str(&format!("+{}\n", actual_lines[*j])),
+      }
+    }
+  }
+  output
+}
+
+/// Returns expected and actual text displayed side by side, lines are aligned using the edit script.
+/// Only the differing part of changed lines is highlighted, lines wider than the column are truncated.
+pub fn side_by_side(expected: &str, actual: &str) -> String {
+  let expected_lines = expected.lines().collect::<Vec<&str>>();
+  let actual_lines = actual.lines().collect::<Vec<&str>>();
+  let width = expected_lines
+    .iter()
+    .chain(&actual_lines)
+    .map(|line| line.chars().count())
+    .max()
+    .unwrap_or_default()
+    .min(MAX_COLUMN_WIDTH);
+  let mut rows: Vec<(Option<&str>, Option<&str>)> = vec![];
+  let (mut deleted, mut inserted) = (vec![], vec![]);
+  let edits = edit_script(&expected_lines, &actual_lines);
+  for edit in edits.iter().chain([Edit::Equal(usize::MAX, usize::MAX)].iter()) {
+    match *edit {
+      Edit::Delete(i) => deleted.push(expected_lines[i]),
+      Edit::Insert(j) => inserted.push(actual_lines[j]),
+      Edit::Equal(i, j) => {
+        // deleted and inserted lines between equal lines are displayed as changed lines
+        for index in 0..deleted.len().max(inserted.len()) {
+          rows.push((deleted.get(index).copied(), inserted.get(index).copied()));
+        }
+        deleted.clear();
+        inserted.clear();
+        if i != usize::MAX {
+          rows.push((Some(expected_lines[i]), Some(actual_lines[j])));
+        }
+      }
+    }
+  }
+  let mut output = String::new();
+  for (expected_line, actual_line) in rows {
+    let (marker, expected_range, actual_range) = match (expected_line, actual_line) {
+      (Some(a), Some(b)) if a == b => (' ', 0..0, 0..0),
+      (Some(a), Some(b)) => {
+        let (expected_range, actual_range) = differing_ranges(a, b);
+        ('|', expected_range, actual_range)
+      }
+      (Some(a), None) => ('<', 0..a.chars().count(), 0..0),
+      _ => ('>', 0..0, 0..actual_line.unwrap_or_default().chars().count()),
+    };
+    output.push_str(&format!(
+      "{} {} {}\n",
+      marker,
+      render_cell(expected_line.unwrap_or_default(), expected_range, COLOR_GREEN, width, true),
+      render_cell(actual_line.unwrap_or_default(), actual_range, COLOR_RED, width, false)
+    ));
+  }
+  output
+}
+
+/// Returns the ranges of characters differing in two lines, after removing their common prefix and suffix.
+fn differing_ranges(a: &str, b: &str) -> (Range<usize>, Range<usize>) {
+  let (a, b) = (a.chars().collect::<Vec<char>>(), b.chars().collect::<Vec<char>>());
+  let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+  let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+  (prefix..a.len() - suffix, prefix..b.len() - suffix)
+}
+
+/// Renders the line in the column of specified width, characters in the range are highlighted in specified color.
+/// Lines wider than the column are truncated with ellipsis, highlighted when the truncated part differs.
+fn render_cell(line: &str, highlight: Range<usize>, color: &str, width: usize, padded: bool) -> String {
+  let chars = line.chars().collect::<Vec<char>>();
+  let truncated = chars.len() > width;
+  let visible = if truncated { width.saturating_sub(1) } else { chars.len() };
+  let mut cell = String::new();
+  for (index, ch) in chars[..visible].iter().enumerate() {
+    if index == highlight.start && !highlight.is_empty() {
+      cell.push_str(color);
+    }
+    cell.push(*ch);
+    if index + 1 == highlight.end && !highlight.is_empty() {
+      cell.push_str(COLOR_RESET);
+    }
+  }
+  if truncated {
+    if highlight.end > visible {
+      if highlight.start >= visible {
+        cell.push_str(color);
+      }
+      cell.push(ELLIPSIS);
+      cell.push_str(COLOR_RESET);
+    } else {
+      cell.push(ELLIPSIS);
+    }
+  }
+  if padded {
+    cell.push_str(&" ".repeat(width.saturating_sub(visible + usize::from(truncated))));
+  }
+  cell
+}
+
+/// Builds the shortest edit script using the longest common subsequence of lines.
+fn edit_script(a: &[&str], b: &[&str]) -> Vec<Edit> {
+  let (n, m) = (a.len(), b.len());
+  let mut lcs = vec![vec![0_usize; m + 1]; n + 1];
+  for i in (0..n).rev() {
+    for j in (0..m).rev() {
+      lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+    }
+  }
+  let mut edits = vec![];
+  let (mut i, mut j) = (0, 0);
+  while i < n || j < m {
+    if i < n && j < m && a[i] == b[j] {
+      edits.push(Edit::Equal(i, j));
+      i += 1;
+      j += 1;
+    } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+      edits.push(Edit::Delete(i));
+      i += 1;
+    } else {
+      edits.push(Edit::Insert(j));
+      j += 1;
+    }
+  }
+  edits
+}
+
+/// Returns ranges of edits grouped into hunks, including surrounding context lines.
+fn hunks(edits: &[Edit]) -> Vec<(usize, usize)> {
+  let mut ranges: Vec<(usize, usize)> = vec![];
+  for (index, edit) in edits.iter().enumerate() {
+    if matches!(edit, Edit::Equal(_, _)) {
+      continue;
+    }
+    let start = index.saturating_sub(CONTEXT_LINES);
+    let end = (index + CONTEXT_LINES + 1).min(edits.len());
+    match ranges.last_mut() {
+      Some((_, last_end)) if start <= *last_end => *last_end = end,
+      _ => ranges.push((start, end)),
+    }
+  }
+  ranges
+}
+
+/// Returns line indexes in expected and actual text where the hunk starting at specified edit begins.
+fn hunk_start(edits: &[Edit], start: usize) -> (usize, usize) {
+  edits[..start].iter().fold((0, 0), |(i, j), edit| match edit {
+    Edit::Equal(_, _) => (i + 1, j + 1),
+    Edit::Delete(_) => (i + 1, j),
+    Edit::Insert(_) => (i, j + 1),
+  })
+}
+
+/// Returns the line number displayed in hunk header, for empty ranges it is the line before the hunk.
+fn hunk_line(start: usize, count: usize) -> usize {
+  if count > 0 {
+    start + 1
+  } else {
+    start
+  }
+}
@@ -32,8 +32,13 @@
 
 //! # Data transfer objects for input and output values
 
-use crate::model::{Component, InputNode, List, Simple, Value};
+use crate::compare::{canonical_integer, collapse_whitespace, TYPE_ANY_URI, TYPE_BASE64_BINARY, TYPE_INTEGER};
+use crate::model::canonical_type_name;
+use crate::model::{Component, Function, InputNode, List, Simple, Value};
+use crate::params::EvaluateParams;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 /// Data transfer object for an error.
 #[derive(Debug, Deserialize)]
@@ -54,21 +59,56 @@ pub struct ResultDto<T> {
   pub errors: Option<Vec<ErrorDto>>,
 }
 
-impl<T> ToString for ResultDto<T> {
+impl<T> fmt::Display for ResultDto<T> {
   /// Converts results to string.
-  fn to_string(&self) -> String {
-    self
-      .errors
-      .as_ref()
-      .map(|v| v.iter().map(|e| e.detail.clone()).collect::<Vec<String>>().join(", "))
-      .unwrap_or_default()
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      self
+        .errors
+        .as_ref()
+        .map(|v| v.iter().map(|e| e.detail.clone()).collect::<Vec<String>>().join(", "))
+        .unwrap_or_default()
+    )
   }
 }
 
+/// Data transfer object for a failure artifact, containing all details needed to reproduce the failure.
+#[derive(Serialize)]
+pub struct FailureArtifactDto<'a> {
+  /// URL of the evaluation endpoint.
+  #[serde(rename = "url")]
+  pub evaluate_url: &'a str,
+  /// Resolved path to invocable.
+  #[serde(rename = "invocable")]
+  pub invocable_path: &'a str,
+  /// Request body sent to engine.
+  #[serde(rename = "request")]
+  pub request: &'a EvaluateParams,
+  /// Raw response body received from engine.
+  #[serde(rename = "response")]
+  pub response: Option<&'a str>,
+  /// Expected value.
+  #[serde(rename = "expected")]
+  pub expected: Option<ValueDto>,
+  /// Failure remarks.
+  #[serde(rename = "remarks")]
+  pub remarks: &'a str,
+  /// Metadata defined in extension elements.
+  #[serde(rename = "metadata", skip_serializing_if = "BTreeMap::is_empty")]
+  pub metadata: &'a BTreeMap<String, String>,
+  /// Curl command reproducing the evaluation request.
+  #[serde(rename = "curl", skip_serializing_if = "Option::is_none")]
+  pub curl: Option<&'a str>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InputNodeDto {
   #[serde(rename = "name")]
   pub name: String,
+  #[serde(rename = "namespace", skip_serializing_if = "Option::is_none")]
+  pub namespace: Option<String>,
   #[serde(rename = "value")]
   pub value: Option<ValueDto>,
 }
@@ -79,7 +119,7 @@ pub struct OptionalValueDto {
   pub value: Option<ValueDto>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ValueDto {
   #[serde(rename = "simple", skip_serializing_if = "Option::is_none")]
   pub simple: Option<SimpleDto>,
@@ -87,9 +127,49 @@ pub struct ValueDto {
   pub components: Option<Vec<ComponentDto>>,
   #[serde(rename = "list", skip_serializing_if = "Option::is_none")]
   pub list: Option<ListDto>,
+  #[serde(rename = "function", skip_serializing_if = "Option::is_none")]
+  pub function: Option<FunctionDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ValueDto {
+  /// Sorts components by name, including components of nested values.
+  /// Sorting is stable, so components with duplicated names retain their order.
+  pub fn sort_components(&mut self) {
+    if let Some(components) = &mut self.components {
+      components.sort_by(|a, b| a.name.cmp(&b.name));
+      components.iter_mut().filter_map(|component| component.value.as_mut()).for_each(ValueDto::sort_components);
+    }
+    if let Some(list) = &mut self.list {
+      list.items.iter_mut().for_each(ValueDto::sort_components);
+    }
+  }
+
+  /// Replaces type names having an alias with the aliased type name, including types of nested values.
+  pub fn replace_type_aliases(&mut self, aliases: &BTreeMap<String, String>) {
+    let replace = |typ: &mut Option<String>| {
+      if let Some(aliased) = typ.as_deref().and_then(|type_name| aliases.get(type_name.trim())) {
+        *typ = Some(aliased.clone());
+      }
+    };
+    if let Some(simple) = &mut self.simple {
+      replace(&mut simple.typ);
+    }
+    if let Some(function) = &mut self.function {
+      replace(&mut function.typ);
+    }
+    if let Some(components) = &mut self.components {
+      components
+        .iter_mut()
+        .filter_map(|component| component.value.as_mut())
+        .for_each(|value| value.replace_type_aliases(aliases));
+    }
+    if let Some(list) = &mut self.list {
+      list.items.iter_mut().for_each(|item| item.replace_type_aliases(aliases));
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimpleDto {
   #[serde(rename = "type")]
   pub typ: Option<String>,
@@ -99,43 +179,34 @@ pub struct SimpleDto {
   pub nil: bool,
 }
 
-impl PartialEq for SimpleDto {
-  fn eq(&self, rhs: &Self) -> bool {
-    // if self.typ.is_some()
-    //   && rhs.typ.is_some()
-    //   && (self.typ.as_ref().unwrap() == "xsd:decimal" || self.typ.as_ref().unwrap() == "xsd:double")
-    //   && (rhs.typ.as_ref().unwrap() == "xsd:decimal" || rhs.typ.as_ref().unwrap() == "xsd:double")
-    //   && self.nil == rhs.nil
-    // {
-    //   return compare_decimals(self.text.clone(), rhs.text.clone());
-    // }
-    self.typ == rhs.typ && self.text == rhs.text && self.nil == rhs.nil
+/// Returns the text of simple value in canonical form of its type, texts of other types are returned unchanged.
+fn canonical_text(type_name: Option<&str>, text: &str) -> String {
+  match type_name.map(canonical_type_name).as_deref() {
+    Some(TYPE_BASE64_BINARY) => text.split_whitespace().collect(),
+    Some(TYPE_INTEGER) => canonical_integer(text).unwrap_or(text.to_string()),
+    Some(TYPE_ANY_URI) => collapse_whitespace(text),
+    _ => text.to_string(),
   }
 }
 
-// ///
-// fn compare_decimals(actual: Option<String>, expected: Option<String>) -> bool {
-//   if let Some((actual_text, expected_text)) = actual.zip(expected.as_ref()) {
-//     if actual_text.starts_with(expected_text) {
-//       return true;
-//     }
-//     if actual_text.starts_with(&expected_text[..expected_text.len() - 1]) {
-//       //TODO report warning 1
-//       return true;
-//     }
-//     if actual_text.starts_with(&expected_text[..expected_text.len() - 2]) {
-//       //TODO report warning 2
-//       return true;
-//     }
-//     if actual_text.starts_with(&expected_text[..expected_text.len() - 3]) {
-//       //TODO report warning 3
-//       return true;
-//     }
-//   }
-//   false
-// }
-
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionDto {
+  #[serde(rename = "type")]
+  pub typ: Option<String>,
+  #[serde(rename = "text", skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+}
+
+impl From<&Function> for FunctionDto {
+  fn from(function: &Function) -> Self {
+    Self {
+      typ: function.typ.clone(),
+      text: function.text.clone(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentDto {
   #[serde(rename = "name")]
   pub name: Option<String>,
@@ -155,7 +226,7 @@ impl From<&Component> for ComponentDto {
   }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListDto {
   #[serde(rename = "items")]
   pub items: Vec<ValueDto>,
@@ -176,6 +247,7 @@ impl From<&InputNode> for InputNodeDto {
   fn from(input_node: &InputNode) -> Self {
     Self {
       name: input_node.name.clone(),
+      namespace: input_node.namespace.clone(),
       value: input_node.value.as_ref().map(|value| value.into()),
     }
   }
@@ -185,7 +257,7 @@ impl From<&Simple> for SimpleDto {
   fn from(simple: &Simple) -> Self {
     Self {
       typ: simple.typ.clone(),
-      text: simple.text.clone(),
+      text: simple.text.as_deref().map(|text| canonical_text(simple.typ.as_deref(), text)),
       nil: simple.nil,
     }
   }
@@ -206,6 +278,10 @@ impl From<&Value> for ValueDto {
         list: Some(ListDto::from(list)),
         ..Default::default()
       },
+      Value::Function(function) => Self {
+        function: Some(FunctionDto::from(function)),
+        ..Default::default()
+      },
     }
   }
 }
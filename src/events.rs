@@ -0,0 +1,129 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Stream of progress events in JSON lines format
+
+use crate::console::{self, Target};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// Event emitted in each step of the run.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+  /// Test files have been parsed and the execution of tests has started.
+  RunStarted {
+    /// Full paths of the test cases directories.
+    test_cases_dirs: &'a [String],
+    /// URL of the evaluation endpoint.
+    evaluate_url: &'a str,
+    /// Expected number of tests, without tests of lazily parsed test files.
+    expected_tests: usize,
+    /// Metadata of the run.
+    metadata: &'a BTreeMap<String, String>,
+  },
+  /// Test file has been parsed, before its tests are executed.
+  FileParsed {
+    /// Path of the test file, displayed as configured.
+    file: &'a str,
+    /// Number of test cases, not present for lazily parsed test files and invalid test files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    test_cases: Option<usize>,
+    /// Reason why the test file is invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+  },
+  /// Test has been executed.
+  TestFinished {
+    /// Full path of the test cases directory containing the test file.
+    root: &'a str,
+    /// Directory of the test file, displayed as configured.
+    directory: &'a str,
+    /// Test file name without extension.
+    file: &'a str,
+    /// Identifier of the test case.
+    test_case_id: &'a str,
+    /// Identifier of the test.
+    test_id: &'a str,
+    /// Test result.
+    result: &'a str,
+    /// Failure category, empty for passed tests.
+    category: &'a str,
+    /// Failure remarks.
+    remarks: &'a str,
+    /// Evaluation duration in microseconds.
+    duration_us: u128,
+  },
+  /// All tests have been executed.
+  RunFinished {
+    /// Number of executed tests.
+    total: usize,
+    /// Number of tests that have passed.
+    success: usize,
+    /// Number of tests that have failed.
+    failure: usize,
+    /// Number of skipped tests.
+    skipped: usize,
+    /// Wall-clock duration of the run in milliseconds.
+    duration_ms: u128,
+  },
+}
+
+/// Stream of events written to standard output, one JSON object per line.
+/// The console output is redirected to standard error while the stream is open.
+pub struct EventStream {
+  /// Destination of the console output before the stream was opened.
+  console: Target,
+}
+
+impl EventStream {
+  /// Opens the stream of events, redirecting the console output to standard error.
+  pub fn open() -> Self {
+    Self {
+      console: console::redirect(Target::Stderr),
+    }
+  }
+
+  /// Writes a single event, events are not buffered so they are received immediately.
+  pub fn emit(&mut self, event: &Event) {
+    let line = serde_json::to_string(event).unwrap_or_else(|e| panic!("serializing event failed with reason: {}", e));
+    // consumer may stop reading events, but the run is completed anyway
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line).and_then(|_| stdout.flush());
+  }
+
+  /// Closes the stream of events and restores the console output.
+  pub fn close(self) {
+    console::redirect(self.console);
+  }
+}
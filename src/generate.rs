@@ -0,0 +1,229 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Generator of test case skeletons from DMN models
+
+use crate::model::{InputNode, ResultNode, Simple, TestCase, TestCaseType, TestCases, Unrecognized, Value};
+use crate::serializer::serialize;
+use crate::types::xsd_type;
+use roxmltree::{Document, Node};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Input or result of an invocable, with optional type reference.
+struct Variable {
+  /// Name of the variable.
+  name: String,
+  /// Name of the XML Schema type corresponding to type reference.
+  typ: Option<&'static str>,
+}
+
+/// Invocable defined in DMN model.
+struct Invocable {
+  /// Name of the invocable.
+  name: String,
+  /// Type of the test case, `None` for decisions.
+  typ: Option<&'static str>,
+  /// Inputs of the invocable.
+  inputs: Vec<Variable>,
+  /// Type of the result.
+  output_type: Option<&'static str>,
+}
+
+/// Generates the skeleton of the test file for the specified DMN model.
+/// When the output file is not given, the name is derived from model file name, like `model-test-01.xml`.
+pub fn generate(model_file: &str, output_file: Option<&str>) -> Result<String, String> {
+  let content = fs::read_to_string(model_file).map_err(|e| format!("reading model file {} failed with reason: {}", model_file, e))?;
+  let document = Document::parse(&content).map_err(|e| format!("parsing model file {} failed with reason: {}", model_file, e))?;
+  let model_path = Path::new(model_file);
+  let model_file_name = model_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+  let output_file = match output_file {
+    Some(output_file) => output_file.to_string(),
+    None => {
+      let stem = model_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+      model_path.with_file_name(format!("{}-test-01.xml", stem)).to_string_lossy().to_string()
+    }
+  };
+  if Path::new(&output_file).exists() {
+    return Err(format!("output file {} already exists", output_file));
+  }
+  let invocables = invocables(&document.root_element());
+  fs::write(&output_file, serialize(&skeleton(&model_file_name, invocables))).map_err(|e| format!("writing test file {} failed with reason: {}", output_file, e))?;
+  Ok(output_file)
+}
+
+/// Collects all invocables defined in the model.
+fn invocables(definitions: &Node) -> Vec<Invocable> {
+  let elements = definitions
+    .children()
+    .filter(|n| n.is_element())
+    .filter_map(|n| n.attribute("id").map(|id| (id.to_string(), n)))
+    .collect::<BTreeMap<String, Node>>();
+  let mut invocables = vec![];
+  for node in definitions.children().filter(|n| n.is_element()) {
+    let name = node.attribute("name").unwrap_or_default().to_string();
+    match node.tag_name().name() {
+      "decision" => {
+        let mut inputs = vec![];
+        collect_decision_inputs(&node, &elements, &mut inputs);
+        invocables.push(Invocable {
+          name,
+          typ: None,
+          inputs,
+          output_type: variable_type(&node),
+        });
+      }
+      "businessKnowledgeModel" => {
+        let inputs = node
+          .children()
+          .filter(|n| n.tag_name().name() == "encapsulatedLogic")
+          .flat_map(|n| n.children().filter(|n| n.tag_name().name() == "formalParameter"))
+          .map(|n| Variable {
+            name: n.attribute("name").unwrap_or_default().to_string(),
+            typ: n.attribute("typeRef").and_then(xsd_type),
+          })
+          .collect();
+        invocables.push(Invocable {
+          name,
+          typ: Some("bkm"),
+          inputs,
+          output_type: variable_type(&node),
+        });
+      }
+      "decisionService" => {
+        let inputs = node
+          .children()
+          .filter(|n| matches!(n.tag_name().name(), "inputData" | "inputDecision"))
+          .filter_map(|n| referenced(&n, &elements))
+          .map(|n| Variable {
+            name: n.attribute("name").unwrap_or_default().to_string(),
+            typ: variable_type(&n),
+          })
+          .collect();
+        invocables.push(Invocable {
+          name,
+          typ: Some("decisionService"),
+          inputs,
+          output_type: None,
+        });
+      }
+      _ => {}
+    }
+  }
+  invocables
+}
+
+/// Collects input data required by the decision, including input data required by all required decisions.
+fn collect_decision_inputs(decision: &Node, elements: &BTreeMap<String, Node>, inputs: &mut Vec<Variable>) {
+  for requirement in decision.children().filter(|n| n.tag_name().name() == "informationRequirement") {
+    for reference in requirement.children().filter(|n| n.is_element()) {
+      let Some(node) = referenced(&reference, elements) else {
+        continue;
+      };
+      match reference.tag_name().name() {
+        "requiredInput" => {
+          let name = node.attribute("name").unwrap_or_default().to_string();
+          if !inputs.iter().any(|input| input.name == name) {
+            inputs.push(Variable { name, typ: variable_type(&node) });
+          }
+        }
+        "requiredDecision" => collect_decision_inputs(&node, elements, inputs),
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Returns the element referenced by `href` attribute in form `#id`.
+fn referenced<'a, 'input>(reference: &Node, elements: &BTreeMap<String, Node<'a, 'input>>) -> Option<Node<'a, 'input>> {
+  reference.attribute("href").and_then(|href| elements.get(href.trim_start_matches('#'))).copied()
+}
+
+/// Returns the XML Schema type of the variable defined for the element.
+fn variable_type(node: &Node) -> Option<&'static str> {
+  node
+    .children()
+    .find(|n| n.tag_name().name() == "variable")
+    .and_then(|n| n.attribute("typeRef"))
+    .and_then(xsd_type)
+}
+
+/// Builds the test cases with empty input and expected values for all invocables.
+fn skeleton(model_file_name: &str, invocables: Vec<Invocable>) -> TestCases {
+  let empty_value = |typ: Option<&str>| {
+    Some(Value::Simple(Simple {
+      typ: typ.map(|typ| typ.to_string()),
+      text: None,
+      nil: false,
+      unrecognized: Unrecognized::default(),
+    }))
+  };
+  let test_cases = invocables
+    .into_iter()
+    .enumerate()
+    .map(|(index, invocable)| TestCase {
+      id: Some(format!("{:03}", index + 1)),
+      name: None,
+      typ: invocable.typ.map(|typ| TestCaseType::from(typ.to_string())).unwrap_or(TestCaseType::Decision),
+      description: None,
+      invocable_name: invocable.typ.map(|_| invocable.name.clone()),
+      input_nodes: invocable
+        .inputs
+        .into_iter()
+        .map(|input| InputNode {
+          name: input.name,
+          namespace: None,
+          value: empty_value(input.typ),
+        })
+        .collect(),
+      result_nodes: vec![ResultNode {
+        name: invocable.name,
+        namespace: None,
+        error_result: false,
+        typ: TestCaseType::Decision,
+        cast: None,
+        expected: empty_value(invocable.output_type),
+        computed: None,
+        extension_elements: vec![],
+      }],
+      extension_elements: vec![],
+    })
+    .collect();
+  TestCases {
+    namespace: None,
+    spec_version: None,
+    model_name: Some(model_file_name.to_string()),
+    labels: vec![],
+    test_cases,
+  }
+}
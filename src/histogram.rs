@@ -0,0 +1,124 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Latency histogram exported in HdrHistogram percentile distribution format (hgrm)
+
+use hdrhistogram::Histogram;
+use std::fs;
+use std::time::Duration;
+
+/// Highest recorded latency in microseconds (one hour), longer latencies are recorded as this value.
+const HIGHEST_LATENCY: u64 = 3_600_000_000;
+
+/// Number of significant decimal digits of recorded latencies.
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Number of reporting points per each halving of the distance to 100th percentile.
+const TICKS_PER_HALF_DISTANCE: u32 = 5;
+
+/// Histogram of request latencies recorded in microseconds.
+pub struct LatencyHistogram {
+  /// Recorded latencies.
+  histogram: Histogram<u64>,
+}
+
+impl Default for LatencyHistogram {
+  fn default() -> Self {
+    Self {
+      histogram: Histogram::new_with_bounds(1, HIGHEST_LATENCY, SIGNIFICANT_DIGITS).unwrap_or_else(|e| panic!("creating latency histogram failed with reason: {}", e)),
+    }
+  }
+}
+
+impl LatencyHistogram {
+  /// Records the latency of a single request.
+  pub fn record(&mut self, latency: Duration) {
+    self.histogram.saturating_record((latency.as_micros() as u64).max(1));
+  }
+
+  /// Returns the number of recorded latencies.
+  pub fn count(&self) -> u64 {
+    self.histogram.len()
+  }
+
+  /// Returns the mean of recorded latencies.
+  pub fn mean(&self) -> Duration {
+    Duration::from_micros(self.histogram.mean().round() as u64)
+  }
+
+  /// Returns the latency at specified quantile, like `0.95` for 95th percentile.
+  pub fn quantile(&self, quantile: f64) -> Duration {
+    Duration::from_micros(self.histogram.value_at_quantile(quantile))
+  }
+
+  /// Returns the maximal recorded latency.
+  pub fn max(&self) -> Duration {
+    Duration::from_micros(self.histogram.max())
+  }
+
+  /// Writes the percentile distribution of latencies in milliseconds to file in hgrm format,
+  /// the same as produced by `outputPercentileDistribution` of HdrHistogram.
+  pub fn write(&self, file_name: &str) {
+    fs::write(file_name, self.to_hgrm()).unwrap_or_else(|e| panic!("writing latency histogram to '{}' failed with reason: {}", file_name, e));
+  }
+
+  /// Returns the percentile distribution of latencies in milliseconds in hgrm format.
+  fn to_hgrm(&self) -> String {
+    // recorded microseconds are reported as milliseconds
+    let scale = 1000.0;
+    let mut output = format!("{:>12} {:>14} {:>10} {:>14}\n\n", "Value", "Percentile", "TotalCount", "1/(1-Percentile)");
+    let mut total_count = 0;
+    for value in self.histogram.iter_quantiles(TICKS_PER_HALF_DISTANCE) {
+      total_count += value.count_since_last_iteration();
+      let quantile = value.quantile_iterated_to();
+      let latency = value.value_iterated_to() as f64 / scale;
+      if quantile < 1.0 {
+        output.push_str(&format!("{:>12.3} {:>2.12} {:>10} {:>14.2}\n", latency, quantile, total_count, 1.0 / (1.0 - quantile)));
+      } else {
+        output.push_str(&format!("{:>12.3} {:>1.12} {:>10}\n", latency, quantile, total_count));
+      }
+    }
+    let sub_buckets = (2 * 10_u64.pow(SIGNIFICANT_DIGITS as u32)).next_power_of_two();
+    output.push_str(&format!(
+      "#[Mean    = {:>12.3}, StdDeviation   = {:>12.3}]\n",
+      self.histogram.mean() / scale,
+      self.histogram.stdev() / scale
+    ));
+    output.push_str(&format!(
+      "#[Max     = {:>12.3}, Total count    = {:>12}]\n",
+      self.histogram.max() as f64 / scale,
+      self.histogram.len()
+    ));
+    output.push_str(&format!("#[Buckets = {:>12}, SubBuckets     = {:>12}]\n", self.histogram.buckets(), sub_buckets));
+    output
+  }
+}
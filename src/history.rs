@@ -0,0 +1,330 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # History of test runs stored in SQLite database
+
+use crate::context::{FailedTest, NOT_FAILED_RESULTS};
+use rusqlite::{params, Connection, OpenFlags};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Number of results written to history database in a single transaction,
+/// results written before a crash are preserved up to the last committed batch.
+const BATCH_SIZE: usize = 1_000;
+
+/// Schema of the history database.
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS runs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  started_at TEXT NOT NULL,
+  finished_at TEXT,
+  root_dir TEXT NOT NULL,
+  file_search_pattern TEXT NOT NULL,
+  evaluate_url TEXT NOT NULL,
+  success_count INTEGER,
+  failure_count INTEGER,
+  execution_time_us INTEGER
+);
+CREATE TABLE IF NOT EXISTS results (
+  run_id INTEGER NOT NULL REFERENCES runs(id),
+  root TEXT NOT NULL DEFAULT '',
+  directory TEXT NOT NULL,
+  file TEXT NOT NULL,
+  test_case_id TEXT NOT NULL,
+  test_id TEXT NOT NULL,
+  result TEXT NOT NULL,
+  remarks TEXT NOT NULL,
+  duration_us INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS results_test ON results (directory, file, test_id);
+CREATE TABLE IF NOT EXISTS run_metadata (
+  run_id INTEGER NOT NULL REFERENCES runs(id),
+  key TEXT NOT NULL,
+  value TEXT NOT NULL
+);
+"#;
+
+/// History of test runs.
+pub struct History {
+  /// Connection to history database.
+  connection: Connection,
+  /// Identifier of the current run.
+  run_id: i64,
+  /// Number of results written in the open transaction, no transaction is open when zero.
+  pending: Cell<usize>,
+}
+
+impl History {
+  /// Opens (or creates) the history database and registers a new run with its metadata.
+  pub fn open(file_name: &str, root_dir: &str, file_search_pattern: &str, evaluate_url: &str, run_metadata: &BTreeMap<String, String>) -> Self {
+    let connection = connect(file_name, OpenFlags::default());
+    connection
+      .execute_batch(SCHEMA)
+      .unwrap_or_else(|e| panic!("creating history database schema failed with reason: {}", e));
+    add_root_column(&connection);
+    connection
+      .execute(
+        "INSERT INTO runs (started_at, root_dir, file_search_pattern, evaluate_url) VALUES (datetime('now'), ?1, ?2, ?3)",
+        params![root_dir, file_search_pattern, evaluate_url],
+      )
+      .unwrap_or_else(|e| panic!("registering run in history database failed with reason: {}", e));
+    let run_id = connection.last_insert_rowid();
+    for (key, value) in run_metadata {
+      connection
+        .execute("INSERT INTO run_metadata (run_id, key, value) VALUES (?1, ?2, ?3)", params![run_id, key, value])
+        .unwrap_or_else(|e| panic!("registering run metadata in history database failed with reason: {}", e));
+    }
+    Self {
+      connection,
+      run_id,
+      pending: Cell::new(0),
+    }
+  }
+
+  /// Appends a single test result to the current run.
+  #[allow(clippy::too_many_arguments)]
+  pub fn add_result(&self, root: &str, directory: &str, file: &str, test_case_id: &str, test_id: &str, result: &str, remarks: &str, duration_us: u128) {
+    // results are written in batches, each batch in one transaction
+    if self.pending.get() == 0 {
+      self
+        .connection
+        .execute_batch("BEGIN")
+        .unwrap_or_else(|e| panic!("starting transaction in history database failed with reason: {}", e));
+    }
+    self
+      .connection
+      .execute(
+        "INSERT INTO results (run_id, root, directory, file, test_case_id, test_id, result, remarks, duration_us) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![self.run_id, root, directory, file, test_case_id, test_id, result, remarks, duration_us as i64],
+      )
+      .unwrap_or_else(|e| panic!("writing result to history database failed with reason: {}", e));
+    self.pending.set(self.pending.get() + 1);
+    if self.pending.get() >= BATCH_SIZE {
+      self.commit();
+    }
+  }
+
+  /// Commits the open transaction, if any.
+  fn commit(&self) {
+    if self.pending.get() > 0 {
+      self
+        .connection
+        .execute_batch("COMMIT")
+        .unwrap_or_else(|e| panic!("committing history database failed with reason: {}", e));
+      self.pending.set(0);
+    }
+  }
+
+  /// Returns tests that have passed in the previous finished run and failed in the current run.
+  pub fn regressions(&self) -> Vec<String> {
+    let mut statement = self
+      .connection
+      .prepare(&format!(
+        "SELECT c.directory || '/' || c.file || '/' || c.test_id FROM results c \
+         JOIN results p ON p.run_id = (SELECT MAX(id) FROM runs WHERE id < ?1 AND finished_at IS NOT NULL) \
+         AND p.root = c.root AND p.directory = c.directory AND p.file = c.file AND p.test_id = c.test_id \
+         WHERE c.run_id = ?1 AND c.result NOT IN ({}) AND p.result IN ('SUCCESS', 'WARNING') ORDER BY 1",
+        not_failed_results()
+      ))
+      .unwrap_or_else(|e| panic!("querying history database failed with reason: {}", e));
+    statement
+      .query_map(params![self.run_id], |row| row.get::<_, String>(0))
+      .and_then(|rows| rows.collect::<Result<Vec<String>, _>>())
+      .unwrap_or_else(|e| panic!("reading regressions from history database failed with reason: {}", e))
+  }
+
+  /// Commits the remaining results and stores the summary of the current run.
+  pub fn finish(self, success_count: usize, failure_count: usize, execution_time_us: u128) {
+    self.commit();
+    self
+      .connection
+      .execute(
+        "UPDATE runs SET finished_at = datetime('now'), success_count = ?1, failure_count = ?2, execution_time_us = ?3 WHERE id = ?4",
+        params![success_count as i64, failure_count as i64, execution_time_us as i64, self.run_id],
+      )
+      .unwrap_or_else(|e| panic!("updating run in history database failed with reason: {}", e));
+  }
+}
+
+/// Summary of a single run loaded from history database.
+pub struct RunSummary {
+  /// Identifier of the run.
+  pub id: i64,
+  /// Date and time when the run has started.
+  pub started_at: String,
+  /// Number of tests that have passed.
+  pub success_count: usize,
+  /// Number of tests that have failed.
+  pub failure_count: usize,
+  /// Average duration of a single request in microseconds.
+  pub average_duration_us: f64,
+}
+
+/// Change of the test status between two consecutive runs.
+pub struct StatusChange {
+  /// Identifier of the run in which the status has changed.
+  pub run_id: i64,
+  /// Test identifier in form `directory/file/test_id`.
+  pub test: String,
+  /// Status in the previous run.
+  pub previous: String,
+  /// Status in the run identified by `run_id`.
+  pub current: String,
+}
+
+/// Loads summaries of the last finished runs, the oldest run first.
+pub fn last_runs(file_name: &str, count: usize) -> Vec<RunSummary> {
+  let connection = connect(file_name, OpenFlags::SQLITE_OPEN_READ_ONLY);
+  let mut statement = connection
+    .prepare(
+      "SELECT r.id, r.started_at, r.success_count, r.failure_count, (SELECT COALESCE(AVG(duration_us), 0.0) FROM results WHERE run_id = r.id) \
+       FROM runs r WHERE r.finished_at IS NOT NULL ORDER BY r.id DESC LIMIT ?1",
+    )
+    .unwrap_or_else(|e| panic!("querying history database failed with reason: {}", e));
+  let mut runs = statement
+    .query_map(params![count as i64], |row| {
+      Ok(RunSummary {
+        id: row.get(0)?,
+        started_at: row.get(1)?,
+        success_count: row.get::<_, i64>(2)? as usize,
+        failure_count: row.get::<_, i64>(3)? as usize,
+        average_duration_us: row.get(4)?,
+      })
+    })
+    .and_then(|rows| rows.collect::<Result<Vec<RunSummary>, _>>())
+    .unwrap_or_else(|e| panic!("reading runs from history database failed with reason: {}", e));
+  runs.reverse();
+  runs
+}
+
+/// Loads tests whose status has changed between consecutive runs starting from the run with specified identifier.
+pub fn status_changes(file_name: &str, first_run_id: i64) -> Vec<StatusChange> {
+  let connection = connect(file_name, OpenFlags::SQLITE_OPEN_READ_ONLY);
+  let mut statement = connection
+    .prepare(&format!(
+      "SELECT run_id, {}, directory, file, test_id, result FROM results WHERE run_id >= ?1 ORDER BY run_id",
+      root_column(&connection)
+    ))
+    .unwrap_or_else(|e| panic!("querying history database failed with reason: {}", e));
+  let rows = statement
+    .query_map(params![first_run_id], |row| {
+      Ok((
+        row.get::<_, i64>(0)?,
+        row.get::<_, String>(1)?,
+        format!("{}/{}/{}", row.get::<_, String>(2)?, row.get::<_, String>(3)?, row.get::<_, String>(4)?),
+        row.get::<_, String>(5)?,
+      ))
+    })
+    .and_then(|rows| rows.collect::<Result<Vec<(i64, String, String, String)>, _>>())
+    .unwrap_or_else(|e| panic!("reading results from history database failed with reason: {}", e));
+  // tests are identified by tests root directory and test identifier
+  let mut last_statuses = BTreeMap::<(String, String), String>::new();
+  let mut changes = vec![];
+  for (run_id, root, test, result) in rows {
+    if let Some(previous) = last_statuses.insert((root, test.clone()), result.clone()) {
+      if previous != result {
+        changes.push(StatusChange {
+          run_id,
+          test,
+          previous,
+          current: result,
+        });
+      }
+    }
+  }
+  changes
+}
+
+/// Loads tests that have failed in the last finished run.
+pub fn last_failures(file_name: &str) -> Result<BTreeSet<FailedTest>, String> {
+  if !Path::new(file_name).exists() {
+    return Err(format!("history database {} not found", file_name));
+  }
+  let connection = connect(file_name, OpenFlags::SQLITE_OPEN_READ_ONLY);
+  let run_id = connection
+    .query_row("SELECT MAX(id) FROM runs WHERE finished_at IS NOT NULL", [], |row| row.get::<_, Option<i64>>(0))
+    .unwrap_or_else(|e| panic!("querying history database failed with reason: {}", e))
+    .ok_or(format!("no finished run found in history database {}", file_name))?;
+  let mut statement = connection
+    .prepare(&format!(
+      "SELECT {}, directory, file, test_id FROM results WHERE run_id = ?1 AND result NOT IN ({})",
+      root_column(&connection),
+      not_failed_results()
+    ))
+    .unwrap_or_else(|e| panic!("querying history database failed with reason: {}", e));
+  let failures = statement
+    .query_map(params![run_id], |row| {
+      Ok(FailedTest {
+        root: row.get(0)?,
+        directory: row.get(1)?,
+        file: row.get(2)?,
+        test_id: row.get(3)?,
+      })
+    })
+    .and_then(|rows| rows.collect::<Result<BTreeSet<FailedTest>, _>>())
+    .unwrap_or_else(|e| panic!("reading failures from history database failed with reason: {}", e));
+  Ok(failures)
+}
+
+/// Adds the column of tests root directories to results stored by previous versions, which did not record it.
+fn add_root_column(connection: &Connection) {
+  if root_column(connection) != "root" {
+    connection
+      .execute_batch("ALTER TABLE results ADD COLUMN root TEXT NOT NULL DEFAULT ''")
+      .unwrap_or_else(|e| panic!("updating history database schema failed with reason: {}", e));
+  }
+}
+
+/// Returns the list of results of tests that did not fail, to be used in SQL queries.
+fn not_failed_results() -> String {
+  NOT_FAILED_RESULTS.iter().map(|result| format!("'{}'", result)).collect::<Vec<String>>().join(", ")
+}
+
+/// Returns the expression selecting the tests root directory of results,
+/// an empty string for databases written by previous versions, which did not record it.
+fn root_column(connection: &Connection) -> &'static str {
+  let has_root_column = connection
+    .prepare("SELECT 1 FROM pragma_table_info('results') WHERE name = 'root'")
+    .and_then(|mut statement| statement.exists([]))
+    .unwrap_or_else(|e| panic!("querying history database schema failed with reason: {}", e));
+  if has_root_column {
+    "root"
+  } else {
+    "''"
+  }
+}
+
+/// Opens the connection to history database.
+fn connect(file_name: &str, flags: OpenFlags) -> Connection {
+  Connection::open_with_flags(file_name, flags).unwrap_or_else(|e| panic!("opening history database {} failed with reason: {}", file_name, e))
+}
@@ -0,0 +1,63 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Human-readable formatting of durations and counts
+
+use std::time::Duration;
+
+/// Returns the duration in the most readable unit, like `850 µs`, `12.34 ms`, `3.21 s`, `2m 05s` or `1h 05m`.
+pub fn duration(duration: Duration) -> String {
+  let seconds = duration.as_secs();
+  if duration < Duration::from_millis(1) {
+    format!("{} µs", duration.as_micros())
+  } else if duration < Duration::from_secs(1) {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+  } else if seconds < 60 {
+    format!("{:.2} s", duration.as_secs_f64())
+  } else if seconds < 3600 {
+    format!("{}m {:02}s", seconds / 60, seconds % 60)
+  } else {
+    format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+  }
+}
+
+/// Returns the count with digits grouped by thousands, like `12,345`.
+pub fn count(count: usize) -> String {
+  let digits = count.to_string();
+  let mut groups = digits
+    .as_bytes()
+    .rchunks(3)
+    .map(|group| std::str::from_utf8(group).unwrap_or_default())
+    .collect::<Vec<&str>>();
+  groups.reverse();
+  groups.join(",")
+}
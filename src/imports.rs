@@ -0,0 +1,153 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Dependency graph of imported DMN models
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Import of another DMN model, defined in `<import>` element.
+pub struct ModelImport {
+  /// Name of the import, used as a prefix for imported elements.
+  pub name: String,
+  /// Namespace of the imported model.
+  pub namespace: String,
+}
+
+/// Graph of imports between DMN models, indexed by model file path.
+#[derive(Default)]
+pub struct ImportGraph {
+  /// Namespaces of models.
+  namespaces: BTreeMap<String, String>,
+  /// Imports defined in models.
+  imports: BTreeMap<String, Vec<ModelImport>>,
+  /// Names of invocables defined in models.
+  invocables: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ImportGraph {
+  /// Adds a model with its namespace, imports and invocables.
+  pub fn add_model(&mut self, model_path: &str, namespace: &str, imports: Vec<ModelImport>, invocable_names: &[String]) {
+    self.namespaces.insert(model_path.to_string(), namespace.to_string());
+    self.imports.insert(model_path.to_string(), imports);
+    self.invocables.insert(model_path.to_string(), invocable_names.iter().cloned().collect());
+  }
+
+  /// Returns the path of the model with specified namespace, models from the same directory are preferred.
+  pub fn model_with_namespace(&self, model_path: &str, namespace: &str) -> Option<String> {
+    let dir = Path::new(model_path).parent();
+    let mut candidates = self.namespaces.iter().filter(|(_, ns)| *ns == namespace).map(|(path, _)| path);
+    let candidates_in_dir = candidates.clone().find(|path| Path::new(path).parent() == dir);
+    candidates_in_dir.or_else(|| candidates.next()).cloned()
+  }
+
+  /// Returns imports of the model resolved to model paths, `None` for unresolved imports.
+  pub fn imported_models(&self, model_path: &str) -> Vec<(&ModelImport, Option<String>)> {
+    self
+      .imports
+      .get(model_path)
+      .map(|imports| imports.iter().map(|import| (import, self.model_with_namespace(model_path, &import.namespace))).collect())
+      .unwrap_or_default()
+  }
+
+  /// Returns model paths ordered so that imported models precede the models importing them.
+  /// When imports are cyclic, models that form cycles are returned as an error.
+  pub fn deployment_order(&self) -> Result<Vec<String>, Vec<String>> {
+    let mut ordered = vec![];
+    let mut remaining = self.namespaces.keys().cloned().collect::<BTreeSet<String>>();
+    while !remaining.is_empty() {
+      let ready = remaining
+        .iter()
+        .filter(|path| {
+          self
+            .imported_models(path)
+            .iter()
+            .filter_map(|(_, imported)| imported.as_ref())
+            .all(|imported| imported == *path || !remaining.contains(imported))
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+      if ready.is_empty() {
+        return Err(remaining.into_iter().collect());
+      }
+      for path in ready {
+        remaining.remove(&path);
+        ordered.push(path);
+      }
+    }
+    Ok(ordered)
+  }
+
+  /// Returns the path of the model and paths of all models it imports, directly or indirectly.
+  pub fn dependencies(&self, model_path: &str) -> BTreeSet<String> {
+    let mut dependencies = BTreeSet::new();
+    let mut remaining = vec![model_path.to_string()];
+    while let Some(path) = remaining.pop() {
+      if dependencies.insert(path.clone()) {
+        remaining.extend(self.imported_models(&path).into_iter().filter_map(|(_, imported)| imported));
+      }
+    }
+    dependencies
+  }
+
+  /// Resolves the model that defines specified invocable, searching imported models when
+  /// the invocable is not defined in the model itself. Invocable names may be prefixed with import name,
+  /// like `importName.invocableName`. Returns the path of the model and the name of the invocable in that model.
+  pub fn resolve_invocable(&self, model_path: &str, invocable_name: &str) -> (String, String) {
+    let mut visited = BTreeSet::new();
+    self
+      .find_invocable(model_path, invocable_name, &mut visited)
+      .unwrap_or_else(|| (model_path.to_string(), invocable_name.to_string()))
+  }
+
+  /// Searches the invocable in specified model and recursively in all models it imports.
+  fn find_invocable(&self, model_path: &str, invocable_name: &str, visited: &mut BTreeSet<String>) -> Option<(String, String)> {
+    if !visited.insert(model_path.to_string()) {
+      return None;
+    }
+    if self.invocables.get(model_path).is_some_and(|names| names.contains(invocable_name)) {
+      return Some((model_path.to_string(), invocable_name.to_string()));
+    }
+    let imported_models = self.imported_models(model_path);
+    for (import, imported_path) in &imported_models {
+      if let Some((imported_path, local_name)) = imported_path.as_ref().zip(invocable_name.strip_prefix(&format!("{}.", import.name))) {
+        if let Some(found) = self.find_invocable(imported_path, local_name, visited) {
+          return Some(found);
+        }
+      }
+    }
+    imported_models
+      .iter()
+      .filter_map(|(_, imported_path)| imported_path.as_ref())
+      .find_map(|imported_path| self.find_invocable(imported_path, invocable_name, visited))
+  }
+}
@@ -0,0 +1,69 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Interruption of the run with Ctrl-C
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of the run interrupted by the user.
+pub const EXIT_CODE: i32 = 130;
+
+/// Flag set when the run was interrupted.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs the handler of SIGINT signal, that stops dispatching new tests.
+/// The process is terminated immediately when interrupted again.
+#[cfg(unix)]
+pub fn install() {
+  extern "C" fn handle(_: libc::c_int) {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+      // SAFETY: terminating the process is async-signal-safe
+      unsafe { libc::_exit(EXIT_CODE) };
+    }
+  }
+  // SAFETY: the handler only uses async-signal-safe operations
+  unsafe { libc::signal(libc::SIGINT, handle as *const () as libc::sighandler_t) };
+}
+
+/// Installs the handler of SIGINT signal, default handling is used on this platform.
+#[cfg(not(unix))]
+pub fn install() {}
+
+/// Interrupts the run, like after receiving SIGINT signal.
+pub fn interrupt() {
+  INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Returns `true` when the run was interrupted.
+pub fn is_interrupted() -> bool {
+  INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,263 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Linter of test files
+
+use crate::model::PREFIX_XSD;
+use roxmltree::{Document, Node};
+use std::fs;
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema";
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Textual edit of the test file, replacing the range of bytes with replacement text.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Edit {
+  /// Start of the replaced range.
+  start: usize,
+  /// End of the replaced range (exclusive).
+  end: usize,
+  /// Replacement text.
+  replacement: String,
+}
+
+/// Problem found in test file.
+pub struct Finding {
+  /// Line where the problem was found.
+  pub line: u32,
+  /// Column where the problem was found.
+  pub column: u32,
+  /// Identifier of the violated rule.
+  pub rule: &'static str,
+  /// Description of the problem.
+  pub message: String,
+  /// Edits fixing the problem, empty when the problem can not be fixed automatically.
+  pub fix: Vec<Edit>,
+}
+
+/// Checks the test file and returns all found problems.
+pub fn lint_file(file_name: &str) -> Result<Vec<Finding>, String> {
+  let content = fs::read_to_string(file_name).map_err(|e| format!("reading test file failed with reason: {}", e))?;
+  lint_content(&content)
+}
+
+/// Checks the test file, applies all available fixes and writes the file back.
+/// Returns all found problems, problems with non-empty fix have been fixed.
+pub fn fix_file(file_name: &str) -> Result<Vec<Finding>, String> {
+  let content = fs::read_to_string(file_name).map_err(|e| format!("reading test file failed with reason: {}", e))?;
+  let findings = lint_content(&content)?;
+  let mut edits: Vec<&Edit> = vec![];
+  for edit in findings.iter().flat_map(|finding| &finding.fix) {
+    if !edits.contains(&edit) {
+      edits.push(edit);
+    }
+  }
+  if edits.is_empty() {
+    return Ok(findings);
+  }
+  edits.sort_by_key(|edit| (edit.start, edit.end));
+  let mut fixed = content.clone();
+  let mut limit = usize::MAX;
+  for edit in edits.into_iter().rev() {
+    // overlapping edits are skipped, they will be applied in the next run
+    if edit.end <= limit {
+      fixed.replace_range(edit.start..edit.end, &edit.replacement);
+      limit = edit.start;
+    }
+  }
+  fs::write(file_name, fixed).map_err(|e| format!("writing test file failed with reason: {}", e))?;
+  Ok(findings)
+}
+
+/// Checks the content of the test file.
+fn lint_content(content: &str) -> Result<Vec<Finding>, String> {
+  let document = Document::parse(content).map_err(|e| format!("parsing test file failed with reason: {}", e))?;
+  let mut findings = vec![];
+  for node in document.descendants().filter(|n| n.is_element()) {
+    if node.tag_name().name() == "value" && node.parent_element().is_some_and(is_value_container) {
+      lint_value(&node, &mut findings);
+    }
+  }
+  findings.sort_by_key(|finding| (finding.line, finding.column));
+  Ok(findings)
+}
+
+/// Returns `true` when the node may contain values, `value` nodes in other places (like extension elements) are not checked.
+fn is_value_container(node: Node) -> bool {
+  matches!(node.tag_name().name(), "inputNode" | "expected" | "computed" | "component" | "item")
+}
+
+/// Checks single value node.
+fn lint_value(node: &Node, findings: &mut Vec<Finding>) {
+  let nil = node.attribute((XSI, "nil")).is_some_and(|nil| nil == "true");
+  let text = node.text().unwrap_or_default().trim();
+  let mut finding = |rule, message, fix| {
+    let pos = node.document().text_pos_at(node.range().start);
+    findings.push(Finding {
+      line: pos.row,
+      column: pos.col,
+      rule,
+      message,
+      fix,
+    })
+  };
+  match node.attributes().find(|attr| attr.namespace() == Some(XSI) && attr.name() == "type") {
+    Some(type_attr) => {
+      let typ = type_attr.value().trim();
+      let (prefix, local_name) = typ.split_once(':').map_or((None, typ), |(prefix, local_name)| (Some(prefix), local_name));
+      if node.lookup_namespace_uri(prefix) == Some(XSD) && prefix != Some(PREFIX_XSD) {
+        let fix = xsd_declaration(node)
+          .map(|mut fix| {
+            fix.push(Edit {
+              start: type_attr.range_value().start,
+              end: type_attr.range_value().end,
+              replacement: format!("{}:{}", PREFIX_XSD, local_name),
+            });
+            fix
+          })
+          .unwrap_or_default();
+        finding(
+          "namespace-prefix",
+          format!("type '{}' should use '{}' prefix for XML Schema namespace", typ, PREFIX_XSD),
+          fix,
+        );
+      }
+      if matches!(local_name, "decimal" | "double") && is_missing_leading_zero(text) {
+        finding("leading-zero", format!("decimal '{}' should be written with leading zero", text), leading_zero_fix(node));
+      }
+      if local_name == "duration" && is_zero_days_duration(text) {
+        finding(
+          "zero-duration",
+          format!("duration '{}' should be written in canonical form 'PT0S'", text),
+          text_fix(node, "P0D", "PT0S"),
+        );
+      }
+    }
+    None if nil => {}
+    None if text == "true" || text == "false" => {
+      finding("untyped-literal", format!("boolean literal '{}' has no 'xsi:type'", text), type_fix(node, "boolean"));
+    }
+    None if text.parse::<f64>().is_ok() => {
+      finding("untyped-literal", format!("number literal '{}' has no 'xsi:type'", text), type_fix(node, "decimal"));
+      if is_missing_leading_zero(text) {
+        finding("leading-zero", format!("decimal '{}' should be written with leading zero", text), leading_zero_fix(node));
+      }
+    }
+    None => finding("missing-type", "value has no 'xsi:type'".to_string(), vec![]),
+  }
+}
+
+/// Returns the edit inserting the leading zero before decimal point.
+fn leading_zero_fix(node: &Node) -> Vec<Edit> {
+  text_fix(node, ".", "0.")
+}
+
+/// Returns the edit replacing the first occurrence of the pattern in the text of the node.
+fn text_fix(node: &Node, pattern: &str, replacement: &str) -> Vec<Edit> {
+  node
+    .first_child()
+    .filter(|child| child.is_text())
+    .and_then(|child| {
+      let range = child.range();
+      node.document().input_text()[range.clone()].find(pattern).map(|offset| Edit {
+        start: range.start + offset,
+        end: range.start + offset + pattern.len(),
+        replacement: replacement.to_string(),
+      })
+    })
+    .into_iter()
+    .collect()
+}
+
+/// Returns the edits adding `xsi:type` attribute with specified XML Schema type to the value node.
+fn type_fix(node: &Node, local_name: &str) -> Vec<Edit> {
+  let Some(mut fix) = xsd_declaration(node) else {
+    return vec![];
+  };
+  let xsi_prefix = match node.lookup_prefix(XSI) {
+    Some(prefix) => prefix.to_string(),
+    None => match root_declaration(node, "xsi", XSI) {
+      Some(declaration) => {
+        fix.push(declaration);
+        "xsi".to_string()
+      }
+      None => return vec![],
+    },
+  };
+  let input = node.document().input_text();
+  let start = node.range().start + 1;
+  let name_len = input[start..].find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/').unwrap_or_default();
+  fix.push(Edit {
+    start: start + name_len,
+    end: start + name_len,
+    replacement: format!(r#" {}:type="{}:{}""#, xsi_prefix, PREFIX_XSD, local_name),
+  });
+  fix
+}
+
+/// Returns the edit declaring the canonical prefix of XML Schema namespace on root element, when not declared yet.
+/// Returns `None` when the canonical prefix is bound to another namespace.
+fn xsd_declaration(node: &Node) -> Option<Vec<Edit>> {
+  if node.lookup_namespace_uri(Some(PREFIX_XSD)) == Some(XSD) {
+    Some(vec![])
+  } else {
+    root_declaration(node, PREFIX_XSD, XSD).map(|declaration| vec![declaration])
+  }
+}
+
+/// Returns the edit declaring the namespace prefix on root element.
+/// Returns `None` when the prefix is already bound in scope of the node,
+/// the declaration would then duplicate or be shadowed by the existing one.
+fn root_declaration(node: &Node, prefix: &str, uri: &str) -> Option<Edit> {
+  if node.lookup_namespace_uri(Some(prefix)).is_some() {
+    return None;
+  }
+  let root = node.document().root_element();
+  let input = node.document().input_text();
+  let start = root.range().start + 1;
+  let name_len = input[start..].find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/').unwrap_or_default();
+  Some(Edit {
+    start: start + name_len,
+    end: start + name_len,
+    replacement: format!(r#" xmlns:{}="{}""#, prefix, uri),
+  })
+}
+
+/// Returns `true` when the decimal is written without leading zero, like `.035` or `-.5`.
+fn is_missing_leading_zero(text: &str) -> bool {
+  text.trim_start_matches(['-', '+']).starts_with('.')
+}
+
+/// Returns `true` when the duration is zero days, like `P0D`, for which the canonical form is `PT0S`.
+fn is_zero_days_duration(text: &str) -> bool {
+  matches!(text.trim_start_matches('-'), "P0D")
+}
@@ -0,0 +1,160 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Listing of discovered models, test files and labels
+
+use crate::model::parse_test_file;
+use crate::{COLOR_BLUE, COLOR_BRIGHT_WHITE, COLOR_RED, COLOR_RESET};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Directory containing models or test files.
+#[derive(Serialize)]
+struct ListedDirectory {
+  /// Tests root directory containing the directory.
+  root: String,
+  /// Directory path, displayed as configured.
+  directory: String,
+  /// Names of model files.
+  models: Vec<String>,
+  /// Test files defined in the directory.
+  test_files: Vec<ListedTestFile>,
+}
+
+/// Test file with the number of test cases and labels.
+#[derive(Serialize)]
+struct ListedTestFile {
+  /// Name of the test file.
+  file: String,
+  /// Number of test cases defined in the test file.
+  test_cases: usize,
+  /// Labels defined in the test file.
+  labels: Vec<String>,
+  /// Reason why the test file could not be parsed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+}
+
+/// Listing of all discovered directories with the set of all labels.
+#[derive(Serialize)]
+struct Listing {
+  /// Discovered directories.
+  directories: Vec<ListedDirectory>,
+  /// Labels defined in all test files.
+  labels: BTreeSet<String>,
+}
+
+/// Displays discovered directories with their models and test files, followed by the set of all labels.
+/// Directories are given as full paths, displayed by specified function.
+/// Directories are grouped by root directories, when there are more than one.
+pub fn display(root_dirs: &[String], files: &BTreeMap<String, (Vec<String>, Vec<String>)>, display_path: impl Fn(&str) -> String, json: bool) {
+  let mut labels = BTreeSet::new();
+  let root_dir = |dir_name: &str| {
+    root_dirs
+      .iter()
+      .filter(|root_dir| dir_name == root_dir.as_str() || dir_name.starts_with(&format!("{}/", root_dir)))
+      .max_by_key(|root_dir| root_dir.len())
+      .cloned()
+      .unwrap_or_default()
+  };
+  let directories = files
+    .iter()
+    .map(|(dir_name, (files_dmn, files_xml))| ListedDirectory {
+      root: root_dir(dir_name),
+      directory: display_path(dir_name),
+      models: files_dmn.clone(),
+      test_files: files_xml
+        .iter()
+        .map(|file_xml| match parse_test_file(&format!("{}/{}", dir_name, file_xml)) {
+          Ok(test_cases) => {
+            labels.extend(test_cases.labels.iter().cloned());
+            ListedTestFile {
+              file: file_xml.clone(),
+              test_cases: test_cases.test_cases.len(),
+              labels: test_cases.labels,
+              error: None,
+            }
+          }
+          Err(reason) => ListedTestFile {
+            file: file_xml.clone(),
+            test_cases: 0,
+            labels: vec![],
+            error: Some(reason.to_string()),
+          },
+        })
+        .collect(),
+    })
+    .collect::<Vec<ListedDirectory>>();
+  let listing = Listing { directories, labels };
+  if json {
+    println!(
+      "{}",
+      serde_json::to_string_pretty(&listing).unwrap_or_else(|e| panic!("serializing listing failed with reason: {}", e))
+    );
+    return;
+  }
+  let (mut model_count, mut test_file_count, mut test_case_count) = (0, 0, 0);
+  let mut current_root = None;
+  for directory in &listing.directories {
+    if root_dirs.len() > 1 && current_root != Some(&directory.root) {
+      outln!("{1}root: {2}{0}", COLOR_RESET, COLOR_BLUE, directory.root);
+      current_root = Some(&directory.root);
+    }
+    outln!("{1}{2}{0}", COLOR_RESET, COLOR_BRIGHT_WHITE, directory.directory);
+    for model in &directory.models {
+      outln!("  model: {1}{2}{0}", COLOR_RESET, COLOR_BLUE, model);
+    }
+    for test_file in &directory.test_files {
+      match &test_file.error {
+        Some(reason) => outln!("  tests: {3} {1}{2}{0}", COLOR_RESET, COLOR_RED, reason, test_file.file),
+        None if test_file.labels.is_empty() => outln!("  tests: {} ({} test case(s))", test_file.file, test_file.test_cases),
+        None => outln!(
+          "  tests: {} ({} test case(s), labels: {})",
+          test_file.file,
+          test_file.test_cases,
+          test_file.labels.join(", ")
+        ),
+      }
+      test_case_count += test_file.test_cases;
+    }
+    model_count += directory.models.len();
+    test_file_count += directory.test_files.len();
+  }
+  outln!("\nLabels: {}", listing.labels.iter().cloned().collect::<Vec<String>>().join(", "));
+  outln!(
+    "Found {} directory(ies), {} model(s), {} test file(s), {} test case(s).",
+    listing.directories.len(),
+    model_count,
+    test_file_count,
+    test_case_count
+  );
+}
@@ -0,0 +1,413 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Load testing with increasing concurrency and soak testing
+
+use crate::dto::{OptionalValueDto, ResultDto};
+use crate::params::EvaluateParams;
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET};
+use reqwest::blocking::Client;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default maximal number of concurrent workers.
+pub const DEFAULT_MAX_WORKERS: usize = 8;
+
+/// Default duration of each concurrency level in seconds.
+pub const DEFAULT_LEVEL_DURATION: u64 = 10;
+
+/// Default duration of adaptive load test in seconds.
+pub const DEFAULT_ADAPTIVE_DURATION: u64 = 60;
+
+/// Default duration of soak test in seconds.
+pub const DEFAULT_SOAK_DURATION: u64 = 3600;
+
+/// Default interval of soak test measurements in seconds.
+pub const DEFAULT_SOAK_INTERVAL: u64 = 60;
+
+/// Interval between adjustments of the number of workers in adaptive load test.
+const CONTROL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pause of the worker waiting until it is allowed to send requests.
+const IDLE_PAUSE: Duration = Duration::from_millis(10);
+
+/// Results of requests sent at single concurrency level.
+struct LevelResult {
+  /// Number of concurrent workers.
+  workers: usize,
+  /// Number of sent requests.
+  request_count: usize,
+  /// Number of requests that failed or were answered with errors.
+  error_count: usize,
+  /// Durations of all requests, sorted ascending.
+  durations: Vec<Duration>,
+  /// Time elapsed from the first request to the last response.
+  elapsed: Duration,
+}
+
+impl LevelResult {
+  /// Returns the number of requests per second.
+  fn throughput(&self) -> f64 {
+    self.request_count as f64 / self.elapsed.as_secs_f64()
+  }
+
+  /// Returns the percentage of requests that failed.
+  fn error_rate(&self) -> f64 {
+    if self.request_count > 0 {
+      self.error_count as f64 * 100.0 / self.request_count as f64
+    } else {
+      0.0
+    }
+  }
+
+  /// Returns the average request duration in milliseconds.
+  fn average_ms(&self) -> f64 {
+    if self.durations.is_empty() {
+      return 0.0;
+    }
+    self.durations.iter().sum::<Duration>().as_secs_f64() * 1000.0 / self.durations.len() as f64
+  }
+
+  /// Returns the request duration in milliseconds below which falls the specified percentage of requests.
+  fn percentile_ms(&self, percentage: usize) -> f64 {
+    if self.durations.is_empty() {
+      return 0.0;
+    }
+    let index = ((self.durations.len() * percentage).div_ceil(100)).saturating_sub(1);
+    self.durations[index].as_secs_f64() * 1000.0
+  }
+}
+
+/// Returns the concurrency levels, doubling from one worker up to maximal number of workers.
+fn levels(max_workers: usize) -> Vec<usize> {
+  let mut levels = vec![];
+  let mut workers = 1;
+  while workers < max_workers {
+    levels.push(workers);
+    workers *= 2;
+  }
+  levels.push(max_workers.max(1));
+  levels
+}
+
+/// Replays the requests at increasing concurrency levels, each level for specified duration,
+/// and displays throughput and error rate per level.
+pub fn run(client: &Client, evaluate_url: &str, requests: &[EvaluateParams], max_workers: usize, level_duration: Duration) {
+  if requests.is_empty() {
+    outln!("{1}no requests to replay{0}", COLOR_RESET, COLOR_RED);
+    return;
+  }
+  outln!("Replaying {} request(s), {} s per concurrency level", requests.len(), level_duration.as_secs());
+  let mut results = vec![];
+  for workers in levels(max_workers) {
+    out!("  {} worker(s) ... ", workers);
+    let result = run_level(client, evaluate_url, requests, workers, level_duration);
+    outln!("{1}{2:.0} req/s{0}", COLOR_RESET, COLOR_GREEN, result.throughput());
+    results.push(result);
+  }
+  display_results(&results);
+}
+
+/// Sends requests from specified number of workers until the duration elapses.
+fn run_level(client: &Client, evaluate_url: &str, requests: &[EvaluateParams], workers: usize, level_duration: Duration) -> LevelResult {
+  let next_request = AtomicUsize::new(0);
+  let start_time = Instant::now();
+  let deadline = start_time + level_duration;
+  let worker_results = thread::scope(|scope| {
+    let handles = (0..workers)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut durations = vec![];
+          let mut error_count = 0;
+          while Instant::now() < deadline {
+            let params = &requests[next_request.fetch_add(1, Ordering::Relaxed) % requests.len()];
+            let request_start_time = Instant::now();
+            let failed = send(client, evaluate_url, params);
+            durations.push(request_start_time.elapsed());
+            if failed {
+              error_count += 1;
+            }
+          }
+          (durations, error_count)
+        })
+      })
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .map(|handle| handle.join().unwrap_or_else(|_| panic!("load testing worker failed")))
+      .collect::<Vec<(Vec<Duration>, usize)>>()
+  });
+  let elapsed = start_time.elapsed();
+  let mut durations = vec![];
+  let mut error_count = 0;
+  for (worker_durations, worker_error_count) in worker_results {
+    durations.extend(worker_durations);
+    error_count += worker_error_count;
+  }
+  durations.sort();
+  LevelResult {
+    workers,
+    request_count: durations.len(),
+    error_count,
+    durations,
+    elapsed,
+  }
+}
+
+/// Replays the requests for specified duration, controlling the number of workers adaptively (AIMD):
+/// after every control interval one worker is added when the 95th percentile of latency stays under the target,
+/// and the number of workers is halved when the latency rises above the target or any errors occur.
+pub fn run_adaptive(client: &Client, evaluate_url: &str, requests: &[EvaluateParams], max_workers: usize, duration: Duration, target_latency: Duration) {
+  if requests.is_empty() {
+    outln!("{1}no requests to replay{0}", COLOR_RESET, COLOR_RED);
+    return;
+  }
+  outln!(
+    "Replaying {} request(s) for {} s, target latency (p95): {} ms",
+    requests.len(),
+    duration.as_secs(),
+    target_latency.as_millis()
+  );
+  let max_workers = max_workers.max(1);
+  let allowed_workers = AtomicUsize::new(1);
+  let next_request = AtomicUsize::new(0);
+  // durations and error flags of requests completed in the current control interval
+  let samples = Mutex::new(Vec::<(Duration, bool)>::new());
+  let deadline = Instant::now() + duration;
+  let mut results = vec![];
+  thread::scope(|scope| {
+    for worker in 0..max_workers {
+      let (allowed_workers, next_request, samples) = (&allowed_workers, &next_request, &samples);
+      scope.spawn(move || {
+        while Instant::now() < deadline {
+          if worker >= allowed_workers.load(Ordering::Relaxed) {
+            thread::sleep(IDLE_PAUSE);
+            continue;
+          }
+          let params = &requests[next_request.fetch_add(1, Ordering::Relaxed) % requests.len()];
+          let request_start_time = Instant::now();
+          let failed = send(client, evaluate_url, params);
+          let sample = (request_start_time.elapsed(), failed);
+          samples.lock().unwrap_or_else(|e| panic!("collecting samples failed with reason: {}", e)).push(sample);
+        }
+      });
+    }
+    let mut interval_start_time = Instant::now();
+    while Instant::now() < deadline {
+      thread::sleep(CONTROL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())));
+      let interval_samples = std::mem::take(&mut *samples.lock().unwrap_or_else(|e| panic!("collecting samples failed with reason: {}", e)));
+      let workers = allowed_workers.load(Ordering::Relaxed);
+      let mut durations = interval_samples.iter().map(|(duration, _)| *duration).collect::<Vec<Duration>>();
+      durations.sort();
+      let result = LevelResult {
+        workers,
+        request_count: durations.len(),
+        error_count: interval_samples.iter().filter(|(_, failed)| *failed).count(),
+        durations,
+        elapsed: interval_start_time.elapsed(),
+      };
+      interval_start_time = Instant::now();
+      // no request completed in the whole interval means the latency is over the target too
+      let over_target = result.request_count == 0 || result.percentile_ms(95) > target_latency.as_secs_f64() * 1000.0;
+      let next_workers = if result.error_count > 0 || over_target {
+        (workers / 2).max(1)
+      } else {
+        (workers + 1).min(max_workers)
+      };
+      allowed_workers.store(next_workers, Ordering::Relaxed);
+      outln!(
+        "  {:>4} s: {} worker(s), {:.0} req/s, p95 {:.2} ms, {} error(s)",
+        results.len() + 1,
+        workers,
+        result.throughput(),
+        result.percentile_ms(95),
+        result.error_count
+      );
+      results.push(result);
+    }
+  });
+  display_results(&results);
+  // the second half of the test shows the number of workers the controller has settled on
+  let settled = &results[results.len() / 2..];
+  if !settled.is_empty() {
+    let average_workers = settled.iter().map(|result| result.workers).sum::<usize>() as f64 / settled.len() as f64;
+    outln!("Settled concurrency: {:.1} worker(s) on average in the second half of the test", average_workers);
+  }
+}
+
+/// Replays the requests continuously from specified number of workers for specified duration,
+/// and displays the latency and error rate measured in every interval, with their drift over the whole test.
+pub fn run_soak(client: &Client, evaluate_url: &str, requests: &[EvaluateParams], workers: usize, duration: Duration, interval: Duration) {
+  if requests.is_empty() {
+    outln!("{1}no requests to replay{0}", COLOR_RESET, COLOR_RED);
+    return;
+  }
+  outln!(
+    "Replaying {} request(s) for {} s with {} worker(s), measured every {} s",
+    requests.len(),
+    duration.as_secs(),
+    workers.max(1),
+    interval.as_secs()
+  );
+  let next_request = AtomicUsize::new(0);
+  // durations and error flags of requests completed in the current interval
+  let samples = Mutex::new(Vec::<(Duration, bool)>::new());
+  let start_time = Instant::now();
+  let deadline = start_time + duration;
+  let mut results = vec![];
+  thread::scope(|scope| {
+    for _ in 0..workers.max(1) {
+      let (next_request, samples) = (&next_request, &samples);
+      scope.spawn(move || {
+        while Instant::now() < deadline {
+          let params = &requests[next_request.fetch_add(1, Ordering::Relaxed) % requests.len()];
+          let request_start_time = Instant::now();
+          let failed = send(client, evaluate_url, params);
+          let sample = (request_start_time.elapsed(), failed);
+          samples.lock().unwrap_or_else(|e| panic!("collecting samples failed with reason: {}", e)).push(sample);
+        }
+      });
+    }
+    let mut interval_start_time = Instant::now();
+    while Instant::now() < deadline {
+      thread::sleep(interval.min(deadline.saturating_duration_since(Instant::now())));
+      let interval_samples = std::mem::take(&mut *samples.lock().unwrap_or_else(|e| panic!("collecting samples failed with reason: {}", e)));
+      let mut durations = interval_samples.iter().map(|(duration, _)| *duration).collect::<Vec<Duration>>();
+      durations.sort();
+      let result = LevelResult {
+        workers,
+        request_count: durations.len(),
+        error_count: interval_samples.iter().filter(|(_, failed)| *failed).count(),
+        durations,
+        elapsed: interval_start_time.elapsed(),
+      };
+      interval_start_time = Instant::now();
+      outln!(
+        "  {:>6} s: {:.0} req/s, avg {:.2} ms, p95 {:.2} ms, {:.2}% error(s)",
+        start_time.elapsed().as_secs(),
+        result.throughput(),
+        result.average_ms(),
+        result.percentile_ms(95),
+        result.error_rate()
+      );
+      results.push(result);
+    }
+  });
+  display_drift(&results, interval);
+}
+
+/// Displays the drift of latency and error rate over all intervals of soak test,
+/// as the slope of linear regression per hour and as the difference between the first and the last interval.
+fn display_drift(results: &[LevelResult], interval: Duration) {
+  let (Some(first), Some(last)) = (results.first(), results.last()) else {
+    return;
+  };
+  let hours = results
+    .iter()
+    .enumerate()
+    .map(|(index, _)| (index as f64 + 0.5) * interval.as_secs_f64() / 3600.0)
+    .collect::<Vec<f64>>();
+  let average_slope = slope(&hours, &results.iter().map(LevelResult::average_ms).collect::<Vec<f64>>());
+  let p95_slope = slope(&hours, &results.iter().map(|result| result.percentile_ms(95)).collect::<Vec<f64>>());
+  let error_rate_slope = slope(&hours, &results.iter().map(LevelResult::error_rate).collect::<Vec<f64>>());
+  outln!(
+    "
+Soak test drift:"
+  );
+  outln!("┌────────────┬────────────────┬────────────────┬──────────────┐");
+  outln!("│     Metric │ First interval │  Last interval │ Trend / hour │");
+  outln!("├────────────┼────────────────┼────────────────┼──────────────┤");
+  outln!("│   Avg (ms) │ {:>14.2} │ {:>14.2} │ {:>+12.2} │", first.average_ms(), last.average_ms(), average_slope);
+  outln!(
+    "│   p95 (ms) │ {:>14.2} │ {:>14.2} │ {:>+12.2} │",
+    first.percentile_ms(95),
+    last.percentile_ms(95),
+    p95_slope
+  );
+  outln!(
+    "│ Errors (%) │ {2:>14.2} │ {1}{3:>14.2}{0} │ {4:>+12.2} │",
+    COLOR_RESET,
+    if last.error_count > 0 { COLOR_RED } else { COLOR_GREEN },
+    first.error_rate(),
+    last.error_rate(),
+    error_rate_slope
+  );
+  outln!("└────────────┴────────────────┴────────────────┴──────────────┘");
+  let request_count = results.iter().map(|result| result.request_count).sum::<usize>();
+  let error_count = results.iter().map(|result| result.error_count).sum::<usize>();
+  outln!("Total requests: {}, errors: {}", request_count, error_count);
+}
+
+/// Returns the slope of the least squares regression line of values.
+fn slope(xs: &[f64], ys: &[f64]) -> f64 {
+  let n = xs.len() as f64;
+  let mean_x = xs.iter().sum::<f64>() / n;
+  let mean_y = ys.iter().sum::<f64>() / n;
+  let covariance = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+  let variance = xs.iter().map(|x| (x - mean_x).powi(2)).sum::<f64>();
+  if variance > 0.0 {
+    covariance / variance
+  } else {
+    0.0
+  }
+}
+
+/// Sends the evaluation request, returns `true` when the request failed or the engine reported errors.
+pub fn send(client: &Client, evaluate_url: &str, params: &EvaluateParams) -> bool {
+  match client.post(evaluate_url).json(params).send().and_then(|response| response.error_for_status()?.text()) {
+    Ok(body) => serde_json::from_str::<ResultDto<OptionalValueDto>>(&body).map_or(true, |result| result.errors.is_some()),
+    Err(_) => true,
+  }
+}
+
+/// Displays the results of all concurrency levels and the level with the highest throughput.
+fn display_results(results: &[LevelResult]) {
+  outln!("\nLoad test:");
+  outln!("┌─────────┬──────────┬───────────┬─────────┬──────────┬──────────┐");
+  outln!("│ Workers │ Requests │ Req / sec │  Errors │ Avg (ms) │ p95 (ms) │");
+  outln!("├─────────┼──────────┼───────────┼─────────┼──────────┼──────────┤");
+  for result in results {
+    let (workers, request_count, throughput) = (result.workers, result.request_count, result.throughput());
+    let (error_rate, average, p95) = (result.error_rate(), result.average_ms(), result.percentile_ms(95));
+    outln!(
+      "│ {workers:>7} │ {request_count:>8} │ {throughput:>9.0} │{1}{error_rate:>7.2}%{0} │ {average:>8.2} │ {p95:>8.2} │",
+      COLOR_RESET,
+      if result.error_count > 0 { COLOR_RED } else { COLOR_GREEN }
+    );
+  }
+  outln!("└─────────┴──────────┴───────────┴─────────┴──────────┴──────────┘");
+  if let Some(peak) = results.iter().max_by(|lhs, rhs| lhs.throughput().total_cmp(&rhs.throughput())) {
+    outln!("Peak throughput: {:.0} req/s with {} worker(s)", peak.throughput(), peak.workers);
+  }
+}
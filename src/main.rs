@@ -32,23 +32,79 @@
 
 //! # Test runner for DMN™ Technology Compatibility Kit
 
-use crate::context::{Context, TestResult};
-use crate::dto::{InputNodeDto, OptionalValueDto, ResultDto, ValueDto};
-use crate::model::{parse_test_file, Value};
+use crate::cli::{Arguments, Command};
+use crate::coldstart::ModelRequest;
+use crate::compare::{ExpectedPattern, Mismatch, MismatchClass};
+use crate::config::{ComparatorConfig, ComponentOrder, ConfigurationParams, ErrorMessageMatching, EventsFormat, Validation};
+use crate::connections::ConnectionMetrics;
+use crate::context::{dir_name, Context, FailedTest, FailureCategory, ModelError, PhaseTimes, TestInfo, TestResult};
+use crate::dto::{FailureArtifactDto, InputNodeDto, OptionalValueDto, ResultDto, ValueDto};
+use crate::events::{Event, EventStream};
+use crate::history::History;
+use crate::model::{metadata, parse_test_file, stream_test_file, Duplicates, ResultNode, TestCase, TestCases, Value};
+use crate::notify::NotificationPayload;
 use crate::params::EvaluateParams;
+use crate::resources::{ResourceSampler, ResourceSource};
+use crate::selection::FileSelection;
+use crate::tui::Tui;
+use rayon::prelude::*;
 use regex::Regex;
 use reqwest::blocking::Client;
-use std::collections::BTreeMap;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
+use std::io;
+use std::mem;
 use std::path::Path;
+use std::process;
 use std::string::ToString;
-use std::time::Instant;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
+#[macro_use]
+mod console;
+mod badge;
+mod bench;
+mod cache;
+mod canonical;
+mod cli;
+mod coldstart;
+mod compare;
+mod computed;
 mod config;
+mod connections;
 mod context;
+mod coverage;
+mod curl;
+mod dashboard;
+mod diff;
 mod dto;
+mod events;
+mod generate;
+mod histogram;
+mod history;
+mod humanize;
+mod imports;
+mod interrupt;
+mod lint;
+mod list;
+mod load;
 mod model;
+mod notify;
 mod params;
+mod plugin;
+mod report;
+mod resources;
+mod selection;
+mod serializer;
+mod shuffle;
+mod trend;
+mod tui;
+mod types;
 
 pub const COLOR_RED: &str = "\u{1b}[31m";
 pub const COLOR_GREEN: &str = "\u{1b}[32m";
@@ -57,210 +113,1880 @@ pub const COLOR_YELLOW: &str = "\u{1b}[33m";
 pub const COLOR_RESET: &str = "\u{1b}[0m";
 pub const COLOR_BRIGHT_WHITE: &str = "\u{1b}[37;1m";
 pub const GUTTER: usize = 250;
-pub const GAP: &str = "...........................................................................................................................................................................................................";
+/// Dots filling the gap between the text and the result of the operation, aligned to the gutter.
+pub const GAP: &str = "..........................................................................................................................................................................................................................................................";
+
+/// Returns the dots filling the gap after text of specified length, empty when the text reaches the gutter.
+fn gap(text_len: usize) -> &'static str {
+  &GAP[..GUTTER.saturating_sub(text_len).min(GAP.len())]
+}
 
 /// Main entrypoint of the runner.
 fn main() {
-  // read configuration from file
-  let config = config::get();
-  // prepare the full directory path where test are stored
-  let root_dir = Path::new(&config.test_cases_dir_path).canonicalize().expect("reading test directory failed");
-  // create the testing context
-  let mut ctx = Context::new(
-    config.stop_on_failure,
-    config.file_search_pattern,
-    &config.report_file,
-    &config.tck_report_file,
-    root_dir.to_string_lossy().to_string(),
-  );
-  if root_dir.exists() && root_dir.is_dir() {
-    print!("Starting DMN TCK runner...");
-    let client = Client::new();
-    println!("ok");
-    println!("File search pattern: {}", ctx.file_search_pattern);
-    print!("Searching DMN files in directory: {} ... ", root_dir.display());
-    let mut files = BTreeMap::new();
-    let pattern = Regex::new(&ctx.file_search_pattern).expect("parsing search pattern failed");
-    search_files(&root_dir, &pattern, &mut files);
-    println!("ok");
-    for (dir_name, (files_dmn, files_xml)) in files {
-      // retrieve model names and namespaces from DMN files
-      for file_dmn in files_dmn {
-        ctx.process_model_definitions(&root_dir, &dir_name, &file_dmn);
+  let arguments = match cli::parse() {
+    Ok(arguments) => arguments,
+    Err(reason) => {
+      outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+      usage();
+      process::exit(1);
+    }
+  };
+  if arguments.ascii {
+    console::enable_ascii();
+  }
+  if arguments.help {
+    usage();
+    return;
+  }
+  match &arguments.command {
+    Command::Run => run(configuration(&arguments)),
+    Command::Load | Command::Soak => load(&arguments.command, configuration(&arguments)),
+    Command::ColdStart => cold_start(configuration(&arguments)),
+    Command::Daemon => daemon(configuration(&arguments)),
+    Command::BenchCompare { baseline_file, current_file } => match bench::compare(baseline_file, current_file, arguments.csv) {
+      Ok(false) => {}
+      Ok(true) => process::exit(1),
+      Err(reason) => {
+        outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+        process::exit(1);
+      }
+    },
+    Command::ReportTrend => {
+      let config = configuration(&arguments);
+      let history_db = config
+        .history_db
+        .as_ref()
+        .unwrap_or_else(|| misconfigured("history_db: history database not specified in configuration"));
+      trend::report(history_db, arguments.last_runs, arguments.csv);
+    }
+    Command::List => list(configuration(&arguments), arguments.json),
+    Command::Lint { path } => {
+      let paths = path.clone().map_or_else(|| configuration(&arguments).test_cases_dir_paths, |path| vec![path]);
+      lint(&paths, arguments.fix);
+    }
+    Command::ConfigCheck => check_configuration(&arguments),
+    Command::Init { output_file } => match config::init(output_file) {
+      Ok(()) => outln!("Generated configuration file: {}", output_file),
+      Err(reason) => {
+        outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+        process::exit(1);
+      }
+    },
+    Command::Generate { model_file, output_file } => match generate::generate(model_file, output_file.as_deref()) {
+      Ok(test_file) => outln!("Generated test file: {}", test_file),
+      Err(reason) => {
+        outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+        process::exit(1);
+      }
+    },
+  }
+}
+
+/// Lists models, test files and labels discovered in test cases directory, without executing any tests.
+fn list(config: ConfigurationParams, json: bool) {
+  let root_dirs = test_cases_dirs(&config);
+  let selection = FileSelection::new(&config);
+  let mut files = DiscoveredFiles::new();
+  for root_dir in &root_dirs {
+    files.extend(find_files(Path::new(root_dir), &config.file_search_pattern, &selection));
+  }
+  let ctx = Context::without_reports(&config, root_dirs.clone());
+  list::display(&root_dirs, &files, |dir_name| ctx.display_path(dir_name), json);
+}
+
+/// Checks all test files in specified files or directories, exits with failure when any problems were found.
+/// When fixing is requested, fixable problems are fixed and only problems that remain are counted.
+fn lint(paths: &[String], fix: bool) {
+  let mut test_files = vec![];
+  let all_files = Regex::new("").unwrap();
+  for path in paths {
+    if Path::new(path).is_dir() {
+      let mut files = BTreeMap::new();
+      let path = Path::new(path)
+        .canonicalize()
+        .unwrap_or_else(|e| panic!("reading directory {} failed with reason: {}", path, e));
+      search_files(&path, &path, &all_files, &FileSelection::default(), &mut files);
+      for (dir_name, (_, mut files_xml)) in files {
+        files_xml.sort();
+        test_files.extend(files_xml.into_iter().map(|file_xml| format!("{}/{}", dir_name, file_xml)));
+      }
+    } else {
+      test_files.push(path.to_string());
+    }
+  }
+  let mut problem_count = 0;
+  for test_file in &test_files {
+    let result = if fix { lint::fix_file(test_file) } else { lint::lint_file(test_file) };
+    match result {
+      Ok(findings) => {
+        for finding in &findings {
+          let fixed = fix && !finding.fix.is_empty();
+          let status = if fixed { format!(" {1}(fixed){0}", COLOR_RESET, COLOR_GREEN) } else { String::new() };
+          outln!(
+            "{2}:{3}:{4}: {1}[{5}]{0} {6}{7}",
+            COLOR_RESET,
+            if fixed { COLOR_GREEN } else { COLOR_YELLOW },
+            test_file,
+            finding.line,
+            finding.column,
+            finding.rule,
+            finding.message,
+            status
+          );
+        }
+        problem_count += findings.iter().filter(|finding| !fix || finding.fix.is_empty()).count();
       }
-      // execute all tests
-      for file_xml in files_xml {
-        let file_path = format!("{}/{}", dir_name, file_xml);
-        execute_tests(&mut ctx, &file_path, &client, &config.evaluate_url);
+      Err(reason) => {
+        outln!("{2}: {1}{3}{0}", COLOR_RESET, COLOR_RED, test_file, reason);
+        problem_count += 1;
       }
     }
-    let success_count = ctx.success_count;
-    let failure_count = ctx.failure_count;
-    let total_count = success_count + failure_count;
-    let total_execution_time = (ctx.execution_time / 1_000_000) as f64 / 1000.0;
-    let requests_per_second = total_count as f64 / total_execution_time;
-    let (success_perc, failure_perc) = if total_count > 0 {
-      ((success_count * 100) as f64 / total_count as f64, (failure_count * 100) as f64 / total_count as f64)
+  }
+  outln!("\nChecked {} test file(s), found {} problem(s).", test_files.len(), problem_count);
+  if problem_count > 0 {
+    process::exit(1);
+  }
+}
+
+/// Returns the name of the configuration file given on command line or found in standard locations.
+fn config_file(arguments: &Arguments) -> String {
+  match &arguments.config_file {
+    Some(config_file) => config_file.clone(),
+    None => config::discover().unwrap_or_else(|reason| misconfigured(&reason)),
+  }
+}
+
+/// Checks the configuration file, exits with failure when any parameter is invalid.
+fn check_configuration(arguments: &Arguments) {
+  let config_file = config_file(arguments);
+  let config = config::get(&config_file, arguments.profile.as_deref()).unwrap_or_else(|reason| {
+    outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+    process::exit(1);
+  });
+  if config.ascii {
+    console::enable_ascii();
+  }
+  let errors = config::validate(&config, Validation::All);
+  if errors.is_empty() {
+    outln!("{1}Configuration file '{2}' is valid.{0}", COLOR_RESET, COLOR_GREEN, config_file);
+  } else {
+    outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, invalid_configuration(&config_file, &errors));
+    process::exit(1);
+  }
+}
+
+/// Returns the message listing invalid parameters of configuration file.
+fn invalid_configuration(config_file: &str, errors: &[String]) -> String {
+  format!("invalid configuration file '{}':\n  {}", config_file, errors.join("\n  "))
+}
+
+/// Reads configuration from file, command line arguments take precedence over configuration.
+/// Exits with failure when any parameter is invalid.
+fn configuration(arguments: &Arguments) -> ConfigurationParams {
+  let config_file = config_file(arguments);
+  let mut config = config::get(&config_file, arguments.profile.as_deref()).unwrap_or_else(|reason| misconfigured(&reason));
+  if arguments.ascii {
+    config.ascii = true;
+  }
+  if config.ascii {
+    console::enable_ascii();
+  }
+  if arguments.repeat.is_some() {
+    config.repeat = arguments.repeat;
+  }
+  if arguments.max_failures.is_some() {
+    config.max_failures = arguments.max_failures;
+  }
+  if arguments.expected_tests.is_some() {
+    config.expected_tests = arguments.expected_tests;
+  }
+  if arguments.load_workers.is_some() {
+    if arguments.command == Command::Soak {
+      config.soak_workers = arguments.load_workers;
     } else {
-      (0.0, 0.0)
-    };
-    println!("\nTests:");
-    println!("┌─────────┬───────┬─────────┐");
-    println!("│   Total │ {total_count:>5} │         │");
-    println!("├─────────┼───────┼─────────┤");
-    println!("│ {1}Success{0} │ {1}{success_count:>5}{0} │{1}{success_perc:>7.2}%{0} │", COLOR_RESET, COLOR_GREEN);
-    println!(
-      "│ {1}Failure{0} │ {1}{failure_count:>5}{0} │{1}{failure_perc:>7.2}%{0} │",
+      config.load_max_workers = arguments.load_workers;
+    }
+  }
+  if let Some(load_target_latency) = arguments.load_target_latency {
+    config.load_target_latency_ms = Some(load_target_latency as u64);
+  }
+  if let Some(load_duration) = arguments.load_duration {
+    if arguments.command == Command::Soak {
+      config.soak_duration = Some(load_duration as u64);
+    } else if config.load_target_latency_ms.is_some() {
+      config.load_adaptive_duration = Some(load_duration as u64);
+    } else {
+      config.load_level_duration = Some(load_duration as u64);
+    }
+  }
+  if !arguments.levels.is_empty() {
+    config.compliance_levels = arguments.levels.clone();
+  }
+  config.run_metadata.extend(arguments.run_metadata.iter().cloned());
+  if arguments.test_case_name.is_some() {
+    config.test_case_name_pattern = arguments.test_case_name.clone();
+  }
+  if arguments.write_computed {
+    config.write_computed = true;
+  }
+  if arguments.strict {
+    config.strict = true;
+  }
+  if arguments.shuffle {
+    config.shuffle = true;
+  }
+  if arguments.changed_only {
+    config.changed_only = true;
+  }
+  if arguments.only_failed {
+    config.only_failed = true;
+  }
+  if arguments.tui {
+    config.tui = true;
+  }
+  if arguments.curl {
+    config.curl_commands = true;
+  }
+  if let Some(events_format) = arguments.events_format {
+    config.events_format = events_format;
+  }
+  if let Some(seed) = arguments.seed {
+    config.seed = Some(seed as u64);
+  }
+  if config.shuffle && config.seed.is_none() {
+    // the seed is always known, so the shuffled order can be reproduced
+    config.seed = Some(shuffle::random_seed());
+  }
+  if arguments.smoke {
+    config.smoke_tests = true;
+  }
+  if arguments.ignore_case || arguments.trim_strings {
+    // options given on command line are merged into configured string comparator, if any
+    match config.comparators.iter_mut().find(|comparator| matches!(comparator, ComparatorConfig::Strings { .. })) {
+      Some(ComparatorConfig::Strings { ignore_case, trim }) => {
+        *ignore_case |= arguments.ignore_case;
+        *trim |= arguments.trim_strings;
+      }
+      _ => config.comparators.insert(
+        0,
+        ComparatorConfig::Strings {
+          ignore_case: arguments.ignore_case,
+          trim: arguments.trim_strings,
+        },
+      ),
+    }
+  }
+  let errors = config::validate(&config, validation(&arguments.command));
+  if !errors.is_empty() {
+    misconfigured(&invalid_configuration(&config_file, &errors));
+  }
+  config
+}
+
+/// Returns the scope of configuration parameters validated for the command.
+fn validation(command: &Command) -> Validation {
+  match command {
+    Command::Run | Command::Daemon | Command::ConfigCheck => Validation::All,
+    Command::Load | Command::Soak | Command::ColdStart => Validation::Requests,
+    Command::List | Command::Lint { path: None } => Validation::TestFiles,
+    _ => Validation::Nothing,
+  }
+}
+
+/// Displays the reason of invalid configuration followed by usage message, and exits with failure.
+fn misconfigured(reason: &str) -> ! {
+  outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+  usage();
+  process::exit(1);
+}
+
+/// Returns full paths of all directories where tests are stored.
+fn test_cases_dirs(config: &ConfigurationParams) -> Vec<String> {
+  config
+    .test_cases_dir_paths
+    .iter()
+    .map(|test_cases_dir_path| match Path::new(test_cases_dir_path).canonicalize() {
+      Ok(root_dir) if root_dir.is_dir() => root_dir.to_string_lossy().to_string(),
+      _ => misconfigured(&format!("test cases directory '{}' not found", test_cases_dir_path)),
+    })
+    .collect()
+}
+
+/// Runs all tests defined in configuration.
+fn run(config: ConfigurationParams) {
+  // prepare full paths of directories where test are stored
+  let root_dirs = test_cases_dirs(&config);
+  // failures are read before the report file is overwritten and the current run is registered in history
+  let previous_failures = config.only_failed.then(|| previous_failures(&config));
+  // create the testing context
+  let mut ctx = Context::new(&config, root_dirs.clone(), open_history(&config, &root_dirs));
+  ctx.previous_failures = previous_failures;
+  if config.events_format == EventsFormat::Jsonl {
+    ctx.events = Some(EventStream::open());
+  }
+  out!("Starting DMN TCK runner...");
+  let connection_metrics = Arc::new(ConnectionMetrics::default());
+  let client = connections::client(&config, &connection_metrics);
+  outln!("ok");
+  outln!("File search pattern: {}", ctx.file_search_pattern);
+  if !config.file_search_globs.is_empty() {
+    outln!("File search globs: {}", config.file_search_globs.join(", "));
+  }
+  if !config.include.is_empty() {
+    outln!("Included test files: {}", config.include.join(", "));
+  }
+  if !config.exclude.is_empty() {
+    outln!("Excluded test files: {}", config.exclude.join(", "));
+  }
+  if let Some(pattern) = &ctx.test_case_name_pattern {
+    outln!("Test case name pattern: {}", pattern);
+  }
+  if !config.run_metadata.is_empty() {
+    let run_metadata = config.run_metadata.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<String>>();
+    outln!("Run metadata: {}", run_metadata.join(", "));
+  }
+  if let Some(previous_failures) = &ctx.previous_failures {
+    outln!("Tests failed in the last run: {}", previous_failures.len());
+  }
+  if !config.compliance_levels.is_empty() {
+    outln!(
+      "Compliance levels: {}",
+      config.compliance_levels.iter().map(u8::to_string).collect::<Vec<String>>().join(", ")
+    );
+  }
+  if let Some(seed) = config.seed.filter(|_| config.shuffle) {
+    outln!("Shuffled execution order, seed: {}", seed);
+  }
+  if config.changed_only && config.cache_file.is_none() {
+    outln!("{1}cache file not specified in configuration, all tests are executed{0}", COLOR_RESET, COLOR_YELLOW);
+  }
+  if config.expected_tests.is_some() && ctx.is_filtered() {
+    outln!("{1}tests are filtered, the number of executed test cases is not checked{0}", COLOR_RESET, COLOR_YELLOW);
+  }
+  let files = discover_files(&mut ctx, &root_dirs);
+  interrupt::install();
+  execute_all(&config, &mut ctx, &files, &client, &connection_metrics, None);
+  if let Some(events) = ctx.events.take() {
+    events.close();
+  }
+  if interrupt::is_interrupted() {
+    process::exit(interrupt::EXIT_CODE);
+  }
+  if ctx.max_failures_reached() {
+    process::exit(1);
+  }
+  if let Some((expected_tests, executed_tests)) = ctx.unexpected_test_count() {
+    outln!(
+      "{1}Expected {2} test case(s), but {3} test case(s) were executed{0}",
       COLOR_RESET,
-      if failure_count > 0 { COLOR_RED } else { COLOR_BRIGHT_WHITE }
+      COLOR_RED,
+      humanize::count(expected_tests),
+      humanize::count(executed_tests)
     );
-    println!("└─────────┴───────┴─────────┘");
-    ctx.display_test_cases_report();
-    println!("\nTimings:");
-    println!("┌───────────────────────┬────────┐");
-    println!("│ Average requests time │ {:>5.02}s │", (ctx.execution_time / 1_000_000) as f64 / 1000.0);
-    println!("│   Requests per second │ {:>6.0} │", requests_per_second);
-    println!("└───────────────────────┴────────┘");
-  } else {
-    usage();
+    process::exit(1);
   }
 }
 
-fn execute_tests(ctx: &mut Context, file_path: &str, client: &Client, evaluate_url: &str) {
-  let text = format!("  Parsing test file: {}", file_path);
-  print!("\n{} {} ", text, &GAP[..GUTTER - text.len()]);
-  let test_cases = parse_test_file(file_path);
-  println!("{1}ok{0}\n", COLOR_RESET, COLOR_GREEN);
-  let empty_id = String::new();
-  let model_file_name = test_cases.model_name.clone().expect("model name not specified in test case");
-  let workspace_name = ctx.get_workspace_name(&model_file_name);
-  let model_namespace = ctx.get_model_rdnn(&model_file_name);
-  let model_name = ctx.get_model_name(&model_file_name);
-  for test_case in &test_cases.test_cases {
-    let test_case_id = test_case.id.as_ref().unwrap_or(&empty_id);
-    let opt_invocable_name = test_case.invocable_name.as_ref().cloned();
-    for (i, result_node) in test_case.result_nodes.iter().enumerate() {
-      let test_id = if i > 0 { format!("{}:{}", test_case_id, i) } else { test_case_id.to_string() };
-      let invocable_name = if let Some(invocable_name) = &opt_invocable_name {
-        invocable_name.to_string()
+/// Returns tests that have failed in the last run, read from the history database when configured,
+/// otherwise from the report file. Exits with failure when the last run can not be read.
+fn previous_failures(config: &ConfigurationParams) -> BTreeSet<FailedTest> {
+  let failures = match &config.history_db {
+    Some(history_db) => history::last_failures(history_db),
+    None => report::read_failures(&config.report_file, &config.report_format),
+  };
+  failures.unwrap_or_else(|reason| {
+    outln!("{1}{2}{0}", COLOR_RESET, COLOR_RED, reason);
+    process::exit(1);
+  })
+}
+
+/// Opens the history database when configured, registering a new run.
+fn open_history(config: &ConfigurationParams, root_dirs: &[String]) -> Option<History> {
+  config
+    .history_db
+    .as_ref()
+    .map(|history_db| History::open(history_db, &root_dirs.join(", "), &config.file_search_pattern, &config.evaluate_url, &config.run_metadata))
+}
+
+/// Executes tests from all discovered test files and displays the summary of the run.
+/// Parsed test files are reused from and stored in specified cache, when given.
+fn execute_all(
+  config: &ConfigurationParams,
+  ctx: &mut Context,
+  files: &DiscoveredFiles,
+  client: &Client,
+  connection_metrics: &ConnectionMetrics,
+  parsed_files: Option<&mut ParsedFiles>,
+) {
+  let start_time = Instant::now();
+  let mut file_paths = files
+    .iter()
+    .flat_map(|(dir_name, (_, files_xml))| files_xml.iter().map(move |file_xml| format!("{}/{}", dir_name, file_xml)))
+    .collect::<Vec<String>>();
+  if let Some(shuffle) = &mut ctx.shuffle {
+    shuffle.shuffle(&mut file_paths);
+  }
+  file_paths.retain(|file_path| ctx.has_previous_failures(file_path));
+  let mut parsed = parse_all(ctx, &file_paths, parsed_files);
+  if config.tui && !Tui::is_available() {
+    outln!(
+      "{1}interactive terminal interface requires a terminal, console output is displayed{0}",
+      COLOR_RESET,
+      COLOR_YELLOW
+    );
+  } else if config.tui {
+    let expected_count = parsed
+      .values()
+      .flatten()
+      .flat_map(|test_cases| &test_cases.test_cases)
+      .map(|test_case| test_case.result_nodes.len())
+      .sum();
+    ctx.tui = Some(Tui::start(expected_count));
+  }
+  if ctx.events.is_some() {
+    let expected_tests = parsed
+      .values()
+      .flatten()
+      .flat_map(|test_cases| &test_cases.test_cases)
+      .map(|test_case| test_case.result_nodes.len())
+      .sum();
+    let test_cases_dirs = ctx
+      .root_dir_paths
+      .iter()
+      .map(|root_dir_path| root_dir_path.trim_end_matches('/').to_string())
+      .collect::<Vec<String>>();
+    ctx.emit(&Event::RunStarted {
+      test_cases_dirs: &test_cases_dirs,
+      evaluate_url: &config.evaluate_url,
+      expected_tests,
+      metadata: &config.run_metadata,
+    });
+  }
+  let mut worker = config.comparison_worker_threshold.map(|threshold| ComparisonWorker::new(config, threshold));
+  let sampler = resource_source(config).and_then(|source| {
+    let interval = config.resource_sampling_interval_ms.unwrap_or(resources::DEFAULT_SAMPLING_INTERVAL);
+    ResourceSampler::start(source, Duration::from_millis(interval))
+  });
+  // execute all tests, until interrupted or the maximal number of failures is reached
+  let mut executed_file_count = 0;
+  for file_path in &file_paths {
+    if ctx.is_stopped() {
+      break;
+    }
+    execute_tests(ctx, file_path, client, &config.evaluate_url, parsed.remove(file_path), worker.as_mut());
+    executed_file_count += 1;
+  }
+  let resource_usage = sampler.map(ResourceSampler::stop);
+  if let Some(tui) = ctx.tui.take() {
+    tui.finish();
+  }
+  let success_count = ctx.success_count;
+  let failure_count = ctx.failure_count;
+  let total_count = success_count + failure_count;
+  let total_execution_time = Duration::from_nanos(ctx.execution_time as u64).as_secs_f64();
+  let requests_per_second = if total_execution_time > 0.0 {
+    ctx.request_count as f64 / total_execution_time
+  } else {
+    0.0
+  };
+  let (success_perc, failure_perc) = if total_count > 0 {
+    ((success_count * 100) as f64 / total_count as f64, (failure_count * 100) as f64 / total_count as f64)
+  } else {
+    (0.0, 0.0)
+  };
+  ctx.emit(&Event::RunFinished {
+    total: total_count,
+    success: success_count,
+    failure: failure_count,
+    skipped: ctx.skipped_count,
+    duration_ms: start_time.elapsed().as_millis(),
+  });
+  let (total, success, failure) = (humanize::count(total_count), humanize::count(success_count), humanize::count(failure_count));
+  outln!("\nTests:");
+  outln!("┌─────────┬─────────┬─────────┐");
+  outln!("│   Total │ {total:>7} │         │");
+  outln!("├─────────┼─────────┼─────────┤");
+  outln!("│ {1}Success{0} │ {1}{success:>7}{0} │{1}{success_perc:>7.2}%{0} │", COLOR_RESET, COLOR_GREEN);
+  outln!(
+    "│ {1}Failure{0} │ {1}{failure:>7}{0} │{1}{failure_perc:>7.2}%{0} │",
+    COLOR_RESET,
+    if failure_count > 0 { COLOR_RED } else { COLOR_BRIGHT_WHITE }
+  );
+  outln!("└─────────┴─────────┴─────────┘");
+  if ctx.is_stopped() {
+    outln!(
+      "{1}Testing {2}, executed {3} of {4} test files{0}",
+      COLOR_RESET,
+      COLOR_YELLOW,
+      if interrupt::is_interrupted() {
+        "interrupted".to_string()
       } else {
-        result_node.name.clone()
+        format!("stopped after {} failure(s)", humanize::count(failure_count))
+      },
+      humanize::count(executed_file_count),
+      humanize::count(file_paths.len())
+    );
+  }
+  if ctx.warning_count > 0 {
+    outln!("{1}Tests passed with warnings: {2}{0}", COLOR_RESET, COLOR_YELLOW, humanize::count(ctx.warning_count));
+  }
+  if ctx.skipped_count > 0 {
+    outln!("{1}Skipped tests: {2}{0}", COLOR_RESET, COLOR_YELLOW, humanize::count(ctx.skipped_count));
+  }
+  if ctx.unchanged_file_count > 0 {
+    outln!(
+      "{1}Unchanged test files skipped: {2}{0}",
+      COLOR_RESET,
+      COLOR_YELLOW,
+      humanize::count(ctx.unchanged_file_count)
+    );
+  }
+  ctx.display_test_cases_report();
+  ctx.display_failure_classes_report();
+  ctx.display_flaky_tests_report();
+  ctx.display_labels_report();
+  if config.coverage_report {
+    ctx.coverage.display_report(|model_path| ctx.display_path(model_path));
+  }
+  if let Some(notify_url) = &config.notify_url {
+    let regressions = ctx.regressions();
+    let test_cases_success_perc = ctx.test_cases_success_perc();
+    let payload = NotificationPayload {
+      text: format!(
+        "DMN TCK run completed: {} tests, {} passed, {} failed, {:.2}% test cases passed, {} regression(s)",
+        total_count,
+        success_count,
+        failure_count,
+        test_cases_success_perc,
+        regressions.len()
+      ),
+      total: total_count,
+      success: success_count,
+      failure: failure_count,
+      test_cases_success_perc,
+      regressions,
+      report: &config.report_file,
+      metadata: &config.run_metadata,
+    };
+    // notification is sent by separate client, not to be included in connection metrics
+    notify::send(&Client::new(), notify_url, &payload);
+  }
+  ctx.flush();
+  if let Some(dashboard_file) = &config.dashboard_file {
+    ctx.write_dashboard(dashboard_file);
+  }
+  if let Some(badge_file) = &config.badge_file {
+    badge::write(badge_file, ctx.test_cases_success_perc());
+  }
+  let phase_times = &ctx.phase_times;
+  let average_request_time = phase_times.network.checked_div(ctx.request_count as u32).unwrap_or_default();
+  outln!("\nTimings:");
+  outln!("┌───────────────────────┬────────────┐");
+  outln!("│      Wall-clock total │ {:>10} │", humanize::duration(start_time.elapsed()));
+  outln!("├───────────────────────┼────────────┤");
+  outln!("│           XML parsing │ {:>10} │", humanize::duration(phase_times.parsing));
+  outln!("│    JSON serialization │ {:>10} │", humanize::duration(phase_times.serialization));
+  outln!("│    Network round-trip │ {:>10} │", humanize::duration(phase_times.network));
+  outln!("│  JSON deserialization │ {:>10} │", humanize::duration(phase_times.deserialization));
+  outln!("│            Comparison │ {:>10} │", humanize::duration(phase_times.comparison));
+  outln!("├───────────────────────┼────────────┤");
+  outln!("│  Average request time │ {:>10} │", humanize::duration(average_request_time));
+  outln!("│   Requests per second │ {:>10} │", humanize::count(requests_per_second.round() as usize));
+  outln!("└───────────────────────┴────────────┘");
+  ctx.display_invocable_types_report();
+  ctx.display_payload_sizes_report();
+  if let Some(resource_usage) = resource_usage {
+    resource_usage.display();
+  }
+  display_connections_report(ctx, connection_metrics);
+}
+
+/// Returns the source of engine resources to be sampled, when configured.
+fn resource_source(config: &ConfigurationParams) -> Option<ResourceSource> {
+  match (config.engine_pid, &config.engine_cgroup) {
+    (Some(pid), _) => Some(ResourceSource::Process(pid)),
+    (None, Some(cgroup)) => Some(ResourceSource::Cgroup(cgroup.clone())),
+    (None, None) => None,
+  }
+}
+
+/// Displays the number of opened and reused connections, and the time spent in connection setup
+/// compared to the time spent in sending requests and receiving responses.
+fn display_connections_report(ctx: &Context, metrics: &ConnectionMetrics) {
+  let opened_count = metrics.opened();
+  let reused_count = ctx.request_count.saturating_sub(opened_count + metrics.failed());
+  let setup_time = metrics.setup_time();
+  let exchange_time = Duration::from_nanos(ctx.execution_time as u64).saturating_sub(setup_time);
+  outln!("\nConnections:");
+  outln!("┌───────────────────────┬────────────┐");
+  outln!("│                Opened │ {:>10} │", humanize::count(opened_count));
+  outln!("│                Reused │ {:>10} │", humanize::count(reused_count));
+  if metrics.failed() > 0 {
+    outln!("│                {1}Failed{0} │ {1}{2:>10}{0} │", COLOR_RESET, COLOR_RED, humanize::count(metrics.failed()));
+  }
+  outln!("│      Connection setup │ {:>10} │", humanize::duration(setup_time));
+  outln!("│    Request / response │ {:>10} │", humanize::duration(exchange_time));
+  outln!("└───────────────────────┴────────────┘");
+}
+
+/// Replays evaluation requests of all selected tests at increasing concurrency levels, without checking the results.
+fn load(command: &Command, config: ConfigurationParams) {
+  let root_dirs = test_cases_dirs(&config);
+  let mut ctx = Context::without_reports(&config, root_dirs.clone());
+  let files = discover_files(&mut ctx, &root_dirs);
+  let requests = collect_requests(&ctx, files)
+    .into_iter()
+    .map(|invocation| invocation.params)
+    .collect::<Vec<EvaluateParams>>();
+  let max_workers = config.load_max_workers.unwrap_or(load::DEFAULT_MAX_WORKERS);
+  if *command == Command::Soak {
+    load::run_soak(
+      &connections::plain_client(&config),
+      &config.evaluate_url,
+      &requests,
+      config.soak_workers.unwrap_or(1),
+      Duration::from_secs(config.soak_duration.unwrap_or(load::DEFAULT_SOAK_DURATION)),
+      Duration::from_secs(config.soak_interval.unwrap_or(load::DEFAULT_SOAK_INTERVAL)),
+    );
+  } else if let Some(target_latency) = config.load_target_latency_ms {
+    let duration = Duration::from_secs(config.load_adaptive_duration.unwrap_or(load::DEFAULT_ADAPTIVE_DURATION));
+    load::run_adaptive(
+      &connections::plain_client(&config),
+      &config.evaluate_url,
+      &requests,
+      max_workers,
+      duration,
+      Duration::from_millis(target_latency),
+    );
+  } else {
+    let level_duration = Duration::from_secs(config.load_level_duration.unwrap_or(load::DEFAULT_LEVEL_DURATION));
+    load::run(&connections::plain_client(&config), &config.evaluate_url, &requests, max_workers, level_duration);
+  }
+}
+
+/// Measures the time from engine readiness to the first successful evaluation of every model.
+/// Every model is evaluated with the request of its first selected test.
+fn cold_start(config: ConfigurationParams) {
+  let Some(health_url) = &config.health_url else {
+    outln!("{1}health check URL not specified in configuration{0}", COLOR_RESET, COLOR_RED);
+    process::exit(1);
+  };
+  let root_dirs = test_cases_dirs(&config);
+  let mut ctx = Context::without_reports(&config, root_dirs.clone());
+  let files = discover_files(&mut ctx, &root_dirs);
+  let mut model_paths = HashSet::new();
+  let requests = collect_requests(&ctx, files)
+    .into_iter()
+    .filter(|invocation| model_paths.insert(invocation.model_path.clone()))
+    .map(|invocation| ModelRequest {
+      workspace: ctx.get_workspace_name(&invocation.model_path),
+      model_path: invocation.model_path,
+      params: invocation.params,
+    })
+    .collect::<Vec<ModelRequest>>();
+  coldstart::run(
+    &connections::plain_client(&config),
+    health_url,
+    &config.evaluate_url,
+    &requests,
+    Duration::from_secs(config.cold_start_timeout.unwrap_or(coldstart::DEFAULT_COLD_START_TIMEOUT)),
+    Duration::from_millis(config.cold_start_poll_interval_ms.unwrap_or(coldstart::DEFAULT_POLL_INTERVAL)),
+  );
+}
+
+/// Returns invocations of all selected tests from discovered test files.
+fn collect_requests(ctx: &Context, files: DiscoveredFiles) -> Vec<Invocation> {
+  let mut requests = vec![];
+  for (dir_name, (_, files_xml)) in files {
+    for file_xml in files_xml {
+      let file_path = format!("{}/{}", dir_name, file_xml);
+      let test_cases = match parse_test_file(&file_path) {
+        Ok(test_cases) => test_cases,
+        Err(reason) => {
+          outln!("{1}{2}{0}", COLOR_RESET, COLOR_YELLOW, reason);
+          continue;
+        }
       };
-      let test_case_details = format!("Executing test case, id: {test_id}, model name: {model_name}, invocable name: {invocable_name}");
-      let text = format!(
-        "Executing test case, {1}id{0}: {2}{test_id}{0}, {1}model name{0}: {2}{model_name}{0}, {1}invocable name{0}: {2}{invocable_name}{0}",
-        COLOR_RESET, COLOR_BRIGHT_WHITE, COLOR_BLUE
-      );
-      print!("{} {} ", text, &GAP[..GUTTER - test_case_details.len()]);
-      let invocable_path = format!(
-        "{}{}/{}/{}",
-        if workspace_name.is_empty() { "".to_string() } else { format!("{}/", workspace_name) },
-        model_namespace,
-        model_name,
-        invocable_name
-      );
-      let params = EvaluateParams {
-        invocable_path,
-        input_values: test_case.input_nodes.iter().map(InputNodeDto::from).collect(),
+      let Some(Ok(model_path)) = test_cases.model_name.as_ref().map(|model_name| ctx.resolve_model(&dir_name, model_name)) else {
+        outln!("{1}{2}: model not found, skipping test file{0}", COLOR_RESET, COLOR_YELLOW, ctx.display_path(&file_path));
+        continue;
       };
-      evaluate_test_case(ctx, file_path, client, evaluate_url, test_case_id, &test_id, &params, &result_node.expected);
+      for test_case in test_cases.test_cases.iter().filter(|test_case| ctx.is_selected(test_case.name.as_deref())) {
+        for result_node in &test_case.result_nodes {
+          requests.push(invocation(ctx, &model_path, test_case, result_node));
+        }
+      }
+    }
+  }
+  requests
+}
+
+/// DMN and test file names indexed by directory name.
+type DiscoveredFiles = BTreeMap<String, (Vec<String>, Vec<String>)>;
+
+/// Parsed test files with their modification time, indexed by file path.
+type ParsedFiles = HashMap<String, (SystemTime, Arc<TestCases>)>;
+
+/// Runs tests repeatedly on commands read from standard input, keeping discovered files,
+/// model definitions and parsed test files in memory between runs.
+/// Test files modified since they were parsed are parsed again.
+fn daemon(config: ConfigurationParams) {
+  let root_dirs = test_cases_dirs(&config);
+  let connection_metrics = Arc::new(ConnectionMetrics::default());
+  let client = connections::client(&config, &connection_metrics);
+  let mut ctx = Context::without_reports(&config, root_dirs.clone());
+  if config.events_format == EventsFormat::Jsonl {
+    ctx.events = Some(EventStream::open());
+  }
+  let mut files = discover_files(&mut ctx, &root_dirs);
+  let mut parsed_files = ParsedFiles::new();
+  outln!("{}", DAEMON_COMMANDS);
+  for line in io::stdin().lines() {
+    let line = line.unwrap_or_else(|e| panic!("reading command failed with reason: {}", e));
+    let (command, argument) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+    match command {
+      "run" => {
+        ctx = ctx.restart(&config, open_history(&config, &root_dirs));
+        if !argument.trim().is_empty() {
+          match Regex::new(argument.trim()) {
+            Ok(pattern) => ctx.test_case_name_pattern = Some(pattern),
+            Err(reason) => {
+              outln!("{1}invalid test case name pattern: {2}{0}", COLOR_RESET, COLOR_RED, reason);
+              continue;
+            }
+          }
+        }
+        connection_metrics.reset();
+        execute_all(&config, &mut ctx, &files, &client, &connection_metrics, Some(&mut parsed_files));
+      }
+      "rescan" => {
+        let events = ctx.events.take();
+        ctx = Context::without_reports(&config, root_dirs.clone());
+        ctx.events = events;
+        files = discover_files(&mut ctx, &root_dirs);
+        parsed_files.clear();
+      }
+      "quit" | "exit" => break,
+      "" => {}
+      other => outln!("{1}unknown command '{2}'{0}\n{3}", COLOR_RESET, COLOR_RED, other, DAEMON_COMMANDS),
+    }
+  }
+}
+
+/// Commands accepted in daemon mode.
+const DAEMON_COMMANDS: &str = "Commands: run [test case name pattern], rescan, quit";
+
+/// Parses all test files concurrently before executing tests, so the execution does not wait for parsing.
+/// Test files parsed lazily and unchanged test files are skipped, cached test cases are reused when the file was not modified since it was parsed.
+/// Returns the results of parsing indexed by file path.
+fn parse_all(ctx: &mut Context, file_paths: &[String], mut parsed_files: Option<&mut ParsedFiles>) -> HashMap<String, model::Result<Arc<TestCases>>> {
+  let parsing_start_time = Instant::now();
+  let mut parsed = HashMap::new();
+  let mut modified_files = vec![];
+  // test files skipped as unchanged since the cached run are not parsed
+  let is_unchanged = |file_path: &str| ctx.changed_only && ctx.cache.as_ref().is_some_and(|cache| cache.unchanged(file_path).is_some());
+  for file_path in file_paths.iter().filter(|file_path| !is_streamed(ctx, file_path) && !is_unchanged(file_path)) {
+    let modified = parsed_files
+      .is_some()
+      .then(|| fs::metadata(file_path).and_then(|metadata| metadata.modified()).ok())
+      .flatten();
+    match parsed_files.as_deref().and_then(|parsed_files| parsed_files.get(file_path)) {
+      Some((parsed_modified, test_cases)) if Some(*parsed_modified) == modified => {
+        parsed.insert(file_path.clone(), Ok(Arc::clone(test_cases)));
+      }
+      _ => modified_files.push((file_path, modified)),
+    }
+  }
+  let results = modified_files
+    .par_iter()
+    .map(|(file_path, _)| parse_test_file(file_path).map(Arc::new))
+    .collect::<Vec<model::Result<Arc<TestCases>>>>();
+  for ((file_path, modified), result) in modified_files.into_iter().zip(results) {
+    if let (Some(parsed_files), Some(modified), Ok(test_cases)) = (parsed_files.as_deref_mut(), modified, &result) {
+      parsed_files.insert(file_path.clone(), (modified, Arc::clone(test_cases)));
+    }
+    parsed.insert(file_path.clone(), result);
+  }
+  ctx.phase_times.parsing += parsing_start_time.elapsed();
+  parsed
+}
+
+/// Returns `true` when the test file is larger than streaming threshold,
+/// such test files are parsed lazily and test cases are executed while parsing.
+fn is_streamed(ctx: &Context, file_path: &str) -> bool {
+  ctx
+    .streaming_threshold
+    .is_some_and(|threshold| fs::metadata(file_path).is_ok_and(|metadata| metadata.len() > threshold))
+}
+
+/// Searches test files and DMN files in all test directories, retrieves model names and namespaces from all DMN files.
+/// Returns DMN and test file names indexed by directory name.
+fn discover_files(ctx: &mut Context, root_dirs: &[String]) -> DiscoveredFiles {
+  let mut files = DiscoveredFiles::new();
+  for root_dir in root_dirs {
+    out!("Searching DMN files in directory: {} ... ", root_dir);
+    let root_files = find_files(Path::new(root_dir), &ctx.file_search_pattern, &ctx.file_selection);
+    outln!("ok");
+    // retrieve model names and namespaces from all DMN files, before executing tests
+    for (dir_name, (files_dmn, _)) in &root_files {
+      for file_dmn in files_dmn {
+        if let Err(reason) = ctx.process_model_definitions(Path::new(root_dir), dir_name, file_dmn) {
+          outln!("{1}{2}{0}", COLOR_RESET, COLOR_YELLOW, reason);
+        }
+      }
+    }
+    files.extend(root_files);
+  }
+  ctx.display_import_warnings();
+  files
+}
+
+/// Searches model and test files matching the search pattern and selected by include and exclude patterns in the root directory.
+fn find_files(root_dir: &Path, file_search_pattern: &str, selection: &FileSelection) -> DiscoveredFiles {
+  let mut files = BTreeMap::new();
+  let pattern = Regex::new(file_search_pattern).expect("parsing search pattern failed");
+  search_files(root_dir, root_dir, &pattern, selection, &mut files);
+  // directory entries are listed in unspecified order, files are sorted to get stable execution order
+  for (files_dmn, files_xml) in files.values_mut() {
+    files_dmn.sort();
+    files_xml.sort();
+  }
+  files
+}
+
+/// Executes tests from the test file, parsed in advance unless the file is parsed lazily.
+fn execute_tests(
+  ctx: &mut Context,
+  file_path: &str,
+  client: &Client,
+  evaluate_url: &str,
+  parsed: Option<model::Result<Arc<TestCases>>>,
+  mut worker: Option<&mut ComparisonWorker>,
+) {
+  let displayed_path = ctx.display_path(file_path);
+  let text = format!("  Parsing test file: {}", displayed_path);
+  out!("\n{} {} ", text, gap(text.len()));
+  if let Some(tui) = &ctx.tui {
+    tui.set_file(&displayed_path);
+  }
+  if ctx.changed_only {
+    if let Some(cached_file) = ctx.cache.as_ref().and_then(|cache| cache.unchanged(file_path)) {
+      outln!(
+        "{1}unchanged{0}, last run: {2}{3} passed{0}, {4}{5} failed{0}",
+        COLOR_RESET,
+        COLOR_YELLOW,
+        COLOR_GREEN,
+        cached_file.success,
+        COLOR_RED,
+        cached_file.failure
+      );
+      ctx.unchanged_file_count += 1;
+      return;
+    }
+  }
+  // large test files are parsed lazily, test cases are executed while parsing
+  let parsing_start_time = Instant::now();
+  let parsed = match parsed {
+    Some(parsed) => parsed.map(|test_cases| (test_cases, None)),
+    None => stream_test_file(file_path).map(|(header, stream)| (Arc::new(header), Some(stream))),
+  };
+  ctx.phase_times.parsing += parsing_start_time.elapsed();
+  let (test_cases, stream) = match parsed {
+    Ok(parsed) => parsed,
+    Err(reason) => {
+      report_invalid_test_file(ctx, file_path, &reason.to_string());
+      return;
+    }
+  };
+  let Some(model_file_name) = test_cases.model_name.clone() else {
+    report_invalid_test_file(ctx, file_path, &format!("{}: model name not specified in test file", displayed_path));
+    return;
+  };
+  let duplicates = test_cases.duplicates();
+  if !duplicates.is_empty() && ctx.strict {
+    report_invalid_test_file(ctx, file_path, &format!("{}: {}", displayed_path, duplicates.join(", ")));
+    return;
+  }
+  let model_path = ctx.resolve_model(&dir_name(file_path), &model_file_name);
+  if let Err(ModelError::Invalid(reason)) = &model_path {
+    report_invalid_test_file(ctx, file_path, &format!("{}: {}", displayed_path, reason));
+    return;
+  }
+  // the hash covers the test file and all models it depends on
+  let dependencies = model_path
+    .as_ref()
+    .map(|model_path| ctx.imports.dependencies(model_path).into_iter().collect::<Vec<String>>())
+    .unwrap_or_default();
+  let hash = ctx.cache.as_ref().map(|_| cache::hash_files(file_path, &dependencies));
+  let (success_count, failure_count) = (ctx.success_count, ctx.failure_count);
+  outln!("{1}ok{0}\n", COLOR_RESET, COLOR_GREEN);
+  ctx.emit(&Event::FileParsed {
+    file: &displayed_path,
+    test_cases: stream.is_none().then_some(test_cases.test_cases.len()),
+    error: None,
+  });
+  for duplicate in &duplicates {
+    outln!("{1}{2}{0}\n", COLOR_RESET, COLOR_YELLOW, duplicate);
+  }
+  if test_cases.spec_version.is_none() {
+    outln!(
+      "{1}unknown test cases namespace: {2}{0}\n",
+      COLOR_RESET,
+      COLOR_YELLOW,
+      test_cases.namespace.as_deref().unwrap_or("(none)")
+    );
+  }
+  if let Err(reason) = &model_path {
+    outln!("{1}{2}{0}\n", COLOR_RESET, COLOR_YELLOW, reason);
+  }
+  // duplicates of streamed test cases are detected while parsing
+  let mut streamed_duplicates = stream.is_some().then(Duplicates::default);
+  // test cases are paired with their index in the document, the execution order may be shuffled,
+  // parsed test cases are shared between runs and copied only when modified
+  let mut items: Box<dyn Iterator<Item = (usize, model::Result<Cow<TestCase>>)>> = match stream {
+    Some(stream) => Box::new(stream.enumerate().map(|(index, item)| (index, item.map(Cow::Owned)))),
+    None => {
+      let mut items = test_cases.test_cases.iter().enumerate().collect::<Vec<(usize, &TestCase)>>();
+      if let Some(shuffle) = &mut ctx.shuffle {
+        shuffle.shuffle(&mut items);
+      }
+      Box::new(items.into_iter().map(|(index, test_case)| (index, Ok(Cow::Borrowed(test_case)))))
+    }
+  };
+  let mut computed_values = vec![];
+  // document indexes of test cases with the number of their result nodes, in execution order
+  let mut execution_order = vec![];
+  let mut batch = vec![];
+  loop {
+    if ctx.is_stopped() {
+      // tests already sent are completed, but the test file is not cached nor updated with computed values
+      complete_pending(ctx, client, evaluate_url, &mut batch, worker, &mut computed_values);
+      return;
+    }
+    // streamed test cases are parsed while iterating
+    let parsing_start_time = Instant::now();
+    let Some((index, item)) = items.next() else {
+      break;
+    };
+    ctx.phase_times.parsing += parsing_start_time.elapsed();
+    let mut test_case = match item {
+      Ok(test_case) => test_case,
+      Err(reason) => {
+        // the file was already reported as parsed, tests executed before the error keep their results
+        complete_pending(ctx, client, evaluate_url, &mut batch, worker.as_deref_mut(), &mut computed_values);
+        write_invalid_test_file(ctx, file_path, &reason.to_string());
+        return;
+      }
+    };
+    if let Some(detector) = &mut streamed_duplicates {
+      let duplicates = detector.check(&test_case);
+      if !duplicates.is_empty() && ctx.strict {
+        complete_pending(ctx, client, evaluate_url, &mut batch, worker.as_deref_mut(), &mut computed_values);
+        write_invalid_test_file(ctx, file_path, &format!("{}: {}", displayed_path, duplicates.join(", ")));
+        return;
+      }
+      for duplicate in &duplicates {
+        outln!("{1}{2}{0}\n", COLOR_RESET, COLOR_YELLOW, duplicate);
+      }
+    }
+    execution_order.push((index, test_case.result_nodes.len()));
+    if !ctx.is_selected(test_case.name.as_deref()) {
+      computed_values.extend(test_case.result_nodes.iter().map(|_| None));
+      continue;
+    }
+    match &model_path {
+      Ok(model_path) => {
+        if ctx.infer_types {
+          infer_types(ctx, model_path, test_case.to_mut());
+        }
+        execute_test_case(
+          ctx,
+          client,
+          evaluate_url,
+          file_path,
+          &test_cases,
+          model_path,
+          index,
+          &test_case,
+          &mut computed_values,
+          &mut batch,
+          worker.as_deref_mut(),
+        );
+      }
+      Err(reason) => skip_test_case(ctx, file_path, &test_cases, &test_case, &model_file_name, &reason.to_string()),
+    }
+  }
+  complete_pending(ctx, client, evaluate_url, &mut batch, worker, &mut computed_values);
+  // results of test files executed only partially are not cached
+  let all_executed = ctx.test_case_name_pattern.is_none() && ctx.previous_failures.is_none();
+  if let (Some(cache), Some(hash), true) = (&mut ctx.cache, hash, all_executed) {
+    cache.update(file_path, hash, dependencies, ctx.success_count - success_count, ctx.failure_count - failure_count);
+  }
+  if ctx.write_computed && model_path.is_ok() {
+    if let Err(reason) = computed::write(file_path, &document_order(computed_values, &execution_order)) {
+      outln!("{1}writing computed values to {2} failed: {3}{0}", COLOR_RESET, COLOR_YELLOW, displayed_path, reason);
     }
   }
 }
 
+/// Rearranges computed values collected in execution order into the document order of result nodes,
+/// test cases are given as document indexes with the number of their result nodes, in execution order.
+fn document_order(computed_values: Vec<Option<ValueDto>>, execution_order: &[(usize, usize)]) -> Vec<Option<ValueDto>> {
+  let mut values = computed_values.into_iter();
+  let mut test_cases = execution_order
+    .iter()
+    .map(|(index, count)| (*index, values.by_ref().take(*count).collect::<Vec<Option<ValueDto>>>()))
+    .collect::<Vec<(usize, Vec<Option<ValueDto>>)>>();
+  test_cases.sort_by_key(|(index, _)| *index);
+  test_cases.into_iter().flat_map(|(_, values)| values).collect()
+}
+
+/// Executes all tests defined by result nodes of the test case.
 #[allow(clippy::too_many_arguments)]
-fn evaluate_test_case(
+fn execute_test_case(
   ctx: &mut Context,
+  client: &Client,
+  evaluate_url: &str,
   file_path: &str,
+  test_cases: &TestCases,
+  model_path: &str,
+  test_case_index: usize,
+  test_case: &TestCase,
+  computed_values: &mut Vec<Option<ValueDto>>,
+  batch: &mut Vec<PendingTest>,
+  mut worker: Option<&mut ComparisonWorker>,
+) {
+  let test_case_id = test_case.id.as_deref().unwrap_or_default();
+  for (i, result_node) in test_case.result_nodes.iter().enumerate() {
+    let test_id = test_id(test_case_id, i);
+    if !ctx.has_previously_failed(file_path, &test_id) {
+      computed_values.push(None);
+      continue;
+    }
+    let Invocation {
+      model_path: invocable_model_path,
+      model_name,
+      invocable_name,
+      params,
+    } = invocation(ctx, model_path, test_case, result_node);
+    let test_case_details = format!("Executing test case, id: {test_id}, model name: {model_name}, invocable name: {invocable_name}");
+    let text = format!(
+      "Executing test case, {1}id{0}: {2}{test_id}{0}, {1}model name{0}: {2}{model_name}{0}, {1}invocable name{0}: {2}{invocable_name}{0}",
+      COLOR_RESET, COLOR_BRIGHT_WHITE, COLOR_BLUE
+    );
+    let header = format!("{} {} ", text, gap(test_case_details.len()));
+    ctx.coverage.mark_exercised(&invocable_model_path, &invocable_name);
+    let mut test = ctx.test_info(file_path, test_case_id, &test_id, &test_cases.labels);
+    test.test_case_name = test_case.name.clone().unwrap_or_default();
+    test.description = test_case.description.clone().unwrap_or_default();
+    test.metadata = metadata(test_case.extension_elements.iter().chain(&result_node.extension_elements));
+    test.spec_version = test_cases.spec_version.clone().unwrap_or_default();
+    test.invocable_type = test_case.typ.to_string();
+    test.test_case_index = test_case_index;
+    let error_message = result_node
+      .extension_elements
+      .iter()
+      .find(|element| element.name == EXPECTED_ERROR)
+      .and_then(|element| element.text.clone());
+    let error_pattern = error_message
+      .as_deref()
+      .filter(|_| ctx.error_message_matching == ErrorMessageMatching::Regex)
+      .map(|message| ctx.error_pattern(message));
+    let expectation = Expectation {
+      value: if ctx.computed_fallback && result_node.expected.is_none() {
+        result_node.computed.clone()
+      } else {
+        result_node.expected.clone()
+      },
+      patterns: ExpectedPattern::from_extension_elements(&result_node.extension_elements),
+      error_result: result_node.error_result,
+      error_message,
+      error_pattern,
+    };
+    let pending_test = PendingTest {
+      header,
+      test,
+      params,
+      expectation,
+      computed_index: computed_values.len(),
+    };
+    computed_values.push(None);
+    if ctx.batch_url.is_some() && ctx.test_timeout(&pending_test.test).is_none() {
+      // tests with time budget are not batched, to be cancelled independently
+      // the result is reported and the computed value is set when the whole batch is evaluated
+      batch.push(pending_test);
+      if batch.len() >= ctx.batch_size {
+        evaluate_batch(ctx, client, batch, computed_values);
+      }
+    } else {
+      evaluate_test_case(ctx, client, evaluate_url, pending_test, computed_values, worker.as_deref_mut());
+    }
+  }
+}
+
+/// Evaluates tests collected in a batch and reports tests waiting for outcomes from comparison worker.
+fn complete_pending(
+  ctx: &mut Context,
   client: &Client,
   evaluate_url: &str,
-  test_case_id: &str,
-  test_id: &str,
-  params: &EvaluateParams,
-  opt_expected: &Option<Value>,
+  batch: &mut Vec<PendingTest>,
+  worker: Option<&mut ComparisonWorker>,
+  computed_values: &mut [Option<ValueDto>],
+) {
+  evaluate_batch(ctx, client, batch, computed_values);
+  if let Some(worker) = worker {
+    worker.report(ctx, evaluate_url, computed_values, true);
+  }
+}
+
+/// Test collected for evaluation in a batch or waiting for outcomes from comparison worker.
+struct PendingTest {
+  /// Header of the test displayed when the result is reported.
+  header: String,
+  /// Identification of the test.
+  test: TestInfo,
+  /// Parameters of the evaluation request.
+  params: EvaluateParams,
+  /// Expected result of the test.
+  expectation: Expectation,
+  /// Index of the computed value of the test in the computed values of the test file.
+  computed_index: usize,
+}
+
+/// Evaluates all collected tests in batches sent to batch endpoint, reports their results and sets their computed values.
+fn evaluate_batch(ctx: &mut Context, client: &Client, batch: &mut Vec<PendingTest>, computed_values: &mut [Option<ValueDto>]) {
+  let Some(batch_url) = ctx.batch_url.clone() else {
+    return;
+  };
+  if batch.is_empty() {
+    return;
+  }
+  let pending_tests = mem::take(batch);
+  let mut executions = pending_tests.iter().map(|_| vec![]).collect::<Vec<Vec<Execution>>>();
+  for _ in 0..ctx.repeat {
+    for (test_executions, execution) in executions.iter_mut().zip(execute_batch(ctx, client, &batch_url, &pending_tests)) {
+      test_executions.push(execution);
+    }
+  }
+  for (pending_test, executions) in pending_tests.into_iter().zip(executions) {
+    out!("{}", pending_test.header);
+    computed_values[pending_test.computed_index] = report_executions(ctx, &batch_url, &pending_test.test, &pending_test.params, &pending_test.expectation, executions);
+  }
+}
+
+/// Sends all evaluation requests of the batch in a single request and checks the responses.
+/// The duration and payload sizes of the batch request are divided equally among all tests in the batch.
+fn execute_batch(ctx: &mut Context, client: &Client, batch_url: &str, pending_tests: &[PendingTest]) -> Vec<Execution> {
+  let requests = pending_tests.iter().map(|pending_test| &pending_test.params).collect::<Vec<&EvaluateParams>>();
+  // all tests in the batch are defined in the same test file
+  let directory = pending_tests.first().map(|pending_test| pending_test.test.directory.clone()).unwrap_or_default();
+  let Exchange {
+    duration,
+    status,
+    request_size,
+    response,
+  } = post_json(ctx, client, batch_url, &directory, &requests, None);
+  let test_duration = duration / pending_tests.len() as u32;
+  let test_request_size = request_size / pending_tests.len();
+  let test_response_size = response.as_ref().map_or(0, String::len) / pending_tests.len();
+  for pending_test in pending_tests {
+    ctx.write_timing(&pending_test.test, test_duration, status, test_request_size, test_response_size);
+  }
+  let deserialization_start_time = Instant::now();
+  let results = response.map(|body| serde_json::from_str::<Vec<serde_json::Value>>(&body));
+  ctx.phase_times.deserialization += deserialization_start_time.elapsed();
+  let bodies = match results {
+    Ok(results) => match results {
+      Ok(results) if results.len() == pending_tests.len() => Ok(results.iter().map(|result| result.to_string()).collect::<Vec<String>>()),
+      Ok(results) => Err((
+        FailureCategory::InvalidResponse,
+        format!("batch response contains {} results, expected {}", results.len(), pending_tests.len()),
+      )),
+      Err(reason) => Err((FailureCategory::InvalidResponse, format!("invalid batch response: {}", reason))),
+    },
+    Err(reason) => Err((FailureCategory::TransportError, reason.to_string())),
+  };
+  match bodies {
+    Ok(bodies) => pending_tests
+      .iter()
+      .zip(bodies)
+      .map(|(pending_test, body)| Execution {
+        duration: test_duration,
+        outcome: check_response(ctx, &pending_test.test, &body, &pending_test.expectation),
+        response_body: Some(body),
+        deferred: false,
+      })
+      .collect(),
+    Err((category, reason)) => pending_tests
+      .iter()
+      .map(|_| Execution {
+        duration: test_duration,
+        response_body: None,
+        outcome: Outcome::failure(category, &reason),
+        deferred: false,
+      })
+      .collect(),
+  }
+}
+
+/// Invocable evaluated by the test, with parameters of the evaluation request.
+struct Invocation {
+  /// Path to the model defining the invocable.
+  model_path: String,
+  /// Name of the model defining the invocable.
+  model_name: String,
+  /// Name of the invocable.
+  invocable_name: String,
+  /// Parameters of the evaluation request.
+  params: EvaluateParams,
+}
+
+/// Returns the invocable evaluated for the result node of the test case.
+fn invocation(ctx: &Context, model_path: &str, test_case: &TestCase, result_node: &ResultNode) -> Invocation {
+  let invocable_name = test_case.invocable_name.as_ref().unwrap_or(&result_node.name).to_string();
+  // results qualified with namespace are defined in the model with that namespace
+  let (invocable_model_path, invocable_name) = match result_node.namespace.as_ref().and_then(|namespace| ctx.imports.model_with_namespace(model_path, namespace)) {
+    Some(namespace_model_path) => (namespace_model_path, invocable_name),
+    None => ctx.imports.resolve_invocable(model_path, &invocable_name),
+  };
+  let workspace_name = ctx.get_workspace_name(&invocable_model_path);
+  let model_namespace = ctx.get_model_rdnn(&invocable_model_path);
+  let model_name = ctx.get_model_name(&invocable_model_path);
+  let invocable_path = format!(
+    "{}{}/{}/{}",
+    if workspace_name.is_empty() { "".to_string() } else { format!("{}/", workspace_name) },
+    model_namespace,
+    model_name,
+    invocable_name
+  );
+  Invocation {
+    model_path: invocable_model_path,
+    model_name,
+    invocable_name,
+    params: EvaluateParams {
+      invocable_path,
+      input_values: test_case.input_nodes.iter().map(InputNodeDto::from).collect(),
+    },
+  }
+}
+
+/// Sets types of untyped input and expected values, inferred from variable types defined in DMN models.
+fn infer_types(ctx: &Context, model_path: &str, test_case: &mut TestCase) {
+  let namespace_model_path = |namespace: &Option<String>| namespace.as_ref().and_then(|namespace| ctx.imports.model_with_namespace(model_path, namespace));
+  for input_node in &mut test_case.input_nodes {
+    let input_model_path = namespace_model_path(&input_node.namespace).unwrap_or(model_path.to_string());
+    if let Some((value, inferred_type)) = input_node.value.as_mut().zip(ctx.variable_type(&input_model_path, &input_node.name)) {
+      types::infer(value, inferred_type);
+    }
+  }
+  for result_node in &mut test_case.result_nodes {
+    let result_model_path = namespace_model_path(&result_node.namespace).unwrap_or_else(|| ctx.imports.resolve_invocable(model_path, &result_node.name).0);
+    if let Some(inferred_type) = ctx.variable_type(&result_model_path, &result_node.name) {
+      for value in [&mut result_node.expected, &mut result_node.computed].into_iter().flatten() {
+        types::infer(value, inferred_type);
+      }
+    }
+  }
+}
+
+/// Returns the identifier of the test, built from test case identifier and result node index.
+fn test_id(test_case_id: &str, index: usize) -> String {
+  if index > 0 {
+    format!("{}:{}", test_case_id, index)
+  } else {
+    test_case_id.to_string()
+  }
+}
+
+/// Reports all tests defined by result nodes of the test case as skipped, with the reason why they could not be executed.
+fn skip_test_case(ctx: &mut Context, file_path: &str, test_cases: &TestCases, test_case: &TestCase, model_file_name: &str, reason: &str) {
+  let test_case_id = test_case.id.as_deref().unwrap_or_default();
+  for (i, result_node) in test_case.result_nodes.iter().enumerate() {
+    let test_id = test_id(test_case_id, i);
+    let invocable_name = test_case.invocable_name.as_ref().unwrap_or(&result_node.name);
+    let test_case_details = format!("Skipping test case, id: {test_id}, model file: {model_file_name}, invocable name: {invocable_name}");
+    let text = format!(
+      "Skipping test case, {1}id{0}: {2}{test_id}{0}, {1}model file{0}: {2}{model_file_name}{0}, {1}invocable name{0}: {2}{invocable_name}{0}",
+      COLOR_RESET, COLOR_BRIGHT_WHITE, COLOR_BLUE
+    );
+    out!("{} {} ", text, gap(test_case_details.len()));
+    let mut test = ctx.test_info(file_path, test_case_id, &test_id, &test_cases.labels);
+    test.test_case_name = test_case.name.clone().unwrap_or_default();
+    test.description = test_case.description.clone().unwrap_or_default();
+    test.spec_version = test_cases.spec_version.clone().unwrap_or_default();
+    ctx.write_line(&test, Duration::ZERO, TestResult::Skipped, reason);
+  }
+}
+
+/// Reports the test file that could not be parsed as a failure.
+fn report_invalid_test_file(ctx: &mut Context, file_path: &str, remarks: &str) {
+  ctx.emit(&Event::FileParsed {
+    file: &ctx.display_path(file_path),
+    test_cases: None,
+    error: Some(remarks),
+  });
+  write_invalid_test_file(ctx, file_path, remarks);
+}
+
+/// Writes the failure of the test file found invalid while its test cases were executed,
+/// the test file is counted as a single failed test in addition to already executed tests.
+fn write_invalid_test_file(ctx: &mut Context, file_path: &str, remarks: &str) {
+  let test = ctx.test_info(file_path, "", "", &[]);
+  ctx.write_line(&test, Duration::ZERO, TestResult::Failure(FailureCategory::InvalidTestFile), remarks);
+}
+
+/// Name of the extension element containing the error message expected for the result node with `errorResult="true"`.
+const EXPECTED_ERROR: &str = "expectedError";
+
+/// Expected result of the test.
+#[derive(Clone)]
+struct Expectation {
+  /// Expected value.
+  value: Option<Value>,
+  /// Expected simple values marked as patterns.
+  patterns: Vec<ExpectedPattern>,
+  /// Flag indicating if engine is expected to report an error.
+  error_result: bool,
+  /// Optional substring or regex the error reported by engine must match.
+  error_message: Option<String>,
+  /// Compiled regex of expected error message, when error messages are matched with regular expressions.
+  error_pattern: Option<Result<Regex, String>>,
+}
+
+/// Outcome of a single test evaluation.
+struct Outcome {
+  /// Result of the test.
+  result: TestResult,
+  /// Remarks reported for failed tests, or warnings reported for tests passed thanks to lenient comparison.
+  remarks: String,
+  /// Rendered differences between actual and expected value, when the values differ.
+  mismatch: Option<RenderedMismatch>,
+  /// Actual value returned by engine.
+  actual: Option<ValueDto>,
+}
+
+impl Outcome {
+  /// Creates a successful outcome.
+  fn success() -> Self {
+    Self {
+      result: TestResult::Success,
+      remarks: String::new(),
+      mismatch: None,
+      actual: None,
+    }
+  }
+
+  /// Creates an outcome of the smoke test, executed without errors.
+  fn executed() -> Self {
+    Self {
+      result: TestResult::Executed,
+      ..Self::success()
+    }
+  }
+
+  /// Creates an outcome of the test passed only thanks to lenient comparison, with warnings as remarks.
+  fn warning(remarks: &str) -> Self {
+    Self {
+      result: TestResult::Warning,
+      remarks: remarks.to_string(),
+      ..Self::success()
+    }
+  }
+
+  /// Creates a failed outcome.
+  fn failure(category: FailureCategory, remarks: &str) -> Self {
+    Self {
+      result: TestResult::Failure(category),
+      remarks: remarks.to_string(),
+      mismatch: None,
+      actual: None,
+    }
+  }
+}
+
+/// Single execution of the test.
+struct Execution {
+  /// Duration of the request.
+  duration: Duration,
+  /// Raw response body, if received.
+  response_body: Option<String>,
+  /// Outcome of the execution.
+  outcome: Outcome,
+  /// Flag indicating if the outcome is checked by comparison worker and was not received yet.
+  deferred: bool,
+}
+
+/// Evaluates the test, reports its result and returns the actual value returned by engine.
+/// When comparison worker is given, the test is reported after outcomes of all executions are received.
+fn evaluate_test_case(
+  ctx: &mut Context,
+  client: &Client,
+  evaluate_url: &str,
+  pending_test: PendingTest,
+  computed_values: &mut [Option<ValueDto>],
+  worker: Option<&mut ComparisonWorker>,
 ) {
+  if worker.is_none() {
+    out!("{}", pending_test.header);
+  }
+  let PendingTest { test, params, expectation, .. } = &pending_test;
+  let timeout = ctx.test_timeout(test);
+  let start_time = Instant::now();
+  let mut executions = vec![];
+  for _ in 0..ctx.repeat {
+    // the time budget is shared by all repeated executions of the test
+    let remaining = timeout.map(|timeout| timeout.saturating_sub(start_time.elapsed()));
+    if remaining.is_some_and(|remaining| remaining.is_zero()) {
+      break;
+    }
+    let execution = execute(ctx, client, evaluate_url, test, params, expectation, remaining, worker.as_deref());
+    let timed_out = matches!(execution.outcome.result, TestResult::Failure(FailureCategory::Timeout));
+    executions.push(execution);
+    if timed_out {
+      break;
+    }
+  }
+  match worker {
+    Some(worker) => {
+      worker.pending.push_back((pending_test, executions));
+      worker.report(ctx, evaluate_url, computed_values, false);
+    }
+    None => computed_values[pending_test.computed_index] = report_executions(ctx, evaluate_url, test, params, expectation, executions),
+  }
+}
+
+/// Reports the result of all executions of the test and returns the actual value returned by engine.
+fn report_executions(
+  ctx: &mut Context,
+  evaluate_url: &str,
+  test: &TestInfo,
+  params: &EvaluateParams,
+  expectation: &Expectation,
+  mut executions: Vec<Execution>,
+) -> Option<ValueDto> {
+  if executions.is_empty() {
+    // the time budget has elapsed before the first execution
+    executions.push(Execution {
+      duration: Duration::ZERO,
+      response_body: None,
+      outcome: Outcome::failure(FailureCategory::Timeout, "time budget elapsed before the test was executed"),
+      deferred: false,
+    });
+  }
+  let repeat = executions.len();
+  let passed = executions.iter().filter(|execution| execution.outcome.result.is_success()).count();
+  let execution_duration = executions.iter().map(|execution| execution.duration).sum::<Duration>() / repeat as u32;
+  // the first failed execution is reported, if any
+  let index = executions
+    .iter()
+    .position(|execution| matches!(execution.outcome.result, TestResult::Failure(_)))
+    .unwrap_or_default();
+  let Execution { response_body, mut outcome, .. } = executions.swap_remove(index);
+  if passed > 0 && passed < repeat {
+    ctx.add_flaky_test(test, passed, repeat);
+    outcome = Outcome {
+      mismatch: outcome.mismatch,
+      actual: outcome.actual,
+      ..Outcome::failure(FailureCategory::Flaky, &format!("inconsistent results, passed {} of {} executions", passed, repeat))
+    };
+  }
+  let failed = matches!(outcome.result, TestResult::Failure(_));
+  ctx.write_line(test, execution_duration, outcome.result, &outcome.remarks);
+  let curl = ctx.curl.as_ref().filter(|_| failed).map(|curl| curl.command(params));
+  if let Some(curl) = &curl {
+    outln!("{1}reproduce:{0} {2}", COLOR_RESET, COLOR_BRIGHT_WHITE, curl);
+  }
+  if failed {
+    let artifact = FailureArtifactDto {
+      evaluate_url,
+      invocable_path: &params.invocable_path,
+      request: params,
+      response: response_body.as_deref(),
+      expected: expectation.value.as_ref().map(ValueDto::from),
+      remarks: &outcome.remarks,
+      metadata: &test.metadata,
+      curl: curl.as_deref(),
+    };
+    ctx.write_failure_artifact(test, &artifact);
+    ctx.write_failing_params(test, params);
+  }
+  if let Some(mismatch) = &outcome.mismatch {
+    ctx.write_diff(test, &mismatch.expected_json, &mismatch.actual_json);
+    out!("{}", mismatch.display);
+  }
+  outcome.actual
+}
+
+/// Sends a single evaluation request to engine and checks the response.
+/// The request is cancelled when the optional timeout elapses.
+/// Large responses are checked by comparison worker, when given.
+#[allow(clippy::too_many_arguments)]
+fn execute(
+  ctx: &mut Context,
+  client: &Client,
+  evaluate_url: &str,
+  test: &TestInfo,
+  params: &EvaluateParams,
+  expectation: &Expectation,
+  timeout: Option<Duration>,
+  worker: Option<&ComparisonWorker>,
+) -> Execution {
+  let Exchange {
+    duration,
+    status,
+    request_size,
+    response,
+  } = post_json(ctx, client, evaluate_url, &test.directory, params, timeout);
+  ctx.write_timing(test, duration, status, request_size, response.as_ref().map_or(0, String::len));
+  match response {
+    Ok(body) if worker.is_some_and(|worker| body.len() > worker.threshold) => {
+      if let Some(worker) = worker {
+        worker.submit(test, &body, expectation);
+      }
+      Execution {
+        duration,
+        response_body: Some(body),
+        outcome: Outcome::success(),
+        deferred: true,
+      }
+    }
+    Ok(body) => {
+      let outcome = check_response(ctx, test, &body, expectation);
+      Execution {
+        duration,
+        response_body: Some(body),
+        outcome,
+        deferred: false,
+      }
+    }
+    Err(reason) if reason.is_timeout() => Execution {
+      duration,
+      response_body: None,
+      outcome: Outcome::failure(
+        FailureCategory::Timeout,
+        &format!("cancelled after exceeding time budget of {} ms", ctx.test_timeout(test).unwrap_or_default().as_millis()),
+      ),
+      deferred: false,
+    },
+    Err(reason) => Execution {
+      duration,
+      response_body: None,
+      outcome: Outcome::failure(FailureCategory::TransportError, &reason.to_string()),
+      deferred: false,
+    },
+  }
+}
+
+/// Response checked by comparison worker.
+struct ComparisonJob {
+  /// Identification of the test.
+  test: TestInfo,
+  /// Raw response body.
+  body: String,
+  /// Expected result of the test.
+  expectation: Expectation,
+}
+
+/// Worker thread deserializing and comparing large responses, so next requests are sent without waiting.
+/// Tests are reported in the order they were executed, after outcomes of all their executions are received.
+struct ComparisonWorker {
+  /// Size of response bodies in bytes above which responses are checked by the worker.
+  threshold: usize,
+  /// Sender of responses to be checked.
+  jobs: Sender<ComparisonJob>,
+  /// Receiver of outcomes with the time spent on deserialization and comparison, in order of sent responses.
+  outcomes: Receiver<(Outcome, PhaseTimes)>,
+  /// Executed tests waiting for reporting, in order of execution.
+  pending: VecDeque<(PendingTest, Vec<Execution>)>,
+}
+
+impl ComparisonWorker {
+  /// Starts the worker thread.
+  fn new(config: &ConfigurationParams, threshold: usize) -> Self {
+    let (jobs, job_receiver) = mpsc::channel::<ComparisonJob>();
+    let (outcome_sender, outcomes) = mpsc::channel();
+    let config = config.clone();
+    thread::spawn(move || {
+      // comparators are not shared between threads, the worker creates its own
+      let mut ctx = Context::without_reports(&config, config.test_cases_dir_paths.clone());
+      for job in job_receiver {
+        let outcome = check_response(&mut ctx, &job.test, &job.body, &job.expectation);
+        if outcome_sender.send((outcome, mem::take(&mut ctx.phase_times))).is_err() {
+          break;
+        }
+      }
+    });
+    Self {
+      threshold,
+      jobs,
+      outcomes,
+      pending: VecDeque::new(),
+    }
+  }
+
+  /// Sends the response to be checked by the worker.
+  fn submit(&self, test: &TestInfo, body: &str, expectation: &Expectation) {
+    let job = ComparisonJob {
+      test: test.clone(),
+      body: body.to_string(),
+      expectation: expectation.clone(),
+    };
+    self
+      .jobs
+      .send(job)
+      .unwrap_or_else(|e| panic!("sending response to comparison worker failed with reason: {}", e));
+  }
+
+  /// Reports pending tests with outcomes of all executions received, in order of execution.
+  /// When `wait` is `true`, waits for outcomes until all pending tests are reported.
+  fn report(&mut self, ctx: &mut Context, evaluate_url: &str, computed_values: &mut [Option<ValueDto>], wait: bool) {
+    while let Some((_, executions)) = self.pending.front_mut() {
+      for execution in executions.iter_mut().filter(|execution| execution.deferred) {
+        let (outcome, phase_times) = if wait {
+          self
+            .outcomes
+            .recv()
+            .unwrap_or_else(|e| panic!("receiving outcome from comparison worker failed with reason: {}", e))
+        } else {
+          match self.outcomes.try_recv() {
+            Ok(received) => received,
+            Err(_) => return,
+          }
+        };
+        ctx.phase_times.deserialization += phase_times.deserialization;
+        ctx.phase_times.comparison += phase_times.comparison;
+        execution.outcome = outcome;
+        execution.deferred = false;
+      }
+      if let Some((pending_test, executions)) = self.pending.pop_front() {
+        let PendingTest {
+          header,
+          test,
+          params,
+          expectation,
+          computed_index,
+        } = pending_test;
+        out!("{}", header);
+        computed_values[computed_index] = report_executions(ctx, evaluate_url, &test, &params, &expectation, executions);
+      }
+    }
+  }
+}
+
+/// Request sent to engine with the received response.
+struct Exchange {
+  /// Duration of the network round-trip.
+  duration: Duration,
+  /// HTTP status of the response, if received.
+  status: Option<u16>,
+  /// Size of the request body in bytes.
+  request_size: usize,
+  /// Response body.
+  response: reqwest::Result<String>,
+}
+
+/// Sends the payload serialized to JSON and receives the response body.
+/// Sizes of request and response bodies are accounted to the directory of tested test file.
+fn post_json<T: Serialize>(ctx: &mut Context, client: &Client, url: &str, directory: &str, payload: &T, timeout: Option<Duration>) -> Exchange {
+  let serialization_start_time = Instant::now();
+  let request_body = serde_json::to_vec(payload).unwrap_or_else(|e| panic!("serializing request failed with reason: {}", e));
+  ctx.phase_times.serialization += serialization_start_time.elapsed();
   let execution_start_time = Instant::now();
-  match client.post(evaluate_url).json(&params).send() {
-    Ok(response) => {
-      let execution_duration = execution_start_time.elapsed();
-      ctx.execution_time += execution_duration.as_nanos();
-      match response.json::<ResultDto<OptionalValueDto>>() {
-        Ok(result) => {
-          if let Some(data) = result.data {
-            if let Some(result_dto) = data.value {
-              if let Some(expected) = opt_expected {
-                let expected_dto = ValueDto::from(expected);
-                if result_dto == expected_dto {
-                  ctx.write_line(file_path, test_case_id, test_id, TestResult::Success, &format!("{} µs", execution_duration.as_micros()));
+  let request_size = request_body.len();
+  let mut request = client.post(url).header(CONTENT_TYPE, "application/json").body(request_body);
+  if let Some(timeout) = timeout {
+    request = request.timeout(timeout);
+  }
+  let response = request.send();
+  let status = response.as_ref().ok().map(|response| response.status().as_u16());
+  let body = response.and_then(|response| response.text());
+  let duration = execution_start_time.elapsed();
+  ctx.execution_time += duration.as_nanos();
+  ctx.request_count += 1;
+  ctx.phase_times.network += duration;
+  ctx.record_latency(duration);
+  ctx.add_payload_sizes(directory, request_size, body.as_ref().map_or(0, String::len));
+  Exchange {
+    duration,
+    status,
+    request_size,
+    response: body,
+  }
+}
+
+/// Checks the response received from engine against expected value,
+/// measuring the time of deserialization of the response and the time of comparison.
+fn check_response(ctx: &mut Context, test: &TestInfo, body: &str, expectation: &Expectation) -> Outcome {
+  let deserialization_start_time = Instant::now();
+  let result = serde_json::from_str::<ResultDto<OptionalValueDto>>(body);
+  ctx.phase_times.deserialization += deserialization_start_time.elapsed();
+  let comparison_start_time = Instant::now();
+  let outcome = check_result(ctx, test, result, expectation);
+  ctx.phase_times.comparison += comparison_start_time.elapsed();
+  outcome
+}
+
+/// Checks the result returned by engine against expected value.
+fn check_result(ctx: &Context, test: &TestInfo, result: serde_json::Result<ResultDto<OptionalValueDto>>, expectation: &Expectation) -> Outcome {
+  let comparison = ctx.comparison(test);
+  let opt_expected = &expectation.value;
+  match result {
+    Ok(result) => {
+      if expectation.error_result && result.errors.is_some() {
+        check_error(&result.to_string(), expectation)
+      } else if expectation.error_result {
+        Outcome {
+          actual: result.data.and_then(|data| data.value),
+          ..Outcome::failure(FailureCategory::UnexpectedError, "expected error, but engine returned a value")
+        }
+      } else if result.errors.is_some() && result.data.as_ref().is_none_or(|data| data.value.is_none()) {
+        // errors reported without a value fail the test, also the smoke test
+        Outcome::failure(FailureCategory::EngineError, &result.to_string())
+      } else if let Some(data) = result.data {
+        if let Some(result_dto) = data.value {
+          if let Some(expected) = opt_expected {
+            // actual value is reported as returned by engine, normalized values are used only for comparison
+            let actual_dto = result_dto.clone();
+            let mut result_dto = result_dto;
+            let mut expected_dto = ValueDto::from(expected);
+            if !ctx.type_aliases.is_empty() {
+              result_dto.replace_type_aliases(&ctx.type_aliases);
+              expected_dto.replace_type_aliases(&ctx.type_aliases);
+            }
+            if !ctx.canonicalization.is_empty() {
+              ctx.canonicalization.apply(&mut result_dto);
+              ctx.canonicalization.apply(&mut expected_dto);
+            }
+            if ctx.component_order == ComponentOrder::Sorted {
+              result_dto.sort_components();
+              expected_dto.sort_components();
+            }
+            if let Err(reason) = expectation.patterns.iter().try_for_each(|pattern| pattern.apply(&result_dto, &mut expected_dto)) {
+              return Outcome::failure(FailureCategory::InvalidTestFile, &reason);
+            }
+            if comparison.equal(&result_dto, &expected_dto) {
+              let warnings = comparison.warnings(&result_dto, &expected_dto);
+              Outcome {
+                actual: Some(actual_dto),
+                ..if warnings.is_empty() {
+                  Outcome::success()
                 } else {
-                  ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, "result differs from expected");
-                  let result_json = serde_json::to_string(&result_dto).unwrap();
-                  let expected_json = serde_json::to_string(&expected_dto).unwrap();
-                  println!("    result: {1}{2}{0}", COLOR_RESET, COLOR_RED, result_json);
-                  println!("  expected: {1}{2}{0}", COLOR_RESET, COLOR_GREEN, expected_json);
-                  println!();
-                  let mut result_chars = result_json.chars();
-                  let mut expected_chars = expected_json.chars();
-                  let mut index = 0;
-                  while let Some((a, b)) = result_chars.next().zip(expected_chars.next()) {
-                    if a != b {
-                      if index > 30 {
-                        index -= 30;
-                      } else {
-                        index = 0;
-                      }
-                      println!("    result [{3}..]: {1}{2}{0}", COLOR_RESET, COLOR_RED, &result_json[index..], index);
-                      println!("  expected [{3}..]: {1}{2}{0}", COLOR_RESET, COLOR_GREEN, &expected_json[index..], index);
-                      println!();
-                      break;
-                    } else {
-                      index += 1;
-                    }
-                  }
-
-                  let result_json_pretty = serde_json::to_string_pretty(&result_dto).unwrap();
-                  let expected_json_pretty = serde_json::to_string_pretty(&expected_dto).unwrap();
-                  let mut result_lines = result_json_pretty.lines();
-                  let mut expected_lines = expected_json_pretty.lines();
-                  let max_width = expected_json_pretty.lines().map(|line| line.len()).max().unwrap() + 5;
-                  while let Some((a, b)) = result_lines.next().zip(expected_lines.next()) {
-                    let color_red = if a != b { COLOR_RED } else { COLOR_RESET };
-                    let color_green = if a != b { COLOR_GREEN } else { COLOR_RESET };
-                    let marker = if a != b { "|" } else { " " };
-                    println!("{3} {2}{5:6$}{0} {1}{4}{0}", COLOR_RESET, color_red, color_green, marker, a, b, max_width);
-                  }
+                  Outcome::warning(&warnings.join("; "))
                 }
-              } else {
-                ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, "no expected value");
               }
             } else {
-              ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, "no actual value");
+              let mismatches = comparison.mismatches(&result_dto, &expected_dto);
+              let class = mismatches.first().map(|mismatch| mismatch.class).unwrap_or(MismatchClass::Value);
+              Outcome {
+                actual: Some(actual_dto),
+                mismatch: Some(render_mismatch(&result_dto, &expected_dto)),
+                ..Outcome::failure(FailureCategory::Mismatch(class), &mismatch_remarks(&mismatches))
+              }
+            }
+          } else if ctx.smoke_tests {
+            Outcome {
+              actual: Some(result_dto),
+              ..Outcome::executed()
             }
-          } else if result.errors.is_some() {
-            ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, &result.to_string());
           } else {
-            ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, format!("{:?}", result).as_str());
+            Outcome {
+              actual: Some(result_dto),
+              ..Outcome::failure(FailureCategory::NoExpectedValue, "no expected value")
+            }
           }
+        } else if ctx.smoke_tests && opt_expected.is_none() {
+          Outcome::executed()
+        } else {
+          Outcome::failure(FailureCategory::NoActualValue, "no actual value")
         }
-        Err(reason) => {
-          ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, &reason.to_string());
-        }
+      } else {
+        Outcome::failure(FailureCategory::InvalidResponse, format!("{:?}", result).as_str())
       }
     }
-    Err(reason) => {
-      let execution_duration = execution_start_time.elapsed();
-      ctx.execution_time += execution_duration.as_nanos();
-      ctx.write_line(file_path, test_case_id, test_id, TestResult::Failure, &reason.to_string());
-    }
+    Err(reason) => Outcome::failure(FailureCategory::InvalidResponse, &reason.to_string()),
   }
 }
 
-fn search_files(path: &Path, pattern: &Regex, files: &mut BTreeMap<String, (Vec<String>, Vec<String>)>) {
+/// Checks the error reported by engine for the test expected to fail, against the expected error message, if any.
+fn check_error(detail: &str, expectation: &Expectation) -> Outcome {
+  let Some(message) = &expectation.error_message else {
+    return Outcome::success();
+  };
+  let matches = match &expectation.error_pattern {
+    Some(Ok(pattern)) => pattern.is_match(detail),
+    Some(Err(reason)) => return Outcome::failure(FailureCategory::InvalidTestFile, &format!("invalid expected error '{}': {}", message, reason)),
+    None => detail.contains(message.as_str()),
+  };
+  if matches {
+    Outcome::success()
+  } else {
+    Outcome::failure(
+      FailureCategory::UnexpectedError,
+      &format!("error differs from expected: '{}' does not match '{}'", detail, message),
+    )
+  }
+}
+
+/// Maximal number of mismatch paths included in remarks.
+const MAX_MISMATCHES: usize = 10;
+
+/// Returns the remarks describing the mismatches between actual and expected value.
+fn mismatch_remarks(mismatches: &[Mismatch]) -> String {
+  let mut remarks = "result differs from expected".to_string();
+  if !mismatches.is_empty() {
+    remarks.push_str(": ");
+    remarks.push_str(
+      &mismatches
+        .iter()
+        .take(MAX_MISMATCHES)
+        .map(|mismatch| mismatch.to_string())
+        .collect::<Vec<String>>()
+        .join("; "),
+    );
+  }
+  if mismatches.len() > MAX_MISMATCHES {
+    remarks.push_str(&format!("; and {} more", mismatches.len() - MAX_MISMATCHES));
+  }
+  remarks
+}
+
+/// Differences between actual and expected value, rendered for display and for the diff file.
+struct RenderedMismatch {
+  /// Actual value as pretty printed JSON.
+  actual_json: String,
+  /// Expected value as pretty printed JSON.
+  expected_json: String,
+  /// Actual and expected value displayed side by side.
+  display: String,
+}
+
+/// Renders the differences between actual and expected value.
+fn render_mismatch(result_dto: &ValueDto, expected_dto: &ValueDto) -> RenderedMismatch {
+  let mut display = String::new();
+  let result_json = serde_json::to_string(result_dto).unwrap();
+  let expected_json = serde_json::to_string(expected_dto).unwrap();
+  display.push_str(&format!("    result: {1}{2}{0}\n", COLOR_RESET, COLOR_RED, result_json));
+  display.push_str(&format!("  expected: {1}{2}{0}\n", COLOR_RESET, COLOR_GREEN, expected_json));
+  display.push('\n');
+  let result_json_pretty = serde_json::to_string_pretty(result_dto).unwrap();
+  let expected_json_pretty = serde_json::to_string_pretty(expected_dto).unwrap();
+  display.push_str(&diff::side_by_side(&expected_json_pretty, &result_json_pretty));
+  RenderedMismatch {
+    actual_json: result_json_pretty,
+    expected_json: expected_json_pretty,
+    display,
+  }
+}
+
+/// Searches recursively model and test files with full name matching the pattern.
+/// Test files are additionally selected by their path relative to root directory,
+/// models are not filtered this way, they may be required by tests in other directories.
+fn search_files(root_dir: &Path, path: &Path, pattern: &Regex, selection: &FileSelection, files: &mut BTreeMap<String, (Vec<String>, Vec<String>)>) {
   if let Ok(entries) = fs::read_dir(path) {
     for entry in entries.flatten() {
       let path = entry.path();
       if path.is_dir() {
-        search_files(&path, pattern, files);
+        search_files(root_dir, &path, pattern, selection, files);
       } else if let Some(dir) = path.parent() {
         let dir_name = dir.canonicalize().unwrap().display().to_string();
         if let Some(exp) = path.extension() {
@@ -275,7 +2001,10 @@ fn search_files(path: &Path, pattern: &Regex, files: &mut BTreeMap<String, (Vec<
           if exp == "xml" {
             let file_name = path.file_name().unwrap().to_string_lossy().to_string();
             let full_name = format!("{}/{}", dir_name, file_name);
-            if pattern.is_match(&full_name) {
+            let relative_name = full_name.strip_prefix(&format!("{}/", root_dir.display())).unwrap_or(&full_name);
+            // labels of test files that fail to parse are not known, such test files are not selected by compliance level
+            let labels = || model::read_labels(&full_name).unwrap_or_default();
+            if pattern.is_match(&full_name) && selection.is_selected(relative_name) && selection.has_compliance_level(relative_name, labels) {
               let (_, files_xml) = files.entry(dir_name).or_insert((vec![], vec![]));
               files_xml.push(file_name);
             }
@@ -288,5 +2017,166 @@ fn search_files(path: &Path, pattern: &Regex, files: &mut BTreeMap<String, (Vec<
 
 /// Displays usage message.
 fn usage() {
-  println!("TBD")
+  out!("{}", USAGE);
 }
+
+/// Usage message describing commands, options, configuration parameters and exit codes.
+const USAGE: &str = r#"
+DMNTK test runner - executes DMN TCK tests against an evaluation endpoint of DMN engine.
+
+USAGE:
+  dmntk-test-runner [OPTIONS] [CONFIG_FILE]               runs all configured tests
+  dmntk-test-runner report trend [OPTIONS] [CONFIG_FILE]  displays the trend of the last runs
+  dmntk-test-runner bench compare BASELINE CURRENT        compares latencies in two timing files
+  dmntk-test-runner daemon [CONFIG_FILE]                  runs tests on commands read from standard input
+  dmntk-test-runner load [OPTIONS] [CONFIG_FILE]          replays requests at increasing concurrency levels
+  dmntk-test-runner soak [OPTIONS] [CONFIG_FILE]          replays requests continuously, tracking the drift
+  dmntk-test-runner cold-start [CONFIG_FILE]              measures the time to the first successful evaluation
+  dmntk-test-runner list [--json] [CONFIG_FILE]           lists discovered models, test files and labels
+  dmntk-test-runner lint [--fix] [PATH]                   checks test files for common problems
+  dmntk-test-runner generate MODEL_FILE [TEST_FILE]       generates the skeleton of the test file
+  dmntk-test-runner init [CONFIG_FILE]                    generates the commented configuration file
+  dmntk-test-runner config check [CONFIG_FILE]            checks the configuration file
+
+When no configuration file is given, it is searched in ./config.yml, ./dmntk-runner.yml
+and $XDG_CONFIG_HOME/dmntk-runner/config.yml (~/.config is used when XDG_CONFIG_HOME is not set).
+
+OPTIONS:
+  -h, --help               displays this message
+  --profile NAME           selects the configuration profile overriding the base section
+  --repeat N               executes every test N times, reporting inconsistent results as flaky
+  --max-failures N         stops testing after N failed tests, writing reports and summary
+  --expect-tests N         fails when the number of executed test cases is not N,
+                           not checked with --changed-only, --name or --only-failed
+  --name PATTERN           executes only test cases with name matching the pattern
+  --level N                executes only test files of compliance level 2 or 3, may be repeated
+  --meta KEY=VALUE         metadata of the run, like engine version, may be repeated
+  --shuffle                executes test files and test cases in shuffled order
+  --seed N                 seed of the shuffled order
+  --changed-only           executes only tests from test files changed since the cached run
+  --only-failed            executes only tests that have failed in the last run
+  --smoke                  executes result nodes without expected value as smoke tests
+  --strict                 reports test files with duplicated identifiers as failures
+  --ignore-case            compares strings case-insensitively
+  --trim-strings           ignores leading and trailing whitespace of strings
+  --write-computed         writes computed values back into test files
+  --tui                    displays interactive terminal interface instead of the console output
+  --ascii                  limits console output to plain ASCII, with +-| tables and without colors
+  --curl                   prints curl commands reproducing evaluations of failed tests
+  --events-format FORMAT   emits progress events in jsonl format to standard output,
+                           the console output is written to standard error
+  --workers N              overrides the number of workers, depending on the command:
+                             load: load_max_workers, the maximal number of concurrent workers
+                             soak: soak_workers, the number of workers
+  --duration SECONDS       overrides the duration, depending on the command:
+                             load: load_level_duration, the duration of each concurrency level,
+                                   or load_adaptive_duration with --target-latency or load_target_latency_ms
+                             soak: soak_duration, the duration of the soak test
+  --target-latency MS      target latency of adaptive load test
+  --last N                 number of runs included in trend report
+  --csv                    prints reports in CSV format
+  --json                   prints listing in JSON format
+  --fix                    fixes problems found by linter
+
+CONFIGURATION (YAML):
+  Required parameters:
+    test_cases_dir_path            directory containing test cases, or a list of directories tested in one run
+    evaluate_url                   URL of the evaluation endpoint
+    report_file                    path to CSV report with results of all tests
+    tck_report_file                path to CSV report for TCK
+    stop_on_failure                stops testing on the first failure
+  Selection:
+    file_search_pattern            regular expression matching full names of files, empty matches all files
+    include, exclude               patterns of included and excluded test files, relative to test cases
+                                   directory, regular expressions or globs prefixed with 'glob:'
+    file_search_globs              globs matching test files, relative to test cases directory
+    test_case_name_pattern         regular expression matching test case names
+    compliance_levels              compliance levels of executed test files, like [2, 3]
+    changed_only, cache_file       executes only changed test files, hashes are cached in file
+    only_failed                    executes only tests failed in the last run, read from history_db
+                                   when configured, otherwise from report_file
+    shuffle, seed                  executes tests in shuffled order
+    streaming_threshold            size of test files in bytes above which test cases are parsed lazily
+  Engine connection:
+    endpoint_address               IP address the host of evaluate URL is resolved to
+    endpoint_socket                unix domain socket engine listens on
+    batch_url, batch_size          endpoint evaluating multiple invocables in one request
+    test_timeout_ms, test_timeouts time budgets of tests, overridden per label or directory
+    repeat                         number of executions of every test
+    max_failures                   stops testing after the number of failed tests, writing reports
+    expected_tests                 fails when a different number of test cases is executed
+    health_url                     health endpoint polled by cold-start command
+    cold_start_timeout             maximal time to wait for engine readiness in seconds
+    cold_start_poll_interval_ms    interval between polls of health endpoint
+  Reports:
+    report_format                  columns of the CSV report
+    path_display                   rendering of paths in console output and reports,
+                                   relative (default), absolute or prefixed
+    path_prefix                    prefix replacing test cases directory in prefixed paths
+    run_metadata                   metadata of the run embedded in reports and history, like
+                                   {engine_version: 1.2.3, git_commit: abc123, environment: ci}
+    diff_file                      unified diffs of mismatched values
+    failure_artifacts_dir          JSON artifacts of failed tests
+    failures_dir                   parameters of evaluation requests of failed tests
+    timing_file                    timing of every request
+    latency_histogram_file         histogram of request latencies in hgrm format
+    history_db                     SQLite database with the history of all runs
+    badge_file                     SVG badge with the percentage of passed test cases
+    dashboard_file                 HTML dashboard with results of all tests
+    labels_report_file             CSV report with results grouped by labels
+    coverage_report                reports models and invocables not exercised by tests
+    notify_url                     URL receiving the JSON notification when the run completes
+    tui                            displays interactive terminal interface
+    ascii                          limits console output to plain ASCII, without box-drawing characters
+                                   and colors, for CI log viewers and Windows consoles
+    events_format                  format of progress events written to standard output (jsonl)
+    curl_commands                  prints curl commands reproducing evaluations of failed tests,
+                                   written also to failure artifacts
+  Comparison:
+    comparators                    comparators applied before strict comparison
+    comparison_overrides           comparators applied only to specified tests
+    canonicalization               normalization steps applied to both values
+    component_order                order of components when comparing values
+    decimal_scale, rounding        scale and rounding mode of compared decimals, not allowed with numeric_tolerance comparator
+    unicode_normalization          normalization form applied to strings
+    type_aliases                   type names replaced before comparison
+    nil_equals_empty_list          treats nil and empty list as equal, with warning
+    missing_component_equals_nil   treats missing and nil components as equal, with warning
+    components_subset              allows components not present in expected value
+    error_message_matching         matching of expected error messages
+    smoke_tests                    executes result nodes without expected value as smoke tests
+    computed_fallback              compares with computed value when expected value is missing
+    infer_types                    infers types of untyped values from DMN models
+    strict                         reports duplicated identifiers as failures
+    write_computed                 writes computed values back into test files
+    comparison_worker_threshold    size of responses in bytes compared in separate thread
+  Performance testing:
+    load_max_workers, load_level_duration, load_target_latency_ms, load_adaptive_duration
+    soak_duration, soak_interval, soak_workers
+    engine_pid, engine_cgroup, resource_sampling_interval_ms
+  String values may contain placeholders of environment variables in form ${NAME}
+  or ${NAME:-default}, expanded when the configuration is loaded, literal ${ is written as $${.
+  Placeholders are not expanded in file_search_pattern, test_case_name_pattern, include and exclude.
+  Profiles:
+    profiles                       named sections selected with --profile, parameters defined
+                                   in the selected profile override parameters of the base section,
+                                   like {local: {evaluate_url: ...}, ci: {stop_on_failure: true}}
+
+EXIT CODES:
+  0    command completed
+  1    invalid arguments or configuration, problems found by linter, latency regressions
+       found by bench compare, testing stopped on failure or after maximal number of failures,
+       or unexpected number of executed test cases
+  130  testing interrupted with Ctrl-C or aborted from interactive terminal interface
+
+EXAMPLES:
+  dmntk-test-runner init
+  dmntk-test-runner config check
+  dmntk-test-runner config-compliance.yml
+  dmntk-test-runner --name "^addition" --repeat 3
+  dmntk-test-runner --level 3
+  dmntk-test-runner --profile ci
+  dmntk-test-runner --changed-only --tui
+  dmntk-test-runner load --workers 16 --duration 30
+  dmntk-test-runner bench compare baseline.csv current.csv
+"#;
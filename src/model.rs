@@ -32,15 +32,55 @@
 
 //! # XML model for test cases
 
+use crate::serializer::escape;
 use roxmltree::Node;
-use std::fs::read_to_string;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::{read_to_string, File};
+use std::io::{ErrorKind, Read};
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+/// Result type returned by test file parsing functions.
+pub type Result<T, E = ParseError> = std::result::Result<T, E>;
 
 const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema";
+const XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Canonical prefix of the XML Schema namespace used in type names.
+pub const PREFIX_XSD: &str = "xsd";
+/// Canonical prefix of the FEEL namespace used in type names.
+pub const PREFIX_FEEL: &str = "feel";
+
+/// Namespace used in type names.
+struct TypeNamespace {
+  /// Canonical prefix of the namespace.
+  prefix: &'static str,
+  /// Prefixes conventionally bound to the namespace, used when no namespace declarations are available.
+  aliases: &'static [&'static str],
+  /// Returns `true` when the URI identifies the namespace.
+  matches: fn(&str) -> bool,
+}
+
+/// Namespaces used in type names.
+const TYPE_NAMESPACES: [TypeNamespace; 2] = [
+  TypeNamespace {
+    prefix: PREFIX_XSD,
+    aliases: &["xsd", "xs"],
+    matches: |uri| uri == XSD,
+  },
+  TypeNamespace {
+    prefix: PREFIX_FEEL,
+    aliases: &["feel"],
+    matches: is_feel_namespace,
+  },
+];
 
 const NODE_COMPONENT: &str = "component";
 const NODE_COMPUTED: &str = "computed";
 const NODE_DESCRIPTION: &str = "description";
 const NODE_EXPECTED: &str = "expected";
+const NODE_EXTENSION_ELEMENTS: &str = "extensionElements";
 const NODE_INPUT_NODE: &str = "inputNode";
 const NODE_ITEM: &str = "item";
 const NODE_LABELS: &str = "labels";
@@ -57,19 +97,65 @@ const ATTR_ERROR_RESULT: &str = "errorResult";
 const ATTR_ID: &str = "id";
 const ATTR_INVOCABLE_NAME: &str = "invocableName";
 const ATTR_NAME: &str = "name";
+const ATTR_NAMESPACE: &str = "namespace";
 const ATTR_NIL: &str = "nil";
+const ATTR_SPACE: &str = "space";
 const ATTR_TYPE: &str = "type";
 
+const TYPE_FUNCTION: &str = "function";
+
+/// Namespaces of test case files published in the DMN TCK, with DMN specification versions that introduced them.
+/// All later versions of the TCK schema keep the namespace introduced with DMN 1.1.
+const TESTCASE_NAMESPACES: [(&str, &str); 1] = [("http://www.omg.org/spec/DMN/20160719/testcase", "1.1")];
+
 /// Test cases.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestCases {
+  /// Namespace of the test cases file.
+  pub namespace: Option<String>,
+  /// Version of the DMN specification detected from namespace, `None` when the namespace is unknown.
+  pub spec_version: Option<String>,
   pub model_name: Option<String>,
   pub labels: Vec<String>,
   pub test_cases: Vec<TestCase>,
 }
 
+impl TestCases {
+  /// Returns descriptions of duplicated test case identifiers and duplicated result node names within test cases.
+  pub fn duplicates(&self) -> Vec<String> {
+    let mut duplicates = Duplicates::default();
+    self.test_cases.iter().flat_map(|test_case| duplicates.check(test_case)).collect()
+  }
+}
+
+/// Detector of duplicated test case identifiers and result node names, checking test cases one by one.
+#[derive(Default)]
+pub struct Duplicates {
+  /// Identifiers of already checked test cases.
+  test_case_ids: BTreeSet<String>,
+}
+
+impl Duplicates {
+  /// Returns descriptions of duplicates found in the test case, including identifiers of previously checked test cases.
+  pub fn check(&mut self, test_case: &TestCase) -> Vec<String> {
+    let mut duplicates = vec![];
+    let test_case_id = test_case.id.as_deref().unwrap_or_default();
+    // test cases without identifier are not duplicates of each other
+    if test_case.id.is_some() && !self.test_case_ids.insert(test_case_id.to_string()) {
+      duplicates.push(format!("duplicate test case id '{}'", test_case_id));
+    }
+    let mut result_node_names = BTreeSet::new();
+    for result_node in &test_case.result_nodes {
+      if !result_node_names.insert((&result_node.namespace, &result_node.name)) {
+        duplicates.push(format!("duplicate result node '{}' in test case '{}'", result_node.name, test_case_id));
+      }
+    }
+    duplicates
+  }
+}
+
 /// Type of the test case.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TestCaseType {
   Decision,
   BusinessKnowledgeModel,
@@ -95,19 +181,22 @@ impl From<Option<String>> for TestCaseType {
   }
 }
 
-impl ToString for TestCaseType {
-  fn to_string(&self) -> String {
-    match self {
-      TestCaseType::Decision => "decision",
-      TestCaseType::BusinessKnowledgeModel => "bkm",
-      TestCaseType::DecisionService => "decisionService",
-    }
-    .to_string()
+impl fmt::Display for TestCaseType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        TestCaseType::Decision => "decision",
+        TestCaseType::BusinessKnowledgeModel => "bkm",
+        TestCaseType::DecisionService => "decisionService",
+      }
+    )
   }
 }
 
 /// Single test case.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestCase {
   /// Optional identifier of this [TestCase].
   pub id: Option<String>,
@@ -123,40 +212,59 @@ pub struct TestCase {
   pub input_nodes: Vec<InputNode>,
   /// Collection of result nodes.
   pub result_nodes: Vec<ResultNode>,
+  /// Extension elements defined for this [TestCase].
+  pub extension_elements: Vec<ExtensionElement>,
 }
 
 /// Input node defined for test case.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InputNode {
   /// Required name of this [InputNode].
   pub name: String,
+  /// Optional namespace of the model where this [InputNode] is defined.
+  pub namespace: Option<String>,
   /// Optional value of this [InputNode].
   pub value: Option<Value>,
 }
 
 /// Result node defined for the test case.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ResultNode {
   pub name: String,
+  /// Optional namespace of the model where the result is defined.
+  pub namespace: Option<String>,
   pub error_result: bool,
   pub typ: TestCaseType,
   pub cast: Option<String>,
   pub expected: Option<Value>,
   pub computed: Option<Value>,
+  pub extension_elements: Vec<ExtensionElement>,
+}
+
+/// Single element defined in `extensionElements` node.
+#[derive(Debug, Clone)]
+pub struct ExtensionElement {
+  /// Local name of the element.
+  pub name: String,
+  /// Attributes of the element (local name and value).
+  pub attributes: Vec<(String, String)>,
+  /// Optional trimmed textual content of the element.
+  pub text: Option<String>,
 }
 
 /// Types of values.
 /// [Value] may be a simple (single) value,
-/// collection of components or a list.
-#[derive(Debug)]
+/// collection of components, a list or a function.
+#[derive(Debug, Clone)]
 pub enum Value {
   Simple(Simple),
   Components(Vec<Component>),
   List(List),
+  Function(Function),
 }
 
 /// Value representing simple result of the test case.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Simple {
   /// Type of the value in namespace-prefixed form.
   pub typ: Option<String>,
@@ -164,10 +272,12 @@ pub struct Simple {
   pub text: Option<String>,
   /// Flag indicating if this [Value] is nil, like `xsi:nil="true"`.
   pub nil: bool,
+  /// Attributes and child elements not recognized by the parser.
+  pub unrecognized: Unrecognized,
 }
 
 /// Value representing complex result of a test case.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Component {
   /// Optional name of this component.
   pub name: Option<String>,
@@ -175,71 +285,461 @@ pub struct Component {
   pub value: Option<Value>,
   /// Flag indicating if this [Component] is nil, like `xsi:nil="true"`.
   pub nil: bool,
+  /// Attributes and child elements not recognized by the parser.
+  pub unrecognized: Unrecognized,
+}
+
+/// Value representing a function, compared as an opaque value.
+#[derive(Debug, Clone)]
+pub struct Function {
+  /// Type of the value in namespace-prefixed form, like `feel:function`.
+  pub typ: Option<String>,
+  /// Optional text of the function, like its signature or body.
+  pub text: Option<String>,
 }
 
 /// Value representing a list.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct List {
   /// Vector of list items (values), may be empty.
   pub items: Vec<Value>,
   /// Flag indicating if this [List] is nil, like `xsi:nil="true"`.
   pub nil: bool,
+  /// Attributes and child elements not recognized by the parser.
+  pub unrecognized: Unrecognized,
 }
 
 impl Default for List {
   /// [List] is empty and nil by default.
   fn default() -> Self {
-    Self { items: vec![], nil: true }
+    Self {
+      items: vec![],
+      nil: true,
+      unrecognized: Unrecognized::default(),
+    }
+  }
+}
+
+/// Attributes and child elements of value nodes not recognized by the parser,
+/// retained to preserve the content when test cases are written back.
+#[derive(Debug, Default, Clone)]
+pub struct Unrecognized {
+  /// Unrecognized attributes.
+  pub attributes: Vec<UnrecognizedAttribute>,
+  /// Unrecognized child elements in their original XML form.
+  pub elements: Vec<String>,
+}
+
+/// Attribute not recognized by the parser.
+#[derive(Debug, Clone)]
+pub struct UnrecognizedAttribute {
+  /// Optional namespace of the attribute.
+  pub namespace: Option<String>,
+  /// Local name of the attribute.
+  pub name: String,
+  /// Value of the attribute.
+  pub value: String,
+}
+
+/// Error reported when the test file could not be parsed.
+#[derive(Debug)]
+pub struct ParseError {
+  /// Name of the file being parsed.
+  pub file: String,
+  /// Line in the file where the error was detected (1-based, 0 when unknown).
+  pub line: u32,
+  /// Column in the file where the error was detected (1-based, 0 when unknown).
+  pub column: u32,
+  /// Description of the error with context.
+  pub message: String,
+}
+
+impl ParseError {
+  /// Creates an error reported for the specified node, the file name is set by [parse_test_file].
+  fn new(node: &Node, message: String) -> Self {
+    let pos = node.document().text_pos_at(node.range().start);
+    Self {
+      file: String::new(),
+      line: pos.row,
+      column: pos.col,
+      message,
+    }
   }
 }
 
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.line > 0 {
+      write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message)
+    } else {
+      write!(f, "{}: {}", self.file, self.message)
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
 /// Parses the XML file containing test cases.
-pub fn parse_test_file(file_name: &str) -> TestCases {
-  let content = read_to_string(file_name).expect("reading test file failed");
-  let document = roxmltree::Document::parse(&content).expect("parsing test file failed");
+pub fn parse_test_file(file_name: &str) -> Result<TestCases> {
+  parse_test_cases_content(file_name, &read_test_file(file_name)?)
+}
+
+/// Reads the content of the test file.
+pub fn read_test_file(file_name: &str) -> Result<String> {
+  read_to_string(file_name).map_err(|e| ParseError {
+    file: file_name.to_string(),
+    line: 0,
+    column: 0,
+    message: format!("reading test file failed with reason: {}", e),
+  })
+}
+
+/// Reads labels of the test file, parsing only the header of the file.
+pub fn read_labels(file_name: &str) -> Result<Vec<String>> {
+  stream_test_file(file_name).map(|(header, _)| header.labels)
+}
+
+/// Parses test cases from the content of the test file.
+fn parse_test_cases_content(file_name: &str, content: &str) -> Result<TestCases> {
+  let document = parse_document(file_name, content)?;
   let test_cases_node = document.root_element();
   if test_cases_node.tag_name().name() != NODE_TEST_CASES {
-    panic!("Expected mandatory node: {}", NODE_TEST_CASES);
+    Err(ParseError::new(&test_cases_node, format!("expected mandatory node '{}'", NODE_TEST_CASES)))
   } else {
     parse_root_node(&test_cases_node)
   }
+  .map_err(|mut e| {
+    e.file = file_name.to_string();
+    e
+  })
 }
 
-/// Parses `testCases` node being the root element of the document.
-fn parse_root_node(node: &Node) -> TestCases {
-  TestCases {
-    model_name: optional_child_required_content(node, NODE_MODEL_NAME),
-    labels: parse_labels(node),
-    test_cases: parse_test_cases(node),
+/// Parses XML document.
+fn parse_document<'a>(file_name: &str, content: &'a str) -> Result<roxmltree::Document<'a>> {
+  roxmltree::Document::parse(content).map_err(|e| ParseError {
+    file: file_name.to_string(),
+    line: e.pos().row,
+    column: e.pos().col,
+    message: format!("parsing test file failed with reason: {}", e),
+  })
+}
+
+/// Size of the chunk read from the lazily parsed test file.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Test cases parsed lazily from the test file read in chunks.
+///
+/// Only the header of the file (model name, labels) is parsed up front,
+/// test cases are parsed one by one while iterating, so the memory used
+/// does not grow with the number of test cases in the file.
+pub struct TestCasesStream<R: Read> {
+  /// Name of the parsed file.
+  file_name: String,
+  /// Reader of the file content.
+  reader: R,
+  /// Flag indicating if the whole content was read.
+  eof: bool,
+  /// Bytes read but not decoded yet, an incomplete UTF-8 sequence at the end of the last chunk.
+  undecoded: Vec<u8>,
+  /// Content read but not parsed yet, always starting at the beginning of a line.
+  buffer: String,
+  /// Number of lines preceding the content in buffer.
+  buffer_line: u32,
+  /// Position in buffer after the last parsed test case, where searching for the next test case starts.
+  position: usize,
+  /// Fragment of the document parsed for a single test case, starting with the content up to the end
+  /// of the root element's start tag, followed by the test case and the end tag of the root element.
+  fragment: String,
+  /// Length of the fragment's part preceding the test case.
+  fragment_prefix: usize,
+  /// Qualified name of the root element.
+  root_name: String,
+  /// Number of lines up to the end of the root element's start tag.
+  root_lines: u32,
+  /// Flag indicating if all test cases were parsed or parsing failed.
+  finished: bool,
+}
+
+/// Opens the test file for lazy parsing of test cases, see [stream_test_cases].
+pub fn stream_test_file(file_name: &str) -> Result<(TestCases, TestCasesStream<File>)> {
+  let file = File::open(file_name).map_err(|e| ParseError {
+    file: file_name.to_string(),
+    line: 0,
+    column: 0,
+    message: format!("reading test file failed with reason: {}", e),
+  })?;
+  stream_test_cases(file_name, file)
+}
+
+/// Prepares lazy parsing of test cases read from the reader, the content is read only up to the first test case.
+/// Returns the header of the test cases file (with no test cases) and the stream of test cases.
+pub fn stream_test_cases<R: Read>(file_name: &str, reader: R) -> Result<(TestCases, TestCasesStream<R>)> {
+  let mut stream = TestCasesStream {
+    file_name: file_name.to_string(),
+    reader,
+    eof: false,
+    undecoded: vec![],
+    buffer: String::new(),
+    buffer_line: 0,
+    position: 0,
+    fragment: String::new(),
+    fragment_prefix: 0,
+    root_name: String::new(),
+    root_lines: 0,
+    finished: false,
+  };
+  // the content is read until the first test case is found, tokenizing is repeated after every read chunk
+  let (first_test_case, root_end) = loop {
+    match stream.find_first_test_case() {
+      Ok(Some(found)) => break found,
+      Ok(None) if stream.eof => break (None, stream.buffer.len()),
+      Err(e) if stream.eof => return Err(stream.token_error(e)),
+      _ => stream.read_chunk()?,
+    }
+  };
+  // the header is parsed as a document closed right before the first test case
+  let header = match first_test_case {
+    Some(start) => parse_test_cases_content(file_name, &format!("{}</{}>", &stream.buffer[..start], stream.root_name))?,
+    None => parse_test_cases_content(file_name, &stream.buffer)?,
+  };
+  stream.fragment = format!("{}\n", &stream.buffer[..root_end]);
+  stream.fragment_prefix = stream.fragment.len();
+  stream.root_lines = stream.buffer[..root_end].matches('\n').count() as u32 + 1;
+  match first_test_case {
+    Some(start) => stream.consume(start),
+    None => stream.finished = true,
+  }
+  Ok((header, stream))
+}
+
+/// Result of searching the test case in the content read so far.
+enum Found {
+  /// Test case placed at the range of the buffer.
+  TestCase(usize, usize),
+  /// End of the root element, there are no more test cases.
+  End,
+  /// More content must be read, the test case is not complete.
+  Incomplete,
+}
+
+impl<R: Read> TestCasesStream<R> {
+  /// Tokenizes the content read so far, returns the start position of the first test case
+  /// and the end position of the root element's start tag, `None` when more content is needed.
+  fn find_first_test_case(&mut self) -> std::result::Result<Option<(Option<usize>, usize)>, xmlparser::Error> {
+    let mut depth = 0;
+    let mut root_end = None;
+    self.root_name.clear();
+    for token in Tokenizer::from(self.buffer.as_str()) {
+      match token? {
+        Token::ElementStart { local, span, .. } if depth == 1 && local.as_str() == NODE_TEST_CASE => return Ok(root_end.map(|root_end| (Some(span.start()), root_end))),
+        Token::ElementStart { prefix, local, .. } if depth == 0 && self.root_name.is_empty() => {
+          self.root_name = if prefix.is_empty() { local.to_string() } else { format!("{}:{}", prefix, local) };
+        }
+        Token::ElementEnd { end: ElementEnd::Open, span } => {
+          depth += 1;
+          if depth == 1 {
+            root_end = Some(span.end());
+          }
+        }
+        Token::ElementEnd { end: ElementEnd::Close(..), .. } => depth -= 1,
+        _ => {}
+      }
+    }
+    Ok(None)
+  }
+
+  /// Tokenizes the content following the last parsed test case and returns the range of the next test case.
+  fn find_next_test_case(&self) -> std::result::Result<Found, xmlparser::Error> {
+    // depth is counted from the content of the root element
+    let mut depth = 0;
+    let mut start = None;
+    for token in Tokenizer::from_fragment(&self.buffer, self.position..self.buffer.len()) {
+      match token? {
+        Token::ElementStart { local, span, .. } if start.is_none() && depth == 0 && local.as_str() == NODE_TEST_CASE => start = Some(span.start()),
+        Token::ElementEnd { end: ElementEnd::Open, .. } => depth += 1,
+        Token::ElementEnd { end, span } => {
+          if matches!(end, ElementEnd::Close(..)) {
+            if depth == 0 {
+              return Ok(Found::End);
+            }
+            depth -= 1;
+          }
+          if let (0, Some(start)) = (depth, start) {
+            return Ok(Found::TestCase(start, span.end()));
+          }
+        }
+        _ => {}
+      }
+    }
+    Ok(Found::Incomplete)
+  }
+
+  /// Finds and parses the next test case, reading more content when needed.
+  fn next_test_case(&mut self) -> Result<Option<TestCase>> {
+    loop {
+      match self.find_next_test_case() {
+        Ok(Found::TestCase(start, end)) => {
+          let test_case = self.parse_test_case(start, end);
+          self.consume(end);
+          return test_case.map(Some);
+        }
+        Ok(Found::End) => return Ok(None),
+        Ok(Found::Incomplete) if self.eof => {
+          return Err(ParseError {
+            file: self.file_name.clone(),
+            line: 0,
+            column: 0,
+            message: "parsing test file failed with reason: the root node was opened but never closed".to_string(),
+          })
+        }
+        Err(e) if self.eof => return Err(self.token_error(e)),
+        _ => self.read_chunk()?,
+      }
+    }
+  }
+
+  /// Reads the next chunk of content into the buffer.
+  fn read_chunk(&mut self) -> Result<()> {
+    let read_error = |file_name: &str, reason: String| ParseError {
+      file: file_name.to_string(),
+      line: 0,
+      column: 0,
+      message: format!("reading test file failed with reason: {}", reason),
+    };
+    let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+    let count = loop {
+      match self.reader.read(&mut chunk) {
+        Ok(count) => break count,
+        Err(e) if e.kind() == ErrorKind::Interrupted => {}
+        Err(e) => return Err(read_error(&self.file_name, e.to_string())),
+      }
+    };
+    self.eof = count == 0;
+    self.undecoded.extend_from_slice(&chunk[..count]);
+    // the chunk may end in the middle of a multibyte character, decoded are only complete characters
+    let valid = match std::str::from_utf8(&self.undecoded) {
+      Ok(text) => text.len(),
+      Err(e) if e.error_len().is_none() && !self.eof => e.valid_up_to(),
+      Err(e) => return Err(read_error(&self.file_name, e.to_string())),
+    };
+    self.buffer.push_str(std::str::from_utf8(&self.undecoded[..valid]).unwrap_or_default());
+    self.undecoded.drain(..valid);
+    Ok(())
+  }
+
+  /// Removes the content preceding the line containing specified position from the buffer,
+  /// searching for the next test case starts at this position.
+  fn consume(&mut self, position: usize) {
+    let line_start = self.buffer[..position].rfind('\n').map_or(0, |index| index + 1);
+    self.buffer_line += self.buffer[..line_start].matches('\n').count() as u32;
+    self.buffer.drain(..line_start);
+    self.position = position - line_start;
+  }
+
+  /// Returns the parsing error at the position in buffer reported by tokenizer.
+  fn token_error(&self, e: xmlparser::Error) -> ParseError {
+    ParseError {
+      file: self.file_name.clone(),
+      line: e.pos().row + self.buffer_line,
+      column: e.pos().col,
+      message: format!("parsing test file failed with reason: {}", e).replace(&e.pos().to_string(), &format!("{}:{}", e.pos().row + self.buffer_line, e.pos().col)),
+    }
+  }
+
+  /// Parses the test case placed at the specified range of the buffer.
+  /// The test case is parsed as the only child of the root element, placed at the same
+  /// column as in the original content, so the reported columns are preserved and lines are shifted.
+  fn parse_test_case(&mut self, start: usize, end: usize) -> Result<TestCase> {
+    let line = self.buffer_line + self.buffer[..start].matches('\n').count() as u32 + 1;
+    let line_start = self.buffer[..start].rfind('\n').map_or(0, |position| position + 1);
+    let column = self.buffer[line_start..start].chars().count();
+    // the part preceding the test case is written once, only the test case is replaced
+    self.fragment.truncate(self.fragment_prefix);
+    self.fragment.extend(std::iter::repeat_n(' ', column));
+    self.fragment.push_str(&self.buffer[start..end]);
+    self.fragment.push_str(&format!("</{}>", self.root_name));
+    let shift_line = |mut e: ParseError| {
+      if e.line > self.root_lines {
+        let shifted_line = e.line - self.root_lines - 1 + line;
+        // XML errors contain the position in parsed fragment
+        e.message = e.message.replace(&format!(" at {}:{}", e.line, e.column), &format!(" at {}:{}", shifted_line, e.column));
+        e.line = shifted_line;
+      }
+      e.file = self.file_name.clone();
+      e
+    };
+    let document = parse_document(&self.file_name, &self.fragment).map_err(shift_line)?;
+    let test_case_node = document
+      .root_element()
+      .children()
+      .find(|n| n.tag_name().name() == NODE_TEST_CASE)
+      .ok_or_else(|| shift_line(ParseError::new(&document.root_element(), format!("expected node '{}'", NODE_TEST_CASE))))?;
+    parse_test_case(&test_case_node).map_err(shift_line)
   }
 }
 
+impl<R: Read> Iterator for TestCasesStream<R> {
+  type Item = Result<TestCase>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.finished {
+      return None;
+    }
+    let result = self.next_test_case().transpose();
+    if !matches!(result, Some(Ok(_))) {
+      self.finished = true;
+    }
+    result
+  }
+}
+
+/// Parses `testCases` node being the root element of the document.
+fn parse_root_node(node: &Node) -> Result<TestCases> {
+  let namespace = node.tag_name().namespace().map(|uri| uri.to_string());
+  let spec_version = namespace
+    .as_deref()
+    .and_then(|uri| TESTCASE_NAMESPACES.iter().find(|(known_uri, _)| *known_uri == uri).map(|(_, version)| version.to_string()));
+  Ok(TestCases {
+    namespace,
+    spec_version,
+    model_name: optional_child_required_content(node, NODE_MODEL_NAME)?,
+    labels: parse_labels(node)?,
+    test_cases: parse_test_cases(node)?,
+  })
+}
+
 /// Parses all labels.
-fn parse_labels(node: &Node) -> Vec<String> {
+fn parse_labels(node: &Node) -> Result<Vec<String>> {
   let mut items = vec![];
   if let Some(labels_node) = node.children().find(|n| n.tag_name().name() == NODE_LABELS) {
     for ref label_node in labels_node.children().filter(|n| n.tag_name().name() == NODE_LABEL) {
-      items.push(required_content(label_node))
+      items.push(required_content(label_node)?)
     }
   }
-  items
+  Ok(items)
 }
 
 /// Parses all test cases.
-fn parse_test_cases(node: &Node) -> Vec<TestCase> {
+fn parse_test_cases(node: &Node) -> Result<Vec<TestCase>> {
   let mut items = vec![];
   for ref test_case_node in node.children().filter(|n| n.tag_name().name() == NODE_TEST_CASE) {
-    items.push(TestCase {
-      id: optional_attribute(test_case_node, ATTR_ID),
-      name: optional_attribute(test_case_node, ATTR_NAME),
-      typ: parse_test_case_type(test_case_node),
-      description: optional_child_required_content(test_case_node, NODE_DESCRIPTION),
-      invocable_name: optional_attribute(test_case_node, ATTR_INVOCABLE_NAME),
-      input_nodes: parse_input_nodes(test_case_node),
-      result_nodes: parse_result_nodes(test_case_node),
-    })
+    items.push(parse_test_case(test_case_node)?)
   }
-  items
+  Ok(items)
+}
+
+/// Parses single test case.
+fn parse_test_case(node: &Node) -> Result<TestCase> {
+  Ok(TestCase {
+    id: optional_attribute(node, ATTR_ID),
+    name: optional_attribute(node, ATTR_NAME),
+    typ: parse_test_case_type(node),
+    description: optional_child_required_content(node, NODE_DESCRIPTION)?,
+    invocable_name: optional_attribute(node, ATTR_INVOCABLE_NAME),
+    input_nodes: parse_input_nodes(node)?,
+    result_nodes: parse_result_nodes(node)?,
+    extension_elements: parse_extension_elements(node),
+  })
 }
 
 /// Parses test case type. The default value is [TestCaseType#Decision].
@@ -252,51 +752,103 @@ fn parse_test_case_type(node: &Node) -> TestCaseType {
 }
 
 /// Parses input nodes defined for test case.
-fn parse_input_nodes(node: &Node) -> Vec<InputNode> {
+fn parse_input_nodes(node: &Node) -> Result<Vec<InputNode>> {
   let mut items = vec![];
   for ref input_node in node.children().filter(|n| n.tag_name().name() == NODE_INPUT_NODE) {
     items.push(InputNode {
-      name: required_attribute(input_node, ATTR_NAME),
-      value: parse_value_type(input_node),
+      name: required_attribute(input_node, ATTR_NAME)?,
+      namespace: optional_attribute(input_node, ATTR_NAMESPACE),
+      value: parse_value_type(input_node)?,
     })
   }
-  items
+  Ok(items)
 }
 
 /// Parses result nodes expected by test case.
-fn parse_result_nodes(node: &Node) -> Vec<ResultNode> {
+fn parse_result_nodes(node: &Node) -> Result<Vec<ResultNode>> {
   let mut items = vec![];
   for ref result_node in node.children().filter(|n| n.tag_name().name() == NODE_RESULT_NODE) {
     items.push(ResultNode {
-      name: required_attribute(result_node, ATTR_NAME),
-      error_result: optional_attribute(result_node, ATTR_ERROR_RESULT).map_or(false, |v| v == "true"),
+      name: required_attribute(result_node, ATTR_NAME)?,
+      namespace: optional_attribute(result_node, ATTR_NAMESPACE),
+      error_result: optional_attribute(result_node, ATTR_ERROR_RESULT).is_some_and(|v| v == "true"),
       typ: optional_attribute(result_node, ATTR_TYPE).into(),
       cast: optional_attribute(result_node, ATTR_CAST),
-      expected: parse_child_value_type(result_node, NODE_EXPECTED),
-      computed: parse_child_value_type(result_node, NODE_COMPUTED),
+      expected: parse_child_value_type(result_node, NODE_EXPECTED)?,
+      computed: parse_child_value_type(result_node, NODE_COMPUTED)?,
+      extension_elements: parse_extension_elements(result_node),
     })
   }
+  Ok(items)
+}
+
+/// Parses extension elements defined for test case or node.
+fn parse_extension_elements(node: &Node) -> Vec<ExtensionElement> {
+  let mut items = vec![];
+  if let Some(extension_elements_node) = node.children().find(|n| n.tag_name().name() == NODE_EXTENSION_ELEMENTS) {
+    for element_node in extension_elements_node.children().filter(|n| n.is_element()) {
+      items.push(ExtensionElement {
+        name: element_node.tag_name().name().to_string(),
+        attributes: element_node.attributes().map(|attr| (attr.name().to_string(), attr.value().to_string())).collect(),
+        text: element_node.text().map(|text| text.trim().to_string()).filter(|text| !text.is_empty()),
+      })
+    }
+  }
   items
 }
 
+/// Returns extension elements as key-value metadata.
+/// Element text is stored under the element name, attributes under `name.attribute` keys.
+/// Later elements override the values of earlier ones.
+pub fn metadata<'a>(elements: impl IntoIterator<Item = &'a ExtensionElement>) -> BTreeMap<String, String> {
+  let mut metadata = BTreeMap::new();
+  for element in elements {
+    if let Some(text) = &element.text {
+      metadata.insert(element.name.clone(), text.clone());
+    }
+    for (attr_name, attr_value) in &element.attributes {
+      metadata.insert(format!("{}.{}", element.name, attr_name), attr_value.clone());
+    }
+  }
+  metadata
+}
+
 /// Parses value type.
-fn parse_value_type(node: &Node) -> Option<Value> {
+fn parse_value_type(node: &Node) -> Result<Option<Value>> {
+  if let Some(f) = parse_function_value(node) {
+    return Ok(Some(Value::Function(f)));
+  }
   if let Some(v) = parse_simple_value(node) {
-    return Some(Value::Simple(v));
+    return Ok(Some(Value::Simple(v)));
   }
-  if let Some(c) = parse_value_components(node) {
-    return Some(Value::Components(c));
+  if let Some(c) = parse_value_components(node)? {
+    return Ok(Some(Value::Components(c)));
   }
-  if let Some(l) = parse_value_list(node) {
-    return Some(Value::List(l));
+  if let Some(l) = parse_value_list(node)? {
+    return Ok(Some(Value::List(l)));
   }
-  None
+  Ok(None)
 }
 
 /// Parses value type from child node.
-fn parse_child_value_type(node: &Node, child_name: &str) -> Option<Value> {
+fn parse_child_value_type(node: &Node, child_name: &str) -> Result<Option<Value>> {
   if let Some(ref child_node) = node.children().find(|n| n.tag_name().name() == child_name) {
     parse_value_type(child_node)
+  } else {
+    Ok(None)
+  }
+}
+
+/// Parses function value, i.e. value node with `xsi:type` attribute referring to function type.
+fn parse_function_value(node: &Node) -> Option<Function> {
+  let value_node = node.children().find(|n| n.tag_name().name() == NODE_VALUE)?;
+  let typ = optional_xsi_type_attribute(&value_node)?;
+  let local_name = typ.rsplit(':').next().unwrap_or_default();
+  if local_name.eq_ignore_ascii_case(TYPE_FUNCTION) {
+    Some(Function {
+      typ: Some(typ),
+      text: optional_content(&value_node).map(|text| text.trim().to_string()).filter(|text| !text.is_empty()),
+    })
   } else {
     None
   }
@@ -306,60 +858,115 @@ fn parse_child_value_type(node: &Node, child_name: &str) -> Option<Value> {
 fn parse_simple_value(node: &Node) -> Option<Simple> {
   if let Some(ref value_node) = node.children().find(|n| n.tag_name().name() == NODE_VALUE) {
     let typ = optional_xsi_type_attribute(value_node);
-    let text = optional_content(value_node);
+    let text = value_content(value_node, typ.as_deref());
     let nil = optional_nil_attribute(value_node);
+    let unrecognized = unrecognized(value_node, &[(Some(XSI), ATTR_TYPE), (Some(XSI), ATTR_NIL)], &[]);
     return Some(match (typ.is_some(), text.is_some(), nil) {
       (true, false, false) => Simple {
         typ,
         text: Some("".to_string()),
         nil,
+        unrecognized,
       },
-      _ => Simple { typ, text, nil },
+      _ => Simple { typ, text, nil, unrecognized },
     });
   }
   None
 }
 
 /// Parses a collection of component values.
-fn parse_value_components(node: &Node) -> Option<Vec<Component>> {
+fn parse_value_components(node: &Node) -> Result<Option<Vec<Component>>> {
   let mut items = vec![];
   for ref component_node in node.children().filter(|n| n.tag_name().name() == NODE_COMPONENT) {
     items.push(Component {
       name: optional_attribute(component_node, ATTR_NAME),
-      value: parse_value_type(component_node),
+      value: parse_value_type(component_node)?,
       nil: optional_nil_attribute(component_node),
+      unrecognized: unrecognized(component_node, &[(None, ATTR_NAME), (Some(XSI), ATTR_NIL)], &[NODE_VALUE, NODE_COMPONENT, NODE_LIST]),
     })
   }
   if !items.is_empty() {
-    items.sort_by(|a, b| a.name.cmp(&b.name));
-    return Some(items);
+    return Ok(Some(items));
   }
-  None
+  Ok(None)
 }
 
 /// Parses a list of values.
-fn parse_value_list(node: &Node) -> Option<List> {
+fn parse_value_list(node: &Node) -> Result<Option<List>> {
   let mut items = vec![];
   if let Some(ref list_node) = node.children().find(|n| n.tag_name().name() == NODE_LIST) {
+    let unrecognized = unrecognized(list_node, &[(Some(XSI), ATTR_NIL)], &[NODE_ITEM]);
     if optional_nil_attribute(list_node) {
-      return Some(List::default());
+      return Ok(Some(List { unrecognized, ..List::default() }));
     }
     for ref item_node in list_node.children().filter(|n| n.tag_name().name() == NODE_ITEM) {
-      if let Some(value_type) = parse_value_type(item_node) {
+      if let Some(value_type) = parse_value_type(item_node)? {
         items.push(value_type)
       }
     }
-    return Some(List { items, nil: false });
+    return Ok(Some(List { items, nil: false, unrecognized }));
   }
-  None
+  Ok(None)
+}
+
+/// XML utility function that returns attributes and child elements of the node,
+/// other than specified known attributes (namespace and name) and known child elements.
+fn unrecognized(node: &Node, known_attributes: &[(Option<&str>, &str)], known_children: &[&str]) -> Unrecognized {
+  let attributes = node
+    .attributes()
+    .filter(|attr| !known_attributes.contains(&(attr.namespace(), attr.name())))
+    .map(|attr| UnrecognizedAttribute {
+      namespace: attr.namespace().map(|namespace| namespace.to_string()),
+      name: attr.name().to_string(),
+      value: attr.value().to_string(),
+    })
+    .collect();
+  let elements = node
+    .children()
+    .filter(|n| n.is_element() && !known_children.contains(&n.tag_name().name()))
+    .map(|n| element_text(&n))
+    .collect();
+  Unrecognized { attributes, elements }
+}
+
+/// XML utility function that returns the element in its original XML form,
+/// with all namespaces in scope declared on the element, so the text stays well-formed outside the document.
+fn element_text(node: &Node) -> String {
+  let text = &node.document().input_text()[node.range()];
+  let declared = declared_prefixes(text);
+  let declarations = node
+    .namespaces()
+    .filter(|namespace| namespace.uri() != XML && !declared.contains(&namespace.name()))
+    .map(|namespace| match namespace.name() {
+      Some(prefix) => format!(r#" xmlns:{}="{}""#, prefix, escape(namespace.uri())),
+      None => format!(r#" xmlns="{}""#, escape(namespace.uri())),
+    })
+    .collect::<String>();
+  let name_len = text[1..].find(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/').unwrap_or_default() + 1;
+  format!("{}{}{}", &text[..name_len], declarations, &text[name_len..])
+}
+
+/// XML utility function that returns prefixes of namespaces declared in the start tag of the element,
+/// `None` stands for the default namespace.
+fn declared_prefixes(text: &str) -> Vec<Option<&str>> {
+  let mut prefixes = vec![];
+  for token in Tokenizer::from(text) {
+    match token {
+      Ok(Token::Attribute { prefix, local, .. }) if prefix.as_str() == "xmlns" => prefixes.push(Some(local.as_str())),
+      Ok(Token::Attribute { prefix, local, .. }) if prefix.is_empty() && local.as_str() == "xmlns" => prefixes.push(None),
+      Ok(Token::Attribute { .. } | Token::ElementStart { .. }) => {}
+      _ => break,
+    }
+  }
+  prefixes
 }
 
 /// XML utility function that returns the value of the required attribute or an error.
-fn required_attribute(node: &Node, attr_name: &str) -> String {
+fn required_attribute(node: &Node, attr_name: &str) -> Result<String> {
   node
     .attribute(attr_name)
-    .unwrap_or_else(|| panic!("No mandatory attribute '{}' in node '{}'", attr_name, node.tag_name().name()))
-    .to_string()
+    .map(|attr_value| attr_value.to_string())
+    .ok_or_else(|| ParseError::new(node, format!("no mandatory attribute '{}' in node '{}'", attr_name, node.tag_name().name())))
 }
 
 /// XML utility function that returns the value of the optional attribute.
@@ -368,21 +975,61 @@ fn optional_attribute(node: &Node, attr_name: &str) -> Option<String> {
 }
 
 /// XML utility function that returns the value of the optional `xsi:type` attribute.
+/// The prefix of the type name is resolved against namespaces declared in the document
+/// and replaced with the canonical prefix, so `xs:decimal` becomes `xsd:decimal`.
 fn optional_xsi_type_attribute(node: &Node) -> Option<String> {
-  node.attribute((XSI, ATTR_TYPE)).map(|attr_value| attr_value.to_owned())
+  node.attribute((XSI, ATTR_TYPE)).map(|attr_value| resolve_type_name(node, attr_value.trim()))
+}
+
+/// Replaces the prefix of the type name with the canonical prefix of the namespace it refers to.
+/// Type names with unknown namespaces are returned unchanged.
+fn resolve_type_name(node: &Node, type_name: &str) -> String {
+  let (prefix, local_name) = match type_name.split_once(':') {
+    Some((prefix, local_name)) => (Some(prefix), local_name),
+    None => (None, type_name),
+  };
+  match node.lookup_namespace_uri(prefix).and_then(canonical_prefix) {
+    Some(canonical) => format!("{}:{}", canonical, local_name),
+    None => type_name.to_string(),
+  }
+}
+
+/// Returns the canonical prefix of the namespace used in type names.
+fn canonical_prefix(uri: &str) -> Option<&'static str> {
+  TYPE_NAMESPACES.iter().find(|namespace| (namespace.matches)(uri)).map(|namespace| namespace.prefix)
+}
+
+/// Returns `true` when the URI is the FEEL namespace of any DMN version, published over http or https.
+fn is_feel_namespace(uri: &str) -> bool {
+  let uri = uri.trim_end_matches('/');
+  uri
+    .strip_prefix("http://")
+    .or_else(|| uri.strip_prefix("https://"))
+    .is_some_and(|uri| uri.starts_with("www.omg.org/spec/DMN/") && uri.ends_with("/FEEL"))
+}
+
+/// Returns the type name with the conventional namespace prefix replaced by its canonical form,
+/// used for type names without namespace declarations, like those returned by the engine.
+pub fn canonical_type_name(type_name: &str) -> String {
+  if let Some((prefix, local_name)) = type_name.trim().split_once(':') {
+    if let Some(namespace) = TYPE_NAMESPACES.iter().find(|namespace| namespace.aliases.contains(&prefix)) {
+      return format!("{}:{}", namespace.prefix, local_name);
+    }
+  }
+  type_name.trim().to_string()
 }
 
 /// XML utility function that returns `true` when `xsi:nil="true"` attribute is specified.
 fn optional_nil_attribute(node: &Node) -> bool {
-  node.attribute((XSI, ATTR_NIL)).map_or(false, |v| v == "true")
+  node.attribute((XSI, ATTR_NIL)).is_some_and(|v| v == "true")
 }
 
 /// XML utility function that returns required textual content from the specified node.
-fn required_content(node: &Node) -> String {
+fn required_content(node: &Node) -> Result<String> {
   node
     .text()
-    .unwrap_or_else(|| panic!("No mandatory text content in node: '{}'", node.tag_name().name()))
-    .to_string()
+    .map(|text| text.to_string())
+    .ok_or_else(|| ParseError::new(node, format!("no mandatory text content in node '{}'", node.tag_name().name())))
 }
 
 /// XML utility function that returns optional textual content of the node.
@@ -390,7 +1037,33 @@ fn optional_content(node: &Node) -> Option<String> {
   node.text().map(|text| text.to_owned())
 }
 
+/// XML utility function that returns the textual content of the value node.
+/// All text and CDATA sections are concatenated, comments are skipped. Whitespace is preserved for strings,
+/// untyped values and when `xml:space="preserve"` is in effect, otherwise leading and trailing whitespace is removed.
+/// Returns `None` when the node has no text nor CDATA sections.
+fn value_content(node: &Node, typ: Option<&str>) -> Option<String> {
+  let texts = node.children().filter(|n| n.is_text()).filter_map(|n| n.text()).collect::<Vec<&str>>();
+  if texts.is_empty() && !node.document().input_text()[node.range()].contains("<![CDATA[") {
+    return None;
+  }
+  let text = texts.concat();
+  let preserve = match typ {
+    Some(typ) => typ.rsplit(':').next() == Some("string") || preserve_space(node),
+    None => true,
+  };
+  Some(if preserve { text } else { text.trim().to_string() })
+}
+
+/// XML utility function that returns `true` when `xml:space="preserve"` is specified on the node or any of its ancestors.
+fn preserve_space(node: &Node) -> bool {
+  node.ancestors().find_map(|n| n.attribute((XML, ATTR_SPACE))).is_some_and(|space| space == "preserve")
+}
+
 /// XML utility function that returns the required textual content from the optional child node.
-fn optional_child_required_content(node: &Node, child_name: &str) -> Option<String> {
-  node.children().find(|n| n.tag_name().name() == child_name).map(|child_node| required_content(&child_node))
+fn optional_child_required_content(node: &Node, child_name: &str) -> Result<Option<String>> {
+  node
+    .children()
+    .find(|n| n.tag_name().name() == child_name)
+    .map(|child_node| required_content(&child_node))
+    .transpose()
 }
@@ -0,0 +1,68 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Notification sent when the run is completed
+
+use crate::{COLOR_RESET, COLOR_YELLOW};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Payload of the notification sent when the run is completed.
+#[derive(Serialize)]
+pub struct NotificationPayload<'a> {
+  /// Short summary of the run, displayed by chat services like Slack.
+  pub text: String,
+  /// Total number of executed tests.
+  pub total: usize,
+  /// Number of tests that have passed.
+  pub success: usize,
+  /// Number of tests that have failed.
+  pub failure: usize,
+  /// Percentage of test cases that have passed.
+  pub test_cases_success_perc: f64,
+  /// Tests that have passed in the previous run and failed in the current run.
+  pub regressions: Vec<String>,
+  /// Location of the report file.
+  pub report: &'a str,
+  /// Metadata of the run.
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  pub metadata: &'a BTreeMap<String, String>,
+}
+
+/// Sends the notification to specified URL, failures are reported as warnings.
+pub fn send(client: &Client, notify_url: &str, payload: &NotificationPayload) {
+  match client.post(notify_url).json(payload).send().and_then(|response| response.error_for_status()) {
+    Ok(_) => outln!("\nNotification sent to: {}", notify_url),
+    Err(reason) => outln!("\n{1}sending notification to {2} failed with reason: {3}{0}", COLOR_RESET, COLOR_YELLOW, notify_url, reason),
+  }
+}
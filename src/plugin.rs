@@ -0,0 +1,188 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Comparators implemented as WASM plugins
+
+use crate::compare::{Comparator, Mismatch, MismatchClass};
+use crate::dto::ValueDto;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::{Arc, Mutex, OnceLock};
+use wasmi::{Config, Engine, Error, Instance, Linker, Memory, Module, Store, TypedFunc};
+
+/// Verdict returned by plugin when compared values are equal.
+const VERDICT_EQUAL: i32 = 1;
+/// Verdict returned by plugin when compared values differ.
+const VERDICT_DIFFERENT: i32 = 0;
+/// Fuel available for a single comparison, limiting the number of executed instructions.
+const FUEL_PER_COMPARISON: u64 = 100_000_000;
+
+/// Engine compiling and executing all WASM plugins.
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+
+/// Compiled WASM modules indexed by file name, every module is compiled once and shared by all comparators.
+static MODULES: Mutex<BTreeMap<String, Arc<Module>>> = Mutex::new(BTreeMap::new());
+
+/// Comparator implemented as WASM module.
+///
+/// The module must export:
+/// - `memory` - linear memory where compared values are written,
+/// - `alloc(len: i32) -> i32` - allocates the buffer of specified length and returns its address,
+/// - `compare(expected_ptr: i32, expected_len: i32, actual_ptr: i32, actual_len: i32) -> i32` - compares
+///   values serialized to JSON, returns `1` when values are equal, `0` when values differ,
+///   any other verdict leaves the decision to the next comparator.
+///
+/// Optionally, the module may export `dealloc(ptr: i32, len: i32)`, called when buffers are no longer used.
+/// The plugin is called for compared values at every level, including components and list items.
+/// Every comparison may execute a limited number of instructions, see [FUEL_PER_COMPARISON].
+/// Values are different when the plugin traps or runs out of fuel, the failure is reported as comparison error.
+pub struct WasmComparator {
+  /// Path to WASM module file.
+  file_name: String,
+  /// Instance of the WASM module, mutably borrowed while comparing values.
+  plugin: RefCell<Plugin>,
+}
+
+impl WasmComparator {
+  /// Loads the comparator from WASM module file, the module is compiled only when loaded for the first time.
+  pub fn new(file_name: &str) -> Result<Self, String> {
+    let module = compiled_module(file_name)?;
+    let plugin = Plugin::new(&module).map_err(|e| format!("loading WASM plugin '{}' failed with reason: {}", file_name, e))?;
+    Ok(Self {
+      file_name: file_name.to_string(),
+      plugin: RefCell::new(plugin),
+    })
+  }
+}
+
+/// Returns the engine executing WASM plugins, with fuel metering enabled.
+fn engine() -> &'static Engine {
+  ENGINE.get_or_init(|| {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+  })
+}
+
+/// Returns the compiled WASM module, compiling it when requested for the first time.
+fn compiled_module(file_name: &str) -> Result<Arc<Module>, String> {
+  let mut modules = MODULES.lock().unwrap_or_else(|e| panic!("loading WASM plugin failed with reason: {}", e));
+  if let Some(module) = modules.get(file_name) {
+    return Ok(Arc::clone(module));
+  }
+  let wasm = fs::read(file_name).map_err(|e| format!("reading WASM plugin '{}' failed with reason: {}", file_name, e))?;
+  let module = Module::new(engine(), &wasm).map_err(|e| format!("loading WASM plugin '{}' failed with reason: {}", file_name, e))?;
+  let module = Arc::new(module);
+  modules.insert(file_name.to_string(), Arc::clone(&module));
+  Ok(module)
+}
+
+impl WasmComparator {
+  /// Returns the verdict of the plugin, `Ok(None)` when the plugin does not decide about the values.
+  fn verdict(&self, actual: &ValueDto, expected: &ValueDto) -> Result<Option<bool>, Error> {
+    let (Ok(expected_json), Ok(actual_json)) = (serde_json::to_vec(expected), serde_json::to_vec(actual)) else {
+      return Ok(None);
+    };
+    match self.plugin.borrow_mut().compare(&expected_json, &actual_json)? {
+      VERDICT_EQUAL => Ok(Some(true)),
+      VERDICT_DIFFERENT => Ok(Some(false)),
+      _ => Ok(None),
+    }
+  }
+}
+
+impl Comparator for WasmComparator {
+  fn compare_values(&self, actual: &ValueDto, expected: &ValueDto) -> Option<bool> {
+    self.verdict(actual, expected).unwrap_or(Some(false))
+  }
+
+  fn values_mismatch(&self, actual: &ValueDto, expected: &ValueDto) -> Option<Mismatch> {
+    match self.verdict(actual, expected) {
+      Err(e) => Some(Mismatch::new(
+        MismatchClass::ComparisonError,
+        format!("calling WASM plugin '{}' failed with reason: {}", self.file_name, e),
+      )),
+      Ok(Some(false)) => Some(Mismatch::new(MismatchClass::Value, format!("different according to WASM plugin '{}'", self.file_name))),
+      Ok(_) => None,
+    }
+  }
+}
+
+/// Instantiated WASM module with its exported functions.
+struct Plugin {
+  store: Store<()>,
+  memory: Memory,
+  alloc: TypedFunc<i32, i32>,
+  dealloc: Option<TypedFunc<(i32, i32), ()>>,
+  compare: TypedFunc<(i32, i32, i32, i32), i32>,
+}
+
+impl Plugin {
+  /// Instantiates the compiled WASM module, the module may not import any functions.
+  fn new(module: &Module) -> Result<Self, Error> {
+    let mut store = Store::new(engine(), ());
+    store.set_fuel(FUEL_PER_COMPARISON)?;
+    let instance: Instance = Linker::<()>::new(engine()).instantiate(&mut store, module)?.start(&mut store)?;
+    let memory = instance.get_memory(&store, "memory").ok_or_else(|| Error::new("missing exported memory"))?;
+    let alloc = instance.get_typed_func(&store, "alloc")?;
+    let dealloc = instance.get_typed_func(&store, "dealloc").ok();
+    let compare = instance.get_typed_func(&store, "compare")?;
+    Ok(Self {
+      store,
+      memory,
+      alloc,
+      dealloc,
+      compare,
+    })
+  }
+
+  /// Writes both values into module's memory and returns the verdict of comparison.
+  fn compare(&mut self, expected: &[u8], actual: &[u8]) -> Result<i32, Error> {
+    self.store.set_fuel(FUEL_PER_COMPARISON)?;
+    let expected_ptr = self.write(expected)?;
+    let actual_ptr = self.write(actual)?;
+    let verdict = self.compare.call(&mut self.store, (expected_ptr, expected.len() as i32, actual_ptr, actual.len() as i32))?;
+    if let Some(dealloc) = &self.dealloc {
+      dealloc.call(&mut self.store, (expected_ptr, expected.len() as i32))?;
+      dealloc.call(&mut self.store, (actual_ptr, actual.len() as i32))?;
+    }
+    Ok(verdict)
+  }
+
+  /// Allocates the buffer in module's memory, writes the bytes and returns the address of the buffer.
+  fn write(&mut self, bytes: &[u8]) -> Result<i32, Error> {
+    let ptr = self.alloc.call(&mut self.store, bytes.len() as i32)?;
+    self.memory.write(&mut self.store, ptr as usize, bytes)?;
+    Ok(ptr)
+  }
+}
@@ -0,0 +1,236 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # CSV report writer and reader
+
+use crate::config::{ReportColumn, ReportFormat};
+use crate::context::{is_failed_result, FailedTest};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::mem;
+
+/// Single row of the test results report.
+pub struct ReportRow<'a> {
+  /// Tests root directory containing the test file.
+  pub root: &'a str,
+  /// Directory of the test file, displayed as configured.
+  pub directory: &'a str,
+  /// Test file name without extension.
+  pub file: &'a str,
+  /// Identifier of the test case.
+  pub test_case_id: &'a str,
+  /// Identifier of the test (test case identifier with result node index).
+  pub test_id: &'a str,
+  /// Name of the test case, empty when not specified.
+  pub test_case_name: &'a str,
+  /// Description of the test case, empty when not specified.
+  pub description: &'a str,
+  /// Test result.
+  pub result: &'a str,
+  /// Remarks, reported only for failures.
+  pub remarks: &'a str,
+  /// Duration of the evaluation in microseconds.
+  pub duration_us: u128,
+  /// Failure category, empty for successful tests.
+  pub category: &'a str,
+  /// Failure class, empty for successful tests.
+  pub failure_class: &'a str,
+  /// Version of the DMN specification of the test file.
+  pub spec_version: &'a str,
+}
+
+/// Writer of the test results report in configurable CSV format.
+pub struct ReportWriter {
+  /// Buffered writer of the report file.
+  writer: BufWriter<File>,
+  /// Format of the report.
+  format: ReportFormat,
+  /// Metadata of the run, written in every row as comma separated `key=value` pairs,
+  /// with backslashes, commas and equal signs in keys and values escaped with backslash.
+  /// Like any other field, it is quoted when it contains the delimiter.
+  run_metadata: String,
+}
+
+impl ReportWriter {
+  /// Creates the report file and writes the header row when configured.
+  pub fn new(file_name: &str, format: ReportFormat, run_metadata: &BTreeMap<String, String>) -> Self {
+    let file = File::create(file_name).unwrap_or_else(|e| panic!("creating output file {} failed with reason: {}", file_name, e));
+    let mut report_writer = Self {
+      writer: BufWriter::new(file),
+      format,
+      run_metadata: run_metadata
+        .iter()
+        .map(|(key, value)| format!("{}={}", escape_metadata(key), escape_metadata(value)))
+        .collect::<Vec<String>>()
+        .join(", "),
+    };
+    if report_writer.format.header {
+      let names = report_writer.format.columns.iter().map(|column| column.name().to_string()).collect::<Vec<String>>();
+      report_writer.write_fields(&names);
+    }
+    report_writer
+  }
+
+  /// Writes a single row into the report.
+  pub fn write_row(&mut self, row: &ReportRow) {
+    let fields = self
+      .format
+      .columns
+      .iter()
+      .map(|column| match column {
+        ReportColumn::Directory => row.directory.to_string(),
+        ReportColumn::File => row.file.to_string(),
+        ReportColumn::TestCaseId => row.test_case_id.to_string(),
+        ReportColumn::TestId => row.test_id.to_string(),
+        ReportColumn::TestCaseName => row.test_case_name.to_string(),
+        ReportColumn::Description => row.description.to_string(),
+        ReportColumn::Result => row.result.to_string(),
+        ReportColumn::Remarks => row.remarks.to_string(),
+        ReportColumn::Duration => row.duration_us.to_string(),
+        ReportColumn::Category => row.category.to_string(),
+        ReportColumn::FailureClass => row.failure_class.to_string(),
+        ReportColumn::SpecVersion => row.spec_version.to_string(),
+        ReportColumn::RunMetadata => self.run_metadata.clone(),
+        ReportColumn::Root => row.root.to_string(),
+      })
+      .collect::<Vec<String>>();
+    self.write_fields(&fields);
+  }
+
+  /// Flushes buffered rows into the report file.
+  pub fn flush(&mut self) {
+    self.writer.flush().unwrap_or_else(|e| panic!("flushing CSV report failed with reason: {}", e));
+  }
+
+  /// Writes fields delimited and quoted according to configured format,
+  /// fields containing delimiters, quotes or line breaks are always quoted.
+  fn write_fields(&mut self, fields: &[String]) {
+    let line = fields
+      .iter()
+      .map(|field| {
+        let delimited = !self.format.delimiter.is_empty() && field.contains(&self.format.delimiter);
+        if self.format.quoted || delimited || field.contains(['"', '\r', '\n']) {
+          quote(field)
+        } else {
+          field.to_string()
+        }
+      })
+      .collect::<Vec<String>>()
+      .join(&self.format.delimiter);
+    writeln!(self.writer, "{}", line).unwrap_or_else(|e| panic!("writing line to CSV report failed with reason: {}", e));
+  }
+}
+
+/// Escapes backslashes, commas and equal signs in keys and values of run metadata.
+fn escape_metadata(text: &str) -> String {
+  text.replace('\\', "\\\\").replace(',', "\\,").replace('=', "\\=")
+}
+
+/// Returns the field enclosed in quotes, with quotes inside the field doubled.
+pub fn quote(field: &str) -> String {
+  format!(r#""{}""#, field.replace('"', r#""""#))
+}
+
+/// Reads tests reported as failed from the report file written in specified format,
+/// tests root directories are read only when the report contains the root column.
+pub fn read_failures(file_name: &str, format: &ReportFormat) -> Result<BTreeSet<FailedTest>, String> {
+  let position = |column: fn(&ReportColumn) -> bool| format.columns.iter().position(column);
+  let (Some(directory), Some(file), Some(test_id), Some(result)) = (
+    position(|column| matches!(column, ReportColumn::Directory)),
+    position(|column| matches!(column, ReportColumn::File)),
+    position(|column| matches!(column, ReportColumn::TestId)),
+    position(|column| matches!(column, ReportColumn::Result)),
+  ) else {
+    return Err(format!("report file {} does not contain directory, file, test_id and result columns", file_name));
+  };
+  let root = position(|column| matches!(column, ReportColumn::Root));
+  let content = fs::read_to_string(file_name).map_err(|e| format!("reading report file {} failed with reason: {}", file_name, e))?;
+  let records = read_records(&content, format);
+  Ok(
+    records
+      .into_iter()
+      .skip(usize::from(format.header))
+      .filter(|fields| fields.get(result).is_some_and(|result| is_failed_result(result)))
+      .filter_map(|fields| {
+        Some(FailedTest {
+          root: root.and_then(|root| fields.get(root).cloned()).unwrap_or_default(),
+          directory: fields.get(directory)?.clone(),
+          file: fields.get(file)?.clone(),
+          test_id: fields.get(test_id)?.clone(),
+        })
+      })
+      .collect(),
+  )
+}
+
+/// Splits the content of the report into records of fields, quoted fields may contain delimiters and line breaks,
+/// fields are quoted in reports written without quotes when they contain delimiters, quotes or line breaks.
+fn read_records(content: &str, format: &ReportFormat) -> Vec<Vec<String>> {
+  let mut records = vec![];
+  let mut fields = vec![];
+  let mut field = String::new();
+  let mut in_quotes = false;
+  let mut rest = content;
+  while let Some(ch) = rest.chars().next() {
+    if in_quotes {
+      if rest.starts_with(r#""""#) {
+        field.push('"');
+        rest = &rest[2..];
+        continue;
+      }
+      if ch == '"' {
+        in_quotes = false;
+      } else {
+        field.push(ch);
+      }
+    } else if ch == '"' && (format.quoted || field.is_empty()) {
+      in_quotes = true;
+    } else if rest.starts_with(&format.delimiter) && !format.delimiter.is_empty() {
+      fields.push(mem::take(&mut field));
+      rest = &rest[format.delimiter.len()..];
+      continue;
+    } else if ch == '\n' {
+      fields.push(mem::take(&mut field));
+      records.push(mem::take(&mut fields));
+    } else if ch != '\r' {
+      field.push(ch);
+    }
+    rest = &rest[ch.len_utf8()..];
+  }
+  if !field.is_empty() || !fields.is_empty() {
+    fields.push(field);
+    records.push(fields);
+  }
+  records
+}
@@ -0,0 +1,195 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Sampling of resources used by engine process
+
+use crate::{COLOR_RESET, COLOR_YELLOW};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Default interval between samples in milliseconds.
+pub const DEFAULT_SAMPLING_INTERVAL: u64 = 500;
+
+/// Returns the number of clock ticks per second used in `/proc/<pid>/stat` (`USER_HZ`).
+fn clock_ticks_per_second() -> u64 {
+  #[cfg(unix)]
+  let ticks = sysconf(libc::_SC_CLK_TCK);
+  #[cfg(not(unix))]
+  let ticks = None;
+  ticks.unwrap_or(100)
+}
+
+/// Returns the size of memory page in bytes, used for resident set size in `/proc/<pid>/statm`.
+fn page_size() -> u64 {
+  #[cfg(unix)]
+  let size = sysconf(libc::_SC_PAGESIZE);
+  #[cfg(not(unix))]
+  let size = None;
+  size.unwrap_or(4096)
+}
+
+/// Returns the value of system configuration variable, `None` when it is not available.
+#[cfg(unix)]
+fn sysconf(name: libc::c_int) -> Option<u64> {
+  // SAFETY: reading the system configuration variable has no side effects
+  u64::try_from(unsafe { libc::sysconf(name) }).ok().filter(|value| *value > 0)
+}
+
+/// Source of resource usage of the engine.
+pub enum ResourceSource {
+  /// Process with specified identifier.
+  Process(u32),
+  /// Control group (version 2) with specified path, like `/sys/fs/cgroup/system.slice/engine.service`.
+  Cgroup(String),
+}
+
+impl ResourceSource {
+  /// Returns the total CPU time consumed so far.
+  fn cpu_time(&self) -> Option<Duration> {
+    match self {
+      Self::Process(pid) => {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // the process name may contain spaces, fields are counted after the closing parenthesis
+        let fields = stat.rsplit_once(')')?.1.split_whitespace().collect::<Vec<&str>>();
+        let user_ticks = fields.get(11)?.parse::<u64>().ok()?;
+        let system_ticks = fields.get(12)?.parse::<u64>().ok()?;
+        Some(Duration::from_millis((user_ticks + system_ticks) * 1000 / clock_ticks_per_second()))
+      }
+      Self::Cgroup(path) => {
+        let stat = fs::read_to_string(format!("{}/cpu.stat", path)).ok()?;
+        let usage = stat.lines().find_map(|line| line.strip_prefix("usage_usec "))?;
+        Some(Duration::from_micros(usage.trim().parse().ok()?))
+      }
+    }
+  }
+
+  /// Returns the memory currently used in bytes, resident set size of the process or memory used by control group.
+  fn memory(&self) -> Option<u64> {
+    match self {
+      Self::Process(pid) => {
+        let statm = fs::read_to_string(format!("/proc/{}/statm", pid)).ok()?;
+        Some(statm.split_whitespace().nth(1)?.parse::<u64>().ok()? * page_size())
+      }
+      Self::Cgroup(path) => fs::read_to_string(format!("{}/memory.current", path)).ok()?.trim().parse().ok(),
+    }
+  }
+}
+
+/// Single sample of resource usage.
+struct Sample {
+  /// CPU usage in percents of a single core, since the previous sample.
+  cpu: f64,
+  /// Used memory in bytes.
+  memory: u64,
+}
+
+/// Sampler of resources used by engine, running in a separate thread.
+pub struct ResourceSampler {
+  /// Flag signalling the sampling thread to stop.
+  stop: Arc<AtomicBool>,
+  /// Handle of the sampling thread returning collected samples.
+  handle: JoinHandle<Vec<Sample>>,
+}
+
+impl ResourceSampler {
+  /// Starts sampling resources of the engine in specified interval.
+  /// Returns `None` and displays a warning, when resources of the engine can not be read.
+  pub fn start(source: ResourceSource, interval: Duration) -> Option<Self> {
+    let Some(mut previous_cpu_time) = source.cpu_time() else {
+      let name = match &source {
+        ResourceSource::Process(pid) => format!("process {}", pid),
+        ResourceSource::Cgroup(path) => format!("control group {}", path),
+      };
+      outln!("{1}reading resources of engine {2} failed, resources are not sampled{0}", COLOR_RESET, COLOR_YELLOW, name);
+      return None;
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::clone(&stop);
+    let handle = thread::spawn(move || {
+      let mut samples = vec![];
+      let mut previous_time = Instant::now();
+      while !stopped.load(Ordering::Relaxed) {
+        thread::sleep(interval);
+        let (Some(cpu_time), Some(memory)) = (source.cpu_time(), source.memory()) else {
+          // the engine has terminated
+          break;
+        };
+        let elapsed = previous_time.elapsed();
+        previous_time = Instant::now();
+        let cpu = cpu_time.saturating_sub(previous_cpu_time).as_secs_f64() * 100.0 / elapsed.as_secs_f64();
+        previous_cpu_time = cpu_time;
+        samples.push(Sample { cpu, memory });
+      }
+      samples
+    });
+    Some(Self { stop, handle })
+  }
+
+  /// Stops sampling and returns collected samples.
+  pub fn stop(self) -> ResourceUsage {
+    self.stop.store(true, Ordering::Relaxed);
+    ResourceUsage {
+      samples: self.handle.join().unwrap_or_else(|_| panic!("sampling engine resources failed")),
+    }
+  }
+}
+
+/// Resources used by engine, sampled during the run.
+pub struct ResourceUsage {
+  /// Collected samples.
+  samples: Vec<Sample>,
+}
+
+impl ResourceUsage {
+  /// Displays average and peak usage of CPU and memory.
+  pub fn display(&self) {
+    let samples = &self.samples;
+    if samples.is_empty() {
+      return;
+    }
+    let count = samples.len() as f64;
+    let cpu_average = samples.iter().map(|sample| sample.cpu).sum::<f64>() / count;
+    let cpu_peak = samples.iter().map(|sample| sample.cpu).fold(0.0, f64::max);
+    let memory_average = samples.iter().map(|sample| sample.memory as f64).sum::<f64>() / count / 1_048_576.0;
+    let memory_peak = samples.iter().map(|sample| sample.memory).max().unwrap_or_default() as f64 / 1_048_576.0;
+    outln!("\nEngine resources ({} samples):", samples.len());
+    outln!("┌─────────────┬────────────┬────────────┐");
+    outln!("│    Resource │    Average │       Peak │");
+    outln!("├─────────────┼────────────┼────────────┤");
+    outln!("│     CPU (%) │ {:>10.1} │ {:>10.1} │", cpu_average, cpu_peak);
+    outln!("│ Memory (MB) │ {:>10.1} │ {:>10.1} │", memory_average, memory_peak);
+    outln!("└─────────────┴────────────┴────────────┘");
+  }
+}
@@ -0,0 +1,137 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Selection of test files by include and exclude patterns
+
+use crate::config::ConfigurationParams;
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+
+/// Prefix of patterns given as globs, patterns without prefix are regular expressions.
+const GLOB_PREFIX: &str = "glob:";
+
+/// Pattern matching the path of test file relative to test cases directory.
+enum FilePattern {
+  /// Regular expression, matching when found anywhere in the path.
+  Regex(Regex),
+  /// Glob matching the whole path, wildcards do not match path separators.
+  Glob(GlobMatcher),
+}
+
+impl FilePattern {
+  /// Parses the pattern, patterns prefixed with `glob:` are globs, other patterns are regular expressions.
+  fn parse(pattern: &str) -> Self {
+    Self::try_parse(pattern).unwrap_or_else(|reason| panic!("{}", reason))
+  }
+
+  /// Parses the pattern, returns the reason when the pattern is not a valid glob or regular expression.
+  fn try_parse(pattern: &str) -> Result<Self, String> {
+    match pattern.strip_prefix(GLOB_PREFIX) {
+      Some(glob) => GlobBuilder::new(glob)
+        .literal_separator(true)
+        .build()
+        .map(|glob| Self::Glob(glob.compile_matcher()))
+        .map_err(|e| format!("parsing search glob '{}' failed with reason: {}", glob, e)),
+      None => Regex::new(pattern)
+        .map(Self::Regex)
+        .map_err(|e| format!("parsing search pattern '{}' failed with reason: {}", pattern, e)),
+    }
+  }
+
+  /// Returns `true` when the pattern matches specified path.
+  fn is_match(&self, path: &str) -> bool {
+    match self {
+      Self::Regex(regex) => regex.is_match(path),
+      Self::Glob(glob) => glob.is_match(path),
+    }
+  }
+}
+
+/// Checks if the pattern of included or excluded test files is valid.
+pub fn check_pattern(pattern: &str) -> Result<(), String> {
+  FilePattern::try_parse(pattern).map(|_| ())
+}
+
+/// Include and exclude patterns selecting test files.
+#[derive(Default)]
+pub struct FileSelection {
+  /// Search globs, test files must match any of them, all test files are matched when empty.
+  globs: Vec<FilePattern>,
+  /// Patterns of included test files, all test files are included when empty.
+  include: Vec<FilePattern>,
+  /// Patterns of excluded test files, applied after include patterns.
+  exclude: Vec<FilePattern>,
+  /// Selected compliance levels, test files of all compliance levels are selected when empty.
+  compliance_levels: Vec<u8>,
+}
+
+impl FileSelection {
+  /// Creates the selection from configured search globs, include and exclude patterns.
+  pub fn new(config: &ConfigurationParams) -> Self {
+    Self {
+      globs: config
+        .file_search_globs
+        .iter()
+        .map(|glob| FilePattern::parse(&format!("{}{}", GLOB_PREFIX, glob)))
+        .collect(),
+      include: config.include.iter().map(|pattern| FilePattern::parse(pattern)).collect(),
+      exclude: config.exclude.iter().map(|pattern| FilePattern::parse(pattern)).collect(),
+      compliance_levels: config.compliance_levels.clone(),
+    }
+  }
+
+  /// Returns `true` when the test file, given by its path relative to test cases directory,
+  /// matches any of the search globs, any of the include patterns and none of the exclude patterns.
+  pub fn is_selected(&self, relative_path: &str) -> bool {
+    let matches_any = |patterns: &[FilePattern]| patterns.is_empty() || patterns.iter().any(|pattern| pattern.is_match(relative_path));
+    matches_any(&self.globs) && matches_any(&self.include) && !self.exclude.iter().any(|pattern| pattern.is_match(relative_path))
+  }
+
+  /// Returns `true` when the test file, given by its path relative to test cases directory, belongs to any
+  /// of the selected compliance levels, being placed in `compliance-level-N` directory or labelled with `Compliance Level N`.
+  /// Labels are read only for test files placed outside of compliance level directories.
+  pub fn has_compliance_level(&self, relative_path: &str, labels: impl FnOnce() -> Vec<String>) -> bool {
+    if self.compliance_levels.is_empty()
+      || self
+        .compliance_levels
+        .iter()
+        .any(|level| relative_path.split('/').any(|segment| segment == format!("compliance-level-{}", level)))
+    {
+      return true;
+    }
+    let labels = labels();
+    self
+      .compliance_levels
+      .iter()
+      .any(|level| labels.iter().any(|label| label.to_lowercase() == format!("compliance level {}", level)))
+  }
+}
@@ -0,0 +1,273 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # XML serializer for test cases
+
+use crate::model::{Component, ExtensionElement, InputNode, List, ResultNode, TestCase, TestCaseType, TestCases, Unrecognized, Value};
+
+/// Default namespace of test case files.
+const TESTCASE_NAMESPACE: &str = "http://www.omg.org/spec/DMN/20160719/testcase";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema";
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const FEEL: &str = "https://www.omg.org/spec/DMN/20191111/FEEL/";
+
+/// Serializes test cases into test file content.
+/// The content parsed with [parse_test_file](crate::model::parse_test_file) gives the same test cases.
+pub fn serialize(test_cases: &TestCases) -> String {
+  let mut out = String::new();
+  out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+  out.push_str(&format!(
+    r#"<testCases xmlns="{}" xmlns:xsd="{}" xmlns:xsi="{}" xmlns:feel="{}">"#,
+    escape(test_cases.namespace.as_deref().unwrap_or(TESTCASE_NAMESPACE)),
+    XSD,
+    XSI,
+    FEEL
+  ));
+  out.push('\n');
+  if let Some(model_name) = &test_cases.model_name {
+    out.push_str(&format!("  <modelName>{}</modelName>\n", escape(model_name)));
+  }
+  if !test_cases.labels.is_empty() {
+    out.push_str("  <labels>\n");
+    for label in &test_cases.labels {
+      out.push_str(&format!("    <label>{}</label>\n", escape(label)));
+    }
+    out.push_str("  </labels>\n");
+  }
+  for test_case in &test_cases.test_cases {
+    write_test_case(&mut out, test_case);
+  }
+  out.push_str("</testCases>\n");
+  out
+}
+
+/// Writes single test case.
+fn write_test_case(out: &mut String, test_case: &TestCase) {
+  out.push_str("  <testCase");
+  write_optional_attribute(out, "id", &test_case.id);
+  write_optional_attribute(out, "name", &test_case.name);
+  if test_case.typ != TestCaseType::Decision {
+    out.push_str(&format!(r#" type="{}""#, test_case.typ));
+  }
+  write_optional_attribute(out, "invocableName", &test_case.invocable_name);
+  out.push_str(">\n");
+  if let Some(description) = &test_case.description {
+    out.push_str(&format!("    <description>{}</description>\n", escape(description)));
+  }
+  if !test_case.extension_elements.is_empty() {
+    out.push_str("    ");
+    write_extension_elements(out, &test_case.extension_elements);
+    out.push('\n');
+  }
+  for input_node in &test_case.input_nodes {
+    write_input_node(out, input_node);
+  }
+  for result_node in &test_case.result_nodes {
+    write_result_node(out, result_node);
+  }
+  out.push_str("  </testCase>\n");
+}
+
+/// Writes input node.
+fn write_input_node(out: &mut String, input_node: &InputNode) {
+  out.push_str(&format!(r#"    <inputNode name="{}""#, escape(&input_node.name)));
+  write_optional_attribute(out, "namespace", &input_node.namespace);
+  out.push('>');
+  if let Some(value) = &input_node.value {
+    write_value(out, value);
+  }
+  out.push_str("</inputNode>\n");
+}
+
+/// Writes result node.
+fn write_result_node(out: &mut String, result_node: &ResultNode) {
+  out.push_str(&format!(r#"    <resultNode name="{}""#, escape(&result_node.name)));
+  write_optional_attribute(out, "namespace", &result_node.namespace);
+  if result_node.error_result {
+    out.push_str(r#" errorResult="true""#);
+  }
+  if result_node.typ != TestCaseType::Decision {
+    out.push_str(&format!(r#" type="{}""#, result_node.typ));
+  }
+  write_optional_attribute(out, "cast", &result_node.cast);
+  out.push('>');
+  write_extension_elements(out, &result_node.extension_elements);
+  if let Some(expected) = &result_node.expected {
+    out.push_str("<expected>");
+    write_value(out, expected);
+    out.push_str("</expected>");
+  }
+  if let Some(computed) = &result_node.computed {
+    out.push_str("<computed>");
+    write_value(out, computed);
+    out.push_str("</computed>");
+  }
+  out.push_str("</resultNode>\n");
+}
+
+/// Writes extension elements, nothing is written when there are no elements.
+fn write_extension_elements(out: &mut String, extension_elements: &[ExtensionElement]) {
+  if extension_elements.is_empty() {
+    return;
+  }
+  out.push_str("<extensionElements>");
+  for element in extension_elements {
+    out.push_str(&format!("<{}", element.name));
+    for (name, value) in &element.attributes {
+      out.push_str(&format!(r#" {}="{}""#, name, escape(value)));
+    }
+    match &element.text {
+      Some(text) => out.push_str(&format!(">{}</{}>", escape(text), element.name)),
+      None => out.push_str("/>"),
+    }
+  }
+  out.push_str("</extensionElements>");
+}
+
+/// Writes the content of the value type node.
+fn write_value(out: &mut String, value: &Value) {
+  match value {
+    Value::Simple(simple) => {
+      out.push_str("<value");
+      write_type_attribute(out, &simple.typ);
+      if simple.nil {
+        out.push_str(r#" xsi:nil="true""#);
+      }
+      write_unrecognized_attributes(out, &simple.unrecognized);
+      let text = simple.text.as_deref().filter(|_| !simple.nil).unwrap_or_default();
+      if text.is_empty() && simple.unrecognized.elements.is_empty() {
+        out.push_str("/>");
+      } else {
+        out.push_str(&format!(">{}", escape(text)));
+        write_unrecognized_elements(out, &simple.unrecognized);
+        out.push_str("</value>");
+      }
+    }
+    Value::Components(components) => {
+      for component in components {
+        write_component(out, component);
+      }
+    }
+    Value::List(list) => write_list(out, list),
+    Value::Function(function) => {
+      out.push_str("<value");
+      write_type_attribute(out, &function.typ);
+      match &function.text {
+        Some(text) => out.push_str(&format!(">{}</value>", escape(text))),
+        None => out.push_str("/>"),
+      }
+    }
+  }
+}
+
+/// Writes component of the complex value.
+fn write_component(out: &mut String, component: &Component) {
+  out.push_str("<component");
+  write_optional_attribute(out, "name", &component.name);
+  if component.nil {
+    out.push_str(r#" xsi:nil="true""#);
+  }
+  write_unrecognized_attributes(out, &component.unrecognized);
+  if component.value.is_none() && component.unrecognized.elements.is_empty() {
+    out.push_str("/>");
+  } else {
+    out.push('>');
+    if let Some(value) = &component.value {
+      write_value(out, value);
+    }
+    write_unrecognized_elements(out, &component.unrecognized);
+    out.push_str("</component>");
+  }
+}
+
+/// Writes list of values.
+fn write_list(out: &mut String, list: &List) {
+  out.push_str("<list");
+  if list.nil {
+    out.push_str(r#" xsi:nil="true""#);
+  }
+  write_unrecognized_attributes(out, &list.unrecognized);
+  if list.items.is_empty() && list.unrecognized.elements.is_empty() {
+    out.push_str("/>");
+    return;
+  }
+  out.push('>');
+  for item in &list.items {
+    out.push_str("<item>");
+    write_value(out, item);
+    out.push_str("</item>");
+  }
+  write_unrecognized_elements(out, &list.unrecognized);
+  out.push_str("</list>");
+}
+
+/// Writes unrecognized attributes, namespaced attributes are written with their own namespace declarations.
+fn write_unrecognized_attributes(out: &mut String, unrecognized: &Unrecognized) {
+  for (index, attribute) in unrecognized.attributes.iter().enumerate() {
+    match &attribute.namespace {
+      Some(namespace) => out.push_str(&format!(
+        r#" xmlns:ns{0}="{1}" ns{0}:{2}="{3}""#,
+        index,
+        escape(namespace),
+        attribute.name,
+        escape(&attribute.value)
+      )),
+      None => out.push_str(&format!(r#" {}="{}""#, attribute.name, escape(&attribute.value))),
+    }
+  }
+}
+
+/// Writes unrecognized child elements in their original form.
+fn write_unrecognized_elements(out: &mut String, unrecognized: &Unrecognized) {
+  for element in &unrecognized.elements {
+    out.push_str(element);
+  }
+}
+
+/// Writes `xsi:type` attribute when the type is specified.
+fn write_type_attribute(out: &mut String, typ: &Option<String>) {
+  if let Some(typ) = typ {
+    out.push_str(&format!(r#" xsi:type="{}""#, escape(typ)));
+  }
+}
+
+/// Writes the attribute when the value is specified.
+fn write_optional_attribute(out: &mut String, name: &str, value: &Option<String>) {
+  if let Some(value) = value {
+    out.push_str(&format!(r#" {}="{}""#, name, escape(value)));
+  }
+}
+
+/// Escapes special XML characters in text and attribute values.
+pub fn escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
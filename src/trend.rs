@@ -0,0 +1,115 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Trend report across the last runs
+
+use crate::history::{last_runs, status_changes};
+use crate::humanize;
+use crate::report::quote;
+use crate::{COLOR_GREEN, COLOR_RED, COLOR_RESET, COLOR_YELLOW};
+
+/// Displays pass rate and latency of the last runs stored in history database,
+/// followed by the list of tests that have changed their status.
+/// In CSV format, status changes follow the runs as a second table, separated by an empty line.
+pub fn report(history_db: &str, count: usize, csv: bool) {
+  let runs = last_runs(history_db, count);
+  let changes = runs.first().map(|run| status_changes(history_db, run.id)).unwrap_or_default();
+  if csv {
+    println!(r#""run","started","total","success","failure","pass_rate","average_us""#);
+    for run in &runs {
+      let total = run.success_count + run.failure_count;
+      println!(
+        r#""{}","{}","{}","{}","{}","{:.2}","{:.0}""#,
+        run.id,
+        run.started_at,
+        total,
+        run.success_count,
+        run.failure_count,
+        pass_rate(run.success_count, total),
+        run.average_duration_us
+      );
+    }
+    if !changes.is_empty() {
+      println!();
+      println!(r#""run","test","previous","current""#);
+      for change in &changes {
+        println!(r#""{}",{},"{}","{}""#, change.run_id, quote(&change.test), change.previous, change.current);
+      }
+    }
+    return;
+  }
+  outln!("\nTrend of the last {} run(s):", runs.len());
+  outln!("┌────────┬─────────────────────┬─────────┬─────────┬───────────┬──────────────┐");
+  outln!("│    Run │ Started             │   Total │   Fails │ Pass rate │ Average time │");
+  outln!("├────────┼─────────────────────┼─────────┼─────────┼───────────┼──────────────┤");
+  for run in &runs {
+    let total = run.success_count + run.failure_count;
+    outln!(
+      "│ {:>6} │ {:<19} │ {:>7} │ {:>7} │ {:>8.2}% │ {:>9.0} µs │",
+      run.id,
+      run.started_at,
+      humanize::count(total),
+      humanize::count(run.failure_count),
+      pass_rate(run.success_count, total),
+      run.average_duration_us
+    );
+  }
+  outln!("└────────┴─────────────────────┴─────────┴─────────┴───────────┴──────────────┘");
+  if !changes.is_empty() {
+    outln!("\nTests that changed status:");
+    for change in &changes {
+      let color = match change.current.as_str() {
+        "SUCCESS" => COLOR_GREEN,
+        "WARNING" => COLOR_YELLOW,
+        _ => COLOR_RED,
+      };
+      outln!(
+        "  run {:>6}: {} {} -> {}{}{}",
+        change.run_id,
+        change.test,
+        change.previous,
+        color,
+        change.current,
+        COLOR_RESET
+      );
+    }
+  }
+}
+
+/// Calculates the pass rate in percents.
+fn pass_rate(success: usize, total: usize) -> f64 {
+  if total > 0 {
+    (success * 100) as f64 / total as f64
+  } else {
+    0.0
+  }
+}
@@ -0,0 +1,297 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Interactive terminal interface with live progress and failures of tests
+
+use crate::console::{self, Target};
+use crate::context::TestInfo;
+use crate::interrupt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::cursor::Show;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Stdout};
+use std::panic;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Interval of refreshing the interface while tests are executed.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Events sent from the testing process to the interface.
+enum TuiEvent {
+  /// Execution of tests from the test file has started.
+  File(String),
+  /// Test has been executed, failure remarks are given for failed tests.
+  Result(String, Option<String>),
+  /// Unified diff of expected and actual value of the test.
+  Diff(String, String),
+  /// All tests have been executed.
+  Finished,
+}
+
+/// Interactive interface displayed in place of the console output while tests are executed.
+pub struct Tui {
+  /// Sender of events to the interface thread.
+  events: Sender<TuiEvent>,
+  /// Handle of the interface thread.
+  handle: JoinHandle<()>,
+  /// Destination of the console output before the interface was started.
+  console: Target,
+}
+
+impl Tui {
+  /// Returns `true` when the interface can be displayed, i.e. both standard input and output are terminals.
+  pub fn is_available() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+  }
+
+  /// Starts the interface for the expected number of tests.
+  /// The console output is suppressed until the interface is closed,
+  /// the terminal is restored when the runner panics.
+  pub fn start(expected_count: usize) -> Self {
+    let console = console::redirect(Target::Null);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+      restore_terminal();
+      hook(info);
+    }));
+    let (events, receiver) = channel();
+    let handle = thread::spawn(move || run(receiver, expected_count));
+    Self { events, handle, console }
+  }
+
+  /// Displays the path of the test file being executed.
+  pub fn set_file(&self, file_path: &str) {
+    let _ = self.events.send(TuiEvent::File(file_path.to_string()));
+  }
+
+  /// Adds the result of a single test.
+  pub fn add_result(&self, test: &TestInfo, failure: Option<&str>) {
+    let _ = self.events.send(TuiEvent::Result(test.name(), failure.map(str::to_string)));
+  }
+
+  /// Adds the unified diff of expected and actual value of the test.
+  pub fn add_diff(&self, test: &TestInfo, diff: &str) {
+    let _ = self.events.send(TuiEvent::Diff(test.name(), diff.to_string()));
+  }
+
+  /// Waits until the user closes the interface and restores the console output.
+  /// The interface is closed immediately when the run was interrupted.
+  pub fn finish(self) {
+    let _ = self.events.send(TuiEvent::Finished);
+    let _ = self.handle.join();
+    // the default panic hook is restored
+    let _ = panic::take_hook();
+    console::redirect(self.console);
+  }
+}
+
+/// State of the interface.
+#[derive(Default)]
+struct State {
+  /// Expected number of tests.
+  expected_count: usize,
+  /// Number of passed tests.
+  success_count: usize,
+  /// Path of the test file being executed.
+  file_path: String,
+  /// Names of failed tests with failure remarks.
+  failures: Vec<(String, String)>,
+  /// Unified diffs indexed by test name.
+  diffs: HashMap<String, String>,
+  /// Selected failure.
+  selected: ListState,
+  /// Scroll offset of the details pane.
+  scroll: u16,
+  /// Flag indicating if all tests have been executed.
+  finished: bool,
+}
+
+impl State {
+  /// Updates the state with the received event.
+  fn update(&mut self, event: TuiEvent) {
+    match event {
+      TuiEvent::File(file_path) => self.file_path = file_path,
+      TuiEvent::Result(_, None) => self.success_count += 1,
+      TuiEvent::Result(name, Some(remarks)) => {
+        self.failures.push((name, remarks));
+        if self.selected.selected().is_none() {
+          self.selected.select(Some(0));
+        }
+      }
+      TuiEvent::Diff(name, diff) => {
+        self.diffs.insert(name, diff);
+      }
+      TuiEvent::Finished => self.finished = true,
+    }
+  }
+
+  /// Selects the failure at the given offset from the current one.
+  fn select(&mut self, offset: isize) {
+    if self.failures.is_empty() {
+      return;
+    }
+    let index = self.selected.selected().unwrap_or_default().saturating_add_signed(offset).min(self.failures.len() - 1);
+    self.selected.select(Some(index));
+    self.scroll = 0;
+  }
+}
+
+/// Runs the interface until all tests are executed and the user quits.
+fn run(events: Receiver<TuiEvent>, expected_count: usize) {
+  enable_raw_mode().unwrap_or_else(|e| panic!("enabling raw mode failed with reason: {}", e));
+  let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout())).unwrap_or_else(|e| panic!("initializing terminal failed with reason: {}", e));
+  execute!(terminal.backend_mut(), EnterAlternateScreen).unwrap_or_else(|e| panic!("entering alternate screen failed with reason: {}", e));
+  let mut state = State {
+    expected_count,
+    ..Default::default()
+  };
+  loop {
+    match events.recv_timeout(REFRESH_INTERVAL) {
+      Ok(event) => {
+        state.update(event);
+        // apply all pending events before redrawing
+        while let Ok(event) = events.try_recv() {
+          state.update(event);
+        }
+      }
+      Err(RecvTimeoutError::Timeout) => {}
+      Err(RecvTimeoutError::Disconnected) => state.finished = true,
+    }
+    terminal
+      .draw(|frame| draw(frame, &mut state))
+      .unwrap_or_else(|e| panic!("drawing interface failed with reason: {}", e));
+    while event::poll(Duration::ZERO).unwrap_or(false) {
+      let Ok(Event::Key(key)) = event::read() else {
+        continue;
+      };
+      if key.kind != KeyEventKind::Press {
+        continue;
+      }
+      match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          // raw mode disables the signal, the run is stopped gracefully like after SIGINT
+          interrupt::interrupt();
+          restore(&mut terminal);
+          return;
+        }
+        KeyCode::Char('q') | KeyCode::Esc if state.finished => {
+          restore(&mut terminal);
+          return;
+        }
+        KeyCode::Up | KeyCode::Char('k') => state.select(-1),
+        KeyCode::Down | KeyCode::Char('j') => state.select(1),
+        KeyCode::Home => state.select(isize::MIN),
+        KeyCode::End => state.select(isize::MAX),
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(10),
+        KeyCode::PageDown => state.scroll = state.scroll.saturating_add(10),
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Leaves the alternate screen and disables raw mode.
+fn restore(terminal: &mut Terminal<CrosstermBackend<Stdout>>) {
+  let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+  let _ = disable_raw_mode();
+  let _ = terminal.show_cursor();
+}
+
+/// Restores the terminal without access to the interface, used when the runner panics.
+fn restore_terminal() {
+  let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+  let _ = disable_raw_mode();
+}
+
+/// Draws the progress, the list of failures and the details of the selected failure.
+fn draw(frame: &mut Frame, state: &mut State) {
+  let [progress_area, main_area, help_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+  let [failures_area, details_area] = Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(main_area);
+  let failure_count = state.failures.len();
+  let executed_count = state.success_count + failure_count;
+  // streamed test files are not counted in advance, so more tests than expected may be executed
+  let total_count = state.expected_count.max(executed_count);
+  let status = if state.finished { "finished".to_string() } else { state.file_path.clone() };
+  let gauge = Gauge::default()
+    .block(Block::default().borders(Borders::ALL).title(format!(
+      " Tests: {executed_count}/{total_count} | passed: {} | failed: {failure_count} ",
+      state.success_count
+    )))
+    .gauge_style(Style::default().fg(if failure_count > 0 { Color::Red } else { Color::Green }))
+    .ratio(if total_count > 0 { executed_count as f64 / total_count as f64 } else { 0.0 })
+    .label(status);
+  frame.render_widget(gauge, progress_area);
+  let items = state.failures.iter().map(|(name, _)| ListItem::new(name.as_str())).collect::<Vec<ListItem>>();
+  let list = List::new(items)
+    .block(Block::default().borders(Borders::ALL).title(" Failures "))
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+  frame.render_stateful_widget(list, failures_area, &mut state.selected);
+  let mut lines = vec![];
+  if let Some((name, remarks)) = state.selected.selected().and_then(|index| state.failures.get(index)) {
+    lines.push(Line::styled(name.clone(), Style::default().add_modifier(Modifier::BOLD)));
+    lines.push(Line::styled(remarks.clone(), Style::default().fg(Color::Yellow)));
+    if let Some(diff) = state.diffs.get(name) {
+      lines.push(Line::default());
+      for line in diff.lines() {
+        let color = match line.chars().next() {
+          Some('-') => Color::Red,
+          Some('+') => Color::Green,
+          Some('@') => Color::Cyan,
+          _ => Color::Reset,
+        };
+        lines.push(Line::styled(line.to_string(), Style::default().fg(color)));
+      }
+    }
+  }
+  let details = Paragraph::new(lines)
+    .block(Block::default().borders(Borders::ALL).title(" Expected / actual "))
+    .wrap(Wrap { trim: false })
+    .scroll((state.scroll, 0));
+  frame.render_widget(details, details_area);
+  let help = if state.finished {
+    " ↑/↓ select failure   PgUp/PgDn scroll details   q quit"
+  } else {
+    " ↑/↓ select failure   PgUp/PgDn scroll details   Ctrl-C abort"
+  };
+  frame.render_widget(Line::styled(help, Style::default().add_modifier(Modifier::DIM)), help_area);
+}
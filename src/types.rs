@@ -0,0 +1,149 @@
+/*
+ * DMNTK - Decision Model and Notation Toolkit
+ *
+ * MIT license
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ *
+ * Apache license, Version 2.0
+ *
+ * Copyright (c) 2015-2023 Dariusz Depta, Engos Software
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! # Types of values inferred from DMN models
+
+use crate::model::{canonical_type_name, Value, PREFIX_FEEL, PREFIX_XSD};
+use roxmltree::Node;
+use std::collections::{BTreeMap, HashMap};
+
+/// Maximum depth of nested item definitions, protects against cyclic definitions.
+const MAX_DEPTH: usize = 16;
+
+/// Type of the value inferred from variable type reference and item definitions.
+#[derive(Debug)]
+pub enum InferredType {
+  /// Simple value with XML Schema type name.
+  Simple(&'static str),
+  /// Complex value with types of components indexed by component name.
+  Components(BTreeMap<String, InferredType>),
+  /// List with type of items.
+  List(Box<InferredType>),
+}
+
+/// Maps FEEL type reference into XML Schema type name.
+pub fn xsd_type(type_ref: &str) -> Option<&'static str> {
+  let type_name = canonical_type_name(type_ref);
+  let local_name = match type_name.split_once(':') {
+    Some((PREFIX_FEEL | PREFIX_XSD, local_name)) => local_name,
+    Some(_) => return None,
+    None => &type_name,
+  };
+  match local_name {
+    "number" => Some("xsd:decimal"),
+    "string" => Some("xsd:string"),
+    "boolean" => Some("xsd:boolean"),
+    "date" => Some("xsd:date"),
+    "time" => Some("xsd:time"),
+    "date and time" | "dateTime" => Some("xsd:dateTime"),
+    "days and time duration" | "dayTimeDuration" | "years and months duration" | "yearMonthDuration" => Some("xsd:duration"),
+    _ => None,
+  }
+}
+
+/// Returns inferred types of input data and decision variables defined in the model, indexed by variable name.
+pub fn variable_types(definitions: &Node) -> HashMap<String, InferredType> {
+  let item_definitions = definitions
+    .children()
+    .filter(|n| n.tag_name().name() == "itemDefinition")
+    .filter_map(|n| n.attribute("name").map(|name| (name.to_string(), n)))
+    .collect::<HashMap<String, Node>>();
+  definitions
+    .children()
+    .filter(|n| matches!(n.tag_name().name(), "inputData" | "decision"))
+    .filter_map(|n| {
+      let variable = n.children().find(|child| child.tag_name().name() == "variable")?;
+      let name = variable.attribute("name").or(n.attribute("name"))?;
+      let inferred_type = resolve_type_ref(variable.attribute("typeRef")?, &item_definitions, 0)?;
+      Some((name.to_string(), inferred_type))
+    })
+    .collect()
+}
+
+/// Resolves the type reference into inferred type.
+fn resolve_type_ref(type_ref: &str, item_definitions: &HashMap<String, Node>, depth: usize) -> Option<InferredType> {
+  if let Some(xsd_type) = xsd_type(type_ref) {
+    return Some(InferredType::Simple(xsd_type));
+  }
+  let type_ref = type_ref.trim();
+  let item_definition = item_definitions.get(type_ref).or_else(|| item_definitions.get(type_ref.rsplit_once(':')?.1))?;
+  resolve_item_definition(item_definition, item_definitions, depth + 1)
+}
+
+/// Resolves the item definition (or item component) into inferred type.
+fn resolve_item_definition(node: &Node, item_definitions: &HashMap<String, Node>, depth: usize) -> Option<InferredType> {
+  if depth > MAX_DEPTH {
+    return None;
+  }
+  let components = node
+    .children()
+    .filter(|n| n.tag_name().name() == "itemComponent")
+    .filter_map(|n| Some((n.attribute("name")?.to_string(), resolve_item_definition(&n, item_definitions, depth + 1)?)))
+    .collect::<BTreeMap<String, InferredType>>();
+  let base = if components.is_empty() {
+    let type_ref = node
+      .children()
+      .find(|n| n.tag_name().name() == "typeRef")
+      .and_then(|n| n.text())
+      .or(node.attribute("typeRef"))?;
+    resolve_type_ref(type_ref, item_definitions, depth)?
+  } else {
+    InferredType::Components(components)
+  };
+  if node.attribute("isCollection").is_some_and(|collection| collection == "true") {
+    Some(InferredType::List(Box::new(base)))
+  } else {
+    Some(base)
+  }
+}
+
+/// Sets the type of untyped simple values, according to inferred type.
+pub fn infer(value: &mut Value, inferred_type: &InferredType) {
+  match (value, inferred_type) {
+    (Value::Simple(simple), InferredType::Simple(xsd_type)) if simple.typ.is_none() && !simple.nil => {
+      simple.typ = Some(xsd_type.to_string());
+    }
+    (Value::Components(components), InferredType::Components(component_types)) => {
+      for component in components {
+        if let Some((value, component_type)) = component.value.as_mut().zip(component.name.as_ref().and_then(|name| component_types.get(name))) {
+          infer(value, component_type);
+        }
+      }
+    }
+    (Value::List(list), InferredType::List(item_type)) => {
+      for item in &mut list.items {
+        infer(item, item_type);
+      }
+    }
+    _ => {}
+  }
+}